@@ -38,6 +38,10 @@ contract RozoIntents is
 
     uint256 private constant MAX_PROTOCOL_FEE_BPS = 30;
     uint256 private constant BPS_DENOMINATOR = 10_000;
+    uint256 private constant NOTIFY_PAYLOAD_V1 = 1;
+    uint256 private constant NOTIFY_PAYLOAD_V2 = 2;
+    uint256 private constant NOTIFY_PAYLOAD_V1_LEN = 224;
+    uint256 private constant NOTIFY_PAYLOAD_V2_LEN = 256;
 
     // ============ Storage ============
 
@@ -233,11 +237,13 @@ contract RozoIntents is
         IMessengerAdapter adapter = messengerAdapters[messengerId];
         if (address(adapter) == address(0)) revert InvalidMessenger();
 
-        // Build payload with relayer identity and address type flags
-        // Format: intentId, fillHash, repaymentAddress, relayer, amount, flags
+        // Build v2 payload with relayer identity and address type flags
+        // Format: version, sourceChainId, intentId, fillHash, repaymentAddress, relayer, amount, flags
         bytes32 relayerBytes32 = _addressToBytes32(msg.sender);
         bytes32 flags = repaymentIsAccount ? bytes32(uint256(1)) : bytes32(0);
         bytes memory payload = abi.encode(
+            NOTIFY_PAYLOAD_V2,
+            sourceChainId,
             intentId,
             fillHash,
             repaymentAddress,
@@ -295,9 +301,11 @@ contract RozoIntents is
         bytes memory payload = adapter.verifyMessage(sourceChainId, messageData);
 
         // Decode payload with relayer identity and address type flags
-        // Format: intentId, fillHash, repaymentAddress, relayer, amount, flags
+        // v1: version, intentId, fillHash, repaymentAddress, relayer, amount, flags
+        // v2: version, sourceChainId, then the v1 fields
+        bytes memory body = _notifyPayloadBody(payload);
         (bytes32 intentId, bytes32 fillHash, bytes32 repaymentAddress, bytes32 relayer, uint256 amountPaid, bytes32 flags) =
-            abi.decode(payload, (bytes32, bytes32, bytes32, bytes32, uint256, bytes32));
+            abi.decode(body, (bytes32, bytes32, bytes32, bytes32, uint256, bytes32));
 
         // Extract repaymentIsAccount from flags (byte 31 = 1 means account)
         bool repaymentIsAccount = uint256(flags) != 0;
@@ -305,6 +313,28 @@ contract RozoIntents is
         _completeFill(intentId, fillHash, repaymentAddress, repaymentIsAccount, relayer, amountPaid);
     }
 
+    /// @notice Strip the version header from a notify payload, checking the v2 source chain
+    function _notifyPayloadBody(bytes memory payload) internal view returns (bytes memory body) {
+        if (payload.length < 32) revert InvalidPayload();
+        uint256 version = abi.decode(payload, (uint256));
+
+        // The body is six static words after the header
+        bytes32[6] memory words;
+        if (version == NOTIFY_PAYLOAD_V1) {
+            if (payload.length != NOTIFY_PAYLOAD_V1_LEN) revert InvalidPayload();
+            (, words) = abi.decode(payload, (uint256, bytes32[6]));
+        } else if (version == NOTIFY_PAYLOAD_V2) {
+            if (payload.length != NOTIFY_PAYLOAD_V2_LEN) revert InvalidPayload();
+            uint256 payloadChainId;
+            (, payloadChainId, words) = abi.decode(payload, (uint256, uint256, bytes32[6]));
+            if (payloadChainId != block.chainid) revert WrongChain();
+        } else {
+            revert InvalidPayload();
+        }
+
+        body = abi.encodePacked(words);
+    }
+
     // ============ Admin Functions ============
 
     /// @inheritdoc IRozoIntentsAdmin
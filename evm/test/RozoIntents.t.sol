@@ -319,8 +319,10 @@ contract RozoIntentsTest is Test {
         bytes32 relayerBytes32 = _addressToBytes32(RELAYER);
         bytes32 flags = bytes32(0); // repaymentIsAccount = false
 
-        // Payload format: intentId, fillHash, repaymentAddress, relayer, amount, flags
-        bytes memory payload = abi.encode(intentId, fillHash, repaymentAddress, relayerBytes32, DESTINATION_AMOUNT, flags);
+        // Payload format: version, sourceChainId, intentId, fillHash, repaymentAddress, relayer, amount, flags
+        bytes memory payload = abi.encode(
+            uint256(2), block.chainid, intentId, fillHash, repaymentAddress, relayerBytes32, DESTINATION_AMOUNT, flags
+        );
 
         uint256 relayerBefore = token.balanceOf(RELAYER);
 
@@ -342,8 +344,10 @@ contract RozoIntentsTest is Test {
         bytes32 relayerBytes32 = _addressToBytes32(RELAYER);
         bytes32 flags = bytes32(0); // repaymentIsAccount = false
 
-        // Payload format: intentId, fillHash, repaymentAddress, relayer, amount, flags
-        bytes memory payload = abi.encode(intentId, wrongFillHash, repaymentAddress, relayerBytes32, DESTINATION_AMOUNT, flags);
+        // Payload format: version, sourceChainId, intentId, fillHash, repaymentAddress, relayer, amount, flags
+        bytes memory payload = abi.encode(
+            uint256(2), block.chainid, intentId, wrongFillHash, repaymentAddress, relayerBytes32, DESTINATION_AMOUNT, flags
+        );
 
         rozoAdapter.simulateNotify(address(intents), DEST_CHAIN_ID, payload);
 
@@ -351,6 +355,49 @@ contract RozoIntentsTest is Test {
         assertEq(uint256(result.status), uint256(IntentStatus.FAILED));
     }
 
+    function testNotifyAcceptsV1Payload() public {
+        bytes32 intentId = _createIntent();
+        bytes32 fillHash = keccak256(abi.encode(_storedIntentData(intentId)));
+        bytes32 relayerBytes32 = _addressToBytes32(RELAYER);
+
+        // Payload format: version, intentId, fillHash, repaymentAddress, relayer, amount, flags
+        bytes memory payload =
+            abi.encode(uint256(1), intentId, fillHash, relayerBytes32, relayerBytes32, DESTINATION_AMOUNT, bytes32(0));
+
+        rozoAdapter.simulateNotify(address(intents), DEST_CHAIN_ID, payload);
+
+        Intent memory result = intents.intents(intentId);
+        assertEq(uint256(result.status), uint256(IntentStatus.FILLED));
+    }
+
+    function testNotifyRejectsV2PayloadForOtherSourceChain() public {
+        bytes32 intentId = _createIntent();
+        bytes32 fillHash = keccak256(abi.encode(_storedIntentData(intentId)));
+        bytes32 relayerBytes32 = _addressToBytes32(RELAYER);
+
+        bytes memory payload = abi.encode(
+            uint256(2), block.chainid + 1, intentId, fillHash, relayerBytes32, relayerBytes32, DESTINATION_AMOUNT, bytes32(0)
+        );
+
+        vm.expectRevert("notify failed");
+        rozoAdapter.simulateNotify(address(intents), DEST_CHAIN_ID, payload);
+
+        Intent memory result = intents.intents(intentId);
+        assertEq(uint256(result.status), uint256(IntentStatus.PENDING));
+    }
+
+    function testNotifyRejectsUnversionedPayload() public {
+        bytes32 intentId = _createIntent();
+        bytes32 relayerBytes32 = _addressToBytes32(RELAYER);
+
+        // Legacy untagged layout: intentId, fillHash, repaymentAddress, relayer, amount, flags
+        bytes memory payload =
+            abi.encode(intentId, bytes32(0), relayerBytes32, relayerBytes32, DESTINATION_AMOUNT, bytes32(0));
+
+        vm.expectRevert("notify failed");
+        rozoAdapter.simulateNotify(address(intents), DEST_CHAIN_ID, payload);
+    }
+
     function testNotifyRevertsIfNotMessenger() public {
         bytes32 flags = bytes32(0);
         bytes memory payload = abi.encode(bytes32(0), bytes32(0), bytes32(0), bytes32(0), uint256(0), flags);
@@ -438,8 +485,10 @@ contract RozoIntentsTest is Test {
         bytes32 fillHash = keccak256(abi.encode(intentData));
         bytes32 relayerBytes32 = _addressToBytes32(RELAYER);
         bytes32 flags = bytes32(0); // repaymentIsAccount = false
-        // Payload format: intentId, fillHash, repaymentAddress, relayer, amount, flags
-        bytes memory payload = abi.encode(intentId, fillHash, relayerBytes32, relayerBytes32, DESTINATION_AMOUNT, flags);
+        // Payload format: version, sourceChainId, intentId, fillHash, repaymentAddress, relayer, amount, flags
+        bytes memory payload = abi.encode(
+            uint256(2), block.chainid, intentId, fillHash, relayerBytes32, relayerBytes32, DESTINATION_AMOUNT, flags
+        );
         rozoAdapter.simulateNotify(address(intents), DEST_CHAIN_ID, payload);
 
         uint256 recipientBefore = token.balanceOf(FEE_RECIPIENT);
@@ -538,6 +587,26 @@ contract RozoIntentsTest is Test {
         });
     }
 
+    function _storedIntentData(bytes32 intentId) internal view returns (IntentData memory) {
+        Intent memory stored = intents.intents(intentId);
+        return IntentData({
+            intentId: intentId,
+            sender: _addressToBytes32(stored.sender),
+            refundAddress: _addressToBytes32(stored.refundAddress),
+            sourceToken: _addressToBytes32(stored.sourceToken),
+            sourceAmount: stored.sourceAmount,
+            sourceChainId: block.chainid,
+            destinationChainId: stored.destinationChainId,
+            destinationToken: stored.destinationToken,
+            receiver: stored.receiver,
+            destinationAmount: stored.destinationAmount,
+            deadline: stored.deadline,
+            createdAt: stored.createdAt,
+            relayer: stored.relayer,
+            receiverIsAccount: stored.receiverIsAccount
+        });
+    }
+
     function _fee() internal view returns (uint256) {
         return (SOURCE_AMOUNT * intents.protocolFee()) / 10_000;
     }
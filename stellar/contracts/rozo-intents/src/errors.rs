@@ -20,20 +20,31 @@ pub enum Error {
     InvalidStatus = 12,
     IntentExpired = 13,
     IntentNotExpired = 14,
+    IntentFrozen = 16,
 
     // Validation errors
     InvalidAmount = 20,
     InvalidDeadline = 21,
     InvalidFee = 22,
     InvalidPayload = 23,
+    InvalidAddress = 24,
+    BatchTooLarge = 26,
+    AssetMismatch = 27,
+    PayloadTooShort = 28,
+    PayloadTooLong = 29,
 
     // Cross-chain errors
     UntrustedSource = 30,
     ChainNotFound = 31,
     WrongChain = 32,
+    ChainAlreadyMapped = 34,
+    NoMessengerAvailable = 36,
+    AllMessengersFailed = 37,
+    RetryLimitReached = 38,
 
     // Transfer errors
     TransferFailed = 40,
+    Reentrancy = 41,
 
     // Relayer errors
     NotAssignedRelayer = 50,
@@ -41,4 +52,53 @@ pub enum Error {
     AlreadyFilled = 52,
     FillHashMismatch = 53,
     InvalidMessenger = 54,
+    NoLiveRelayer = 55,
+    IntentClaimed = 57,
+    ClaimCooldown = 58,
+    ClaimNotFound = 59,
+
+    // Pause errors
+    Paused = 60,
+    NotPaused = 61,
+    NotArmed = 62,
+
+    // Settlement errors
+    SettlementNotFound = 70,
+    SettlementLocked = 71,
+
+    // Conversion errors
+    PrecisionLoss = 80,
+
+    // Auction errors
+    AuctionClosed = 90,
+    AuctionNotClosed = 91,
+    NoBids = 92,
+}
+
+/// Rejections from owner-configured policy (caps, allow-lists, blocklists)
+/// Split from Error to stay within the contracterror variant limit; codes share
+/// one space with Error and are never reused across the two enums.
+#[contracterror]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PolicyError {
+    // Intent limits
+    TooManyPendingIntents = 15,
+    DeadlineTooSoon = 17,
+    AmountTooLarge = 18,
+    TokenNotAllowed = 19,
+
+    // Compliance
+    ReceiverBlocked = 25,
+
+    // Messenger and token restrictions
+    MessengerDisabled = 33,
+    DestinationTokenNotAllowed = 35,
+
+    // Relayer limits
+    VolumeCapExceeded = 56,
+    RateLimited = 100,
+
+    // Bond errors
+    InsufficientBond = 110,
 }
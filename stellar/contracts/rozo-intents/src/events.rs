@@ -1,5 +1,15 @@
-use crate::types::{IntentStatus, RelayerType};
-use soroban_sdk::{Address, BytesN, Env, String, Symbol};
+use crate::storage::get_chain_name;
+use crate::types::{FeeTier, IntentStatus, RelayerType};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, IntoVal, String, Symbol, Val};
+
+/// Destination chain topic, so relayers serving one corridor can subscribe narrowly:
+/// the configured chain name, or the numeric chain ID when the chain is unnamed.
+fn chain_topic(env: &Env, chain_id: u64) -> Val {
+    match get_chain_name(env, chain_id) {
+        Ok(name) => name.into_val(env),
+        Err(_) => chain_id.into_val(env),
+    }
+}
 
 pub fn emit_intent_created(
     env: &Env,
@@ -12,9 +22,10 @@ pub fn emit_intent_created(
     destination_amount: i128,
     deadline: u64,
     relayer: BytesN<32>,
+    referrer: Option<Address>,
 ) {
     env.events().publish(
-        (Symbol::new(env, "intent_created"), intent_id.clone()),
+        (Symbol::new(env, "intent_created"), intent_id.clone(), chain_topic(env, destination_chain_id)),
         (
             sender,
             source_token,
@@ -24,6 +35,7 @@ pub fn emit_intent_created(
             destination_amount,
             deadline,
             relayer,
+            referrer,
         ),
     );
 }
@@ -34,13 +46,22 @@ pub fn emit_intent_filled(
     relayer: BytesN<32>,
     repayment_address: BytesN<32>,
     amount_paid: i128,
+    rebate_amount: i128,
+    filled_at: u64,
 ) {
     env.events().publish(
         (Symbol::new(env, "intent_filled"), intent_id),
-        (relayer, repayment_address, amount_paid),
+        (relayer, repayment_address, amount_paid, rebate_amount, filled_at),
     );
 }
 
+/// `fee_amount` is net of referral, rebate and overpay reimbursement, plus the creation fee;
+/// negative when a reimbursement draws on fees accrued by earlier fills.
+pub fn emit_fee_accrued(env: &Env, token: Address, fee_amount: i128, intent_id: BytesN<32>) {
+    env.events()
+        .publish((Symbol::new(env, "fee_accrued"), token), (fee_amount, intent_id));
+}
+
 pub fn emit_intent_failed(
     env: &Env,
     intent_id: BytesN<32>,
@@ -53,6 +74,124 @@ pub fn emit_intent_failed(
     );
 }
 
+pub fn emit_settlement_scheduled(
+    env: &Env,
+    intent_id: BytesN<32>,
+    fill_hash: BytesN<32>,
+    amount: i128,
+    release_at: u64,
+) {
+    env.events().publish(
+        (Symbol::new(env, "settlement_scheduled"), intent_id),
+        (fill_hash, amount, release_at),
+    );
+}
+
+pub fn emit_settlement_released(
+    env: &Env,
+    intent_id: BytesN<32>,
+    fill_hash: BytesN<32>,
+    payout_address: Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (Symbol::new(env, "settlement_released"), intent_id),
+        (fill_hash, payout_address, amount),
+    );
+}
+
+pub fn emit_overpay_reimbursed(env: &Env, intent_id: BytesN<32>, amount: i128) {
+    env.events()
+        .publish((Symbol::new(env, "overpay_reimbursed"), intent_id), amount);
+}
+
+pub fn emit_intent_expired(env: &Env, intent_id: BytesN<32>, deadline: u64) {
+    env.events()
+        .publish((Symbol::new(env, "intent_expired"), intent_id), deadline);
+}
+
+pub fn emit_deadline_extended(env: &Env, intent_id: BytesN<32>, old_deadline: u64, new_deadline: u64) {
+    env.events().publish(
+        (Symbol::new(env, "deadline_extended"), intent_id),
+        (old_deadline, new_deadline),
+    );
+}
+
+pub fn emit_intent_claimed(env: &Env, intent_id: BytesN<32>, relayer: Address, expires_at: u64) {
+    env.events().publish(
+        (Symbol::new(env, "intent_claimed"), intent_id),
+        (relayer, expires_at),
+    );
+}
+
+pub fn emit_claim_lapsed(env: &Env, intent_id: BytesN<32>, relayer: Address, failures: u64, cooldown_until: u64) {
+    env.events().publish(
+        (Symbol::new(env, "claim_lapsed"), intent_id),
+        (relayer, failures, cooldown_until),
+    );
+}
+
+pub fn emit_bid_placed(env: &Env, intent_id: BytesN<32>, relayer: Address, destination_amount: i128) {
+    env.events().publish(
+        (Symbol::new(env, "bid_placed"), intent_id),
+        (relayer, destination_amount),
+    );
+}
+
+pub fn emit_auction_finalized(env: &Env, intent_id: BytesN<32>, relayer: Address, destination_amount: i128) {
+    env.events().publish(
+        (Symbol::new(env, "auction_finalized"), intent_id),
+        (relayer, destination_amount),
+    );
+}
+
+pub fn emit_auction_window_set(env: &Env, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "auction_window_set"),), window);
+}
+
+pub fn emit_claim_window_set(env: &Env, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "claim_window_set"),), window);
+}
+
+pub fn emit_payout_credited(env: &Env, intent_id: BytesN<32>, payee: Address, amount: i128) {
+    env.events()
+        .publish((Symbol::new(env, "payout_credited"), intent_id), (payee, amount));
+}
+
+pub fn emit_payout_claimed(env: &Env, payee: Address, token: Address, amount: i128) {
+    env.events()
+        .publish((Symbol::new(env, "payout_claimed"), payee), (token, amount));
+}
+
+pub fn emit_pull_payouts_set(env: &Env, enabled: bool) {
+    env.events()
+        .publish((Symbol::new(env, "pull_payouts_set"),), enabled);
+}
+
+pub fn emit_intent_cleaned(env: &Env, intent_id: BytesN<32>, refunded: bool) {
+    env.events()
+        .publish((Symbol::new(env, "intent_cleaned"), intent_id), refunded);
+}
+
+pub fn emit_unfillable_reported(env: &Env, intent_id: BytesN<32>, relayer: Address, reason: Symbol) {
+    env.events().publish(
+        (Symbol::new(env, "unfillable_reported"), intent_id),
+        (relayer, reason),
+    );
+}
+
+pub fn emit_referral_paid(env: &Env, intent_id: BytesN<32>, referrer: Address, amount: i128) {
+    env.events()
+        .publish((Symbol::new(env, "referral_paid"), intent_id), (referrer, amount));
+}
+
+pub fn emit_intent_cancelled(env: &Env, intent_id: BytesN<32>, sender: Address) {
+    env.events()
+        .publish((Symbol::new(env, "intent_cancelled"), intent_id), sender);
+}
+
 pub fn emit_intent_refunded(env: &Env, intent_id: BytesN<32>, refund_address: Address, amount: i128) {
     env.events().publish(
         (Symbol::new(env, "intent_refunded"), intent_id),
@@ -111,16 +250,197 @@ pub fn emit_intent_relayer_changed(
     );
 }
 
+pub fn emit_relayer_metadata_set(env: &Env, relayer: Address, endpoint: String, name: String) {
+    env.events()
+        .publish((Symbol::new(env, "relayer_metadata_set"), relayer), (endpoint, name));
+}
+
+pub fn emit_bond_deposited(env: &Env, relayer: Address, token: Address, amount: i128, balance: i128) {
+    env.events()
+        .publish((Symbol::new(env, "bond_deposited"), relayer), (token, amount, balance));
+}
+
+pub fn emit_bond_withdrawn(env: &Env, relayer: Address, token: Address, amount: i128, balance: i128) {
+    env.events()
+        .publish((Symbol::new(env, "bond_withdrawn"), relayer), (token, amount, balance));
+}
+
+pub fn emit_bond_slashed(env: &Env, relayer: Address, token: Address, amount: i128, balance: i128) {
+    env.events()
+        .publish((Symbol::new(env, "bond_slashed"), relayer), (token, amount, balance));
+}
+
+pub fn emit_min_bond_set(env: &Env, token: Address, amount: i128) {
+    env.events()
+        .publish((Symbol::new(env, "min_bond_set"),), (token, amount));
+}
+
+pub fn emit_relayer_heartbeat(env: &Env, relayer: Address, timestamp: u64) {
+    env.events()
+        .publish((Symbol::new(env, "relayer_heartbeat"),), (relayer, timestamp));
+}
+
+pub fn emit_liveness_window_set(env: &Env, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "liveness_window_set"),), window);
+}
+
+pub fn emit_fill_index_limit_set(env: &Env, limit: u32) {
+    env.events()
+        .publish((Symbol::new(env, "fill_index_limit_set"),), limit);
+}
+
+pub fn emit_settlement_delay_set(env: &Env, delay: u64) {
+    env.events()
+        .publish((Symbol::new(env, "settlement_delay_set"),), delay);
+}
+
+pub fn emit_max_pending_intents_set(env: &Env, max: u32) {
+    env.events()
+        .publish((Symbol::new(env, "max_pending_intents_set"),), max);
+}
+
+pub fn emit_intent_migrated(env: &Env, intent_id: BytesN<32>, from_version: u32, to_version: u32) {
+    env.events().publish(
+        (Symbol::new(env, "intent_migrated"), intent_id),
+        (from_version, to_version),
+    );
+}
+
+pub fn emit_receiver_blacklisted(env: &Env, receiver: BytesN<32>, admin: Address) {
+    env.events()
+        .publish((Symbol::new(env, "receiver_blacklisted"), receiver), admin);
+}
+
+pub fn emit_receiver_unblacklisted(env: &Env, receiver: BytesN<32>, admin: Address) {
+    env.events()
+        .publish((Symbol::new(env, "receiver_unblacklisted"), receiver), admin);
+}
+
+pub fn emit_intent_frozen(env: &Env, intent_id: BytesN<32>, admin: Address) {
+    env.events()
+        .publish((Symbol::new(env, "intent_frozen"), intent_id), admin);
+}
+
+pub fn emit_intent_unfrozen(env: &Env, intent_id: BytesN<32>, admin: Address) {
+    env.events()
+        .publish((Symbol::new(env, "intent_unfrozen"), intent_id), admin);
+}
+
+pub fn emit_overpay_reimburse_set(env: &Env, enabled: bool) {
+    env.events()
+        .publish((Symbol::new(env, "overpay_reimburse_set"),), enabled);
+}
+
+pub fn emit_asset_equivalence_set(env: &Env, local_token: Address, chain_id: u64, remote_token: BytesN<32>) {
+    env.events().publish(
+        (Symbol::new(env, "asset_equivalence_set"), local_token),
+        (chain_id, remote_token),
+    );
+}
+
+pub fn emit_dest_token_allowed_set(env: &Env, chain_id: u64, token: BytesN<32>, allowed: bool) {
+    env.events().publish(
+        (Symbol::new(env, "dest_token_allowed_set"), chain_id),
+        (token, allowed),
+    );
+}
+
+pub fn emit_source_token_allowed_set(env: &Env, token: Address, allowed: bool) {
+    env.events()
+        .publish((Symbol::new(env, "source_token_allowed_set"), token), allowed);
+}
+
+pub fn emit_restrict_dest_tokens_set(env: &Env, restrict: bool) {
+    env.events()
+        .publish((Symbol::new(env, "restrict_dest_tokens_set"),), restrict);
+}
+
+pub fn emit_strict_asset_match_set(env: &Env, strict: bool) {
+    env.events()
+        .publish((Symbol::new(env, "strict_asset_match_set"),), strict);
+}
+
+pub fn emit_relayer_volume_cap_set(env: &Env, relayer: Address, cap: i128, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "relayer_volume_cap_set"),), (relayer, cap, window));
+}
+
+pub fn emit_relayer_rate_limit_set(env: &Env, max_fills: u32, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "relayer_rate_limit_set"),), (max_fills, window));
+}
+
+pub fn emit_circuit_breaker_set(env: &Env, max_fills: u32, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "circuit_breaker_set"),), (max_fills, window));
+}
+
+pub fn emit_auto_paused(env: &Env, fills: u32, window: u64) {
+    env.events()
+        .publish((Symbol::new(env, "auto_paused"),), (fills, window));
+}
+
+pub fn emit_circuit_breaker_reset(env: &Env, admin: Address) {
+    env.events()
+        .publish((Symbol::new(env, "circuit_breaker_reset"),), admin);
+}
+
+pub fn emit_referral_share_set(env: &Env, share_bps: u32) {
+    env.events()
+        .publish((Symbol::new(env, "referral_share_set"),), share_bps);
+}
+
 pub fn emit_protocol_fee_set(env: &Env, fee_bps: u32) {
     env.events()
         .publish((Symbol::new(env, "protocol_fee_set"),), fee_bps);
 }
 
+pub fn emit_owner_proposed(env: &Env, owner: Address, pending_owner: Address) {
+    env.events()
+        .publish((Symbol::new(env, "owner_proposed"),), (owner, pending_owner));
+}
+
+pub fn emit_owner_transferred(env: &Env, old_owner: Address, new_owner: Address) {
+    env.events()
+        .publish((Symbol::new(env, "owner_transferred"),), (old_owner, new_owner));
+}
+
+pub fn emit_min_intent_amount_set(env: &Env, token: Address, min: i128) {
+    env.events()
+        .publish((Symbol::new(env, "min_intent_amount_set"), token), min);
+}
+
+pub fn emit_max_intent_amount_set(env: &Env, max: i128) {
+    env.events()
+        .publish((Symbol::new(env, "max_intent_amount_set"),), max);
+}
+
+pub fn emit_min_duration_set(env: &Env, seconds: u64) {
+    env.events()
+        .publish((Symbol::new(env, "min_duration_set"),), seconds);
+}
+
+pub fn emit_relayer_rebate_set(env: &Env, rebate_bps: u32) {
+    env.events()
+        .publish((Symbol::new(env, "relayer_rebate_set"),), rebate_bps);
+}
+
+pub fn emit_refund_fee_set(env: &Env, fee_bps: u32) {
+    env.events()
+        .publish((Symbol::new(env, "refund_fee_set"),), fee_bps);
+}
+
 pub fn emit_fee_recipient_set(env: &Env, recipient: Address) {
     env.events()
         .publish((Symbol::new(env, "fee_recipient_set"),), recipient);
 }
 
+pub fn emit_token_fee_recipient_set(env: &Env, token: Address, recipient: Address) {
+    env.events()
+        .publish((Symbol::new(env, "token_fee_recipient_set"), token), recipient);
+}
+
 pub fn emit_relayer_added(env: &Env, relayer: Address, relayer_type: RelayerType) {
     env.events()
         .publish((Symbol::new(env, "relayer_added"),), (relayer, relayer_type));
@@ -136,6 +456,49 @@ pub fn emit_messenger_adapter_set(env: &Env, messenger_id: u32, adapter: Address
         .publish((Symbol::new(env, "messenger_adapter_set"),), (messenger_id, adapter));
 }
 
+pub fn emit_messenger_adapter_replaced(
+    env: &Env,
+    messenger_id: u32,
+    old_adapter: Option<Address>,
+    new_adapter: Address,
+    drain: bool,
+) {
+    env.events().publish(
+        (Symbol::new(env, "messenger_adapter_replaced"), messenger_id),
+        (old_adapter, new_adapter, drain),
+    );
+}
+
+pub fn emit_messenger_enabled(env: &Env, messenger_id: u32) {
+    env.events()
+        .publish((Symbol::new(env, "messenger_enabled"), messenger_id), ());
+}
+
+pub fn emit_retry_limits_set(env: &Env, global_max: u32, per_fill_max: u32) {
+    env.events()
+        .publish((Symbol::new(env, "retry_limits_set"),), (global_max, per_fill_max));
+}
+
+pub fn emit_messenger_fallbacks_set(env: &Env, chain_id: u64, messenger_ids: soroban_sdk::Vec<u32>) {
+    env.events()
+        .publish((Symbol::new(env, "messenger_fallbacks_set"), chain_id), messenger_ids);
+}
+
+pub fn emit_adapter_chains_set(env: &Env, messenger_id: u32, chains: soroban_sdk::Vec<u64>) {
+    env.events()
+        .publish((Symbol::new(env, "adapter_chains_set"), messenger_id), chains);
+}
+
+pub fn emit_fee_tiers_set(env: &Env, tiers: soroban_sdk::Vec<FeeTier>) {
+    env.events()
+        .publish((Symbol::new(env, "fee_tiers_set"),), tiers);
+}
+
+pub fn emit_creation_fee_set(env: &Env, fee: i128) {
+    env.events()
+        .publish((Symbol::new(env, "creation_fee_set"),), fee);
+}
+
 pub fn emit_rozo_relayer_set(env: &Env, relayer: Address) {
     env.events()
         .publish((Symbol::new(env, "rozo_relayer_set"),), relayer);
@@ -157,3 +520,38 @@ pub fn emit_fees_withdrawn(env: &Env, token: Address, recipient: Address, amount
     env.events()
         .publish((Symbol::new(env, "fees_withdrawn"),), (token, recipient, amount));
 }
+
+pub fn emit_emergency_evacuated(env: &Env, token: Address, safe_address: Address, amount: i128, intents: u32) {
+    env.events().publish(
+        (Symbol::new(env, "emergency_evacuated"), token),
+        (safe_address, amount, intents),
+    );
+}
+
+pub fn emit_contract_upgraded(env: &Env, new_wasm_hash: BytesN<32>, version: u32) {
+    env.events()
+        .publish((Symbol::new(env, "contract_upgraded"),), (new_wasm_hash, version));
+}
+
+pub fn emit_emergency_armed(env: &Env, admin: Address, armed_until: u64) {
+    env.events()
+        .publish((Symbol::new(env, "emergency_armed"),), (admin, armed_until));
+}
+
+pub fn emit_paused(env: &Env, admin: Address, reason: String) {
+    env.events()
+        .publish((symbol_short!("paused"),), (admin, reason));
+}
+
+pub fn emit_unpaused(env: &Env, admin: Address) {
+    env.events()
+        .publish((symbol_short!("unpaused"),), admin);
+}
+
+/// Unified audit trail: emitted by every owner-only function alongside its specific event
+pub fn emit_admin_action<C: IntoVal<Env, Val>>(env: &Env, action: &str, admin: Address, context: C) {
+    env.events().publish(
+        (Symbol::new(env, "admin_action"), Symbol::new(env, action)),
+        (admin, context.into_val(env)),
+    );
+}
@@ -1,13 +1,18 @@
 #![no_std]
+// Entry points mirror the EVM ABI, several of which take more than seven arguments
+#![allow(clippy::too_many_arguments)]
 
 mod errors;
 mod events;
 mod storage;
 mod types;
 
-use errors::Error;
+use errors::{Error, PolicyError};
 use events::*;
-use soroban_sdk::{contract, contractimpl, token, Address, Bytes, BytesN, Env, IntoVal, String};
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, token, Address, Bytes, BytesN, Env, IntoVal, InvokeError, String,
+    Symbol, TryFromVal, Val, Vec,
+};
 use soroban_sdk::xdr::{FromXdr, ToXdr};
 use storage::*;
 use types::*;
@@ -15,6 +20,9 @@ use types::*;
 // Zero bytes32 constant for comparisons
 const ZERO_BYTES32: [u8; 32] = [0u8; 32];
 
+// ScValType::Address discriminant, the first XDR word of a serialized Address
+const SCV_ADDRESS_TAG: u8 = 18;
+
 /// RozoIntents Soroban Contract
 /// Intent-based cross-chain payments. Base <-> Stellar (bidirectional).
 /// Supports multiple messengers (Rozo, Axelar) via adapter pattern.
@@ -24,11 +32,13 @@ pub struct RozoIntentsContract;
 #[contractimpl]
 impl RozoIntentsContract {
     /// Initialize the contract
+    /// @param hash_algo Fill hash algorithm; must match the counterpart chains
     pub fn initialize(
         env: Env,
         owner: Address,
         fee_recipient: Address,
         chain_id: u64,
+        hash_algo: HashAlgo,
     ) -> Result<(), Error> {
         if has_owner(&env) {
             return Err(Error::AlreadyInitialized);
@@ -37,6 +47,7 @@ impl RozoIntentsContract {
         set_owner(&env, &owner);
         set_fee_recipient(&env, &fee_recipient);
         set_chain_id(&env, chain_id);
+        set_hash_algo(&env, &hash_algo);
 
         Ok(())
     }
@@ -51,68 +62,101 @@ impl RozoIntentsContract {
         params: CreateIntentParams,
     ) -> Result<(), Error> {
         sender.require_auth();
+        require_not_paused(&env)?;
 
-        // Validate
-        if params.source_amount <= 0 || params.destination_amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        if params.deadline <= env.ledger().timestamp() {
-            return Err(Error::InvalidDeadline);
-        }
+        validate_intent_params(&env, &params)?;
         if has_intent(&env, &params.intent_id) {
             return Err(Error::IntentAlreadyExists);
         }
+        require_pending_capacity(&env);
 
-        // Transfer tokens from sender to contract
-        let token_client = token::Client::new(&env, &params.source_token);
-        token_client.transfer(&sender, &env.current_contract_address(), &params.source_amount);
-
-        // Store intent
-        let intent = Intent {
-            intent_id: params.intent_id.clone(),
-            sender: sender.clone(),
-            refund_address: params.refund_address.clone(),
-            source_token: params.source_token.clone(),
-            source_amount: params.source_amount,
-            destination_chain_id: params.destination_chain_id,
-            destination_token: params.destination_token.clone(),
-            receiver: params.receiver.clone(),
-            receiver_is_account: params.receiver_is_account,
-            destination_amount: params.destination_amount,
-            deadline: params.deadline,
-            created_at: env.ledger().timestamp(),
-            status: IntentStatus::Pending,
-            relayer: params.relayer.clone(),
-        };
-        set_intent(&env, &params.intent_id, &intent);
+        open_intent(&env, &sender, params)
+    }
 
-        emit_intent_created(
-            &env,
-            params.intent_id,
-            sender,
-            params.source_token,
-            params.source_amount,
-            params.destination_chain_id,
-            params.receiver,
-            params.destination_amount,
-            params.deadline,
-            params.relayer,
-        );
+    /// Create several intents in one call
+    /// The whole batch is validated before any funds move: a duplicate ID,
+    /// inside the batch or already stored, rejects it with IntentAlreadyExists.
+    pub fn create_intents(
+        env: Env,
+        sender: Address,
+        params: Vec<CreateIntentParams>,
+    ) -> Result<(), Error> {
+        sender.require_auth();
+        require_not_paused(&env)?;
+
+        if params.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut seen: Vec<BytesN<32>> = Vec::new(&env);
+        for p in params.iter() {
+            validate_intent_params(&env, &p)?;
+            if seen.contains(&p.intent_id) || has_intent(&env, &p.intent_id) {
+                return Err(Error::IntentAlreadyExists);
+            }
+            seen.push_back(p.intent_id.clone());
+        }
+
+        let max = get_max_pending_intents(&env);
+        if max > 0 && get_pending_intent_count(&env) + params.len() > max {
+            panic_with_error!(&env, PolicyError::TooManyPendingIntents);
+        }
+
+        for p in params.iter() {
+            open_intent(&env, &sender, p)?;
+        }
 
         Ok(())
     }
 
-    /// Refund an expired intent
+    /// Create a new intent, treating an identical resubmission as a no-op
+    /// Lets relayers safely retry submissions after network errors.
+    /// Named to avoid clashing with the generated `try_create_intent` client method.
+    /// @return true if the intent was created, false if an identical intent already exists
+    pub fn create_intent_idempotent(
+        env: Env,
+        sender: Address,
+        params: CreateIntentParams,
+    ) -> Result<bool, Error> {
+        sender.require_auth();
+        require_not_paused(&env)?;
+
+        if has_intent(&env, &params.intent_id) {
+            // Compare against the original request: the stored intent changes after
+            // creation (net escrow, extended deadline, assigned relayer)
+            let matches = match get_intent_params_hash(&env, &params.intent_id) {
+                Some(hash) => hash == create_params_hash(&env, &sender, &params),
+                None => intent_matches_params(&get_intent(&env, &params.intent_id)?, &sender, &params),
+            };
+            if matches {
+                return Ok(false);
+            }
+            return Err(Error::IntentAlreadyExists);
+        }
+
+        validate_intent_params(&env, &params)?;
+        require_pending_capacity(&env);
+        open_intent(&env, &sender, params)?;
+
+        Ok(true)
+    }
+
+    /// Refund an expired intent, less the refund fee if one is set
     pub fn refund(env: Env, caller: Address, intent_id: BytesN<32>) -> Result<(), Error> {
         caller.require_auth();
+        acquire_lock(&env)?;
 
         let mut intent = get_intent(&env, &intent_id)?;
 
-        // Validate status - only PENDING can be refunded
-        if intent.status != IntentStatus::Pending {
+        // Validate status - only PENDING or EXPIRED can be refunded
+        if !intent.status.is_open() {
             return Err(Error::InvalidStatus);
         }
 
+        if is_intent_frozen(&env, &intent_id) {
+            return Err(Error::IntentFrozen);
+        }
+
         // Check deadline
         if env.ledger().timestamp() < intent.deadline {
             return Err(Error::IntentNotExpired);
@@ -123,19 +167,316 @@ impl RozoIntentsContract {
             return Err(Error::NotAuthorized);
         }
 
-        // Update status
-        intent.status = IntentStatus::Refunded;
+        refund_intent(&env, &intent_id, &mut intent, get_refund_fee_bps(&env));
+        release_lock(&env);
+
+        Ok(())
+    }
+
+    /// Cancel a pending intent before its deadline and refund the sender
+    /// Only the source chain is checked: a fill already in flight on the
+    /// destination still pays the receiver, and its notify will then fail here.
+    /// The refund fee applies as for refund.
+    pub fn cancel_intent(env: Env, sender: Address, intent_id: BytesN<32>) -> Result<(), Error> {
+        sender.require_auth();
+        acquire_lock(&env)?;
+
+        let mut intent = get_intent(&env, &intent_id)?;
+        if intent.sender != sender {
+            return Err(Error::NotAuthorized);
+        }
+        if intent.status != IntentStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+        if is_intent_frozen(&env, &intent_id) {
+            return Err(Error::IntentFrozen);
+        }
+
+        // A recorded fill means a relayer already paid out; leave it to notify
+        let fill_hash = compute_fill_hash(&env, &intent_data_from_intent(&env, &intent));
+        if has_fill_record(&env, &fill_hash) {
+            return Err(Error::AlreadyFilled);
+        }
+
+        refund_intent(&env, &intent_id, &mut intent, get_refund_fee_bps(&env));
+        release_lock(&env);
+        emit_intent_cancelled(&env, intent_id, sender);
+
+        Ok(())
+    }
+
+    /// Refund every open intent of the sender that has not been filled
+    /// Works before or after the deadline since it's the sender's own escrow.
+    /// The refund fee applies as for refund.
+    /// Processes at most MAX_BATCH_SIZE intents per call; call again to continue.
+    /// @return IDs of the refunded intents
+    pub fn cancel_all(env: Env, sender: Address) -> Result<Vec<BytesN<32>>, Error> {
+        sender.require_auth();
+        acquire_lock(&env)?;
+
+        let mut refunded = Vec::new(&env);
+        for intent_id in get_sender_intents(&env, &sender).iter() {
+            if refunded.len() >= MAX_BATCH_SIZE {
+                break;
+            }
+
+            // Entries removed by cleanup_failed are skipped
+            let mut intent = match get_intent(&env, &intent_id) {
+                Ok(intent) => intent,
+                Err(_) => continue,
+            };
+            if !intent.status.is_open() || is_intent_frozen(&env, &intent_id) {
+                continue;
+            }
+
+            // A recorded fill means a relayer already paid out; leave it to notify
+            let fill_hash = compute_fill_hash(&env, &intent_data_from_intent(&env, &intent));
+            if has_fill_record(&env, &fill_hash) {
+                continue;
+            }
+
+            refund_intent(&env, &intent_id, &mut intent, get_refund_fee_bps(&env));
+            refunded.push_back(intent_id);
+        }
+        release_lock(&env);
+
+        Ok(refunded)
+    }
+
+    /// Mark a pending intent past its deadline as Expired, without moving funds
+    /// Permissionless; lets off-chain systems see expiry before a refund happens.
+    pub fn touch_expired(env: Env, intent_id: BytesN<32>) -> Result<(), Error> {
+        let mut intent = get_intent(&env, &intent_id)?;
+
+        if intent.status != IntentStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+        if env.ledger().timestamp() < intent.deadline {
+            return Err(Error::IntentNotExpired);
+        }
+
+        intent.status = IntentStatus::Expired;
+        set_intent(&env, &intent_id, &intent);
+
+        emit_intent_expired(&env, intent_id, intent.deadline);
+
+        Ok(())
+    }
+
+    /// Push back the deadline of a pending intent before it expires
+    /// created_at is kept, so the Rozo fallback still measures from creation.
+    /// The deadline is part of the fill hash: relayers must fill with the new deadline.
+    pub fn extend_deadline(
+        env: Env,
+        caller: Address,
+        intent_id: BytesN<32>,
+        new_deadline: u64,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let mut intent = get_intent(&env, &intent_id)?;
+
+        if caller != intent.sender && caller != intent.refund_address {
+            return Err(Error::NotAuthorized);
+        }
+        if intent.status != IntentStatus::Pending {
+            return Err(Error::InvalidStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        if now >= intent.deadline {
+            return Err(Error::IntentExpired);
+        }
+        if new_deadline <= intent.deadline || new_deadline <= now {
+            return Err(Error::InvalidDeadline);
+        }
+
+        let old_deadline = intent.deadline;
+        intent.deadline = new_deadline;
         set_intent(&env, &intent_id, &intent);
+        remove_from_expiry_bucket(&env, old_deadline, &intent_id);
+        add_to_expiry_bucket(&env, new_deadline, &intent_id);
 
-        // Transfer tokens back
-        let token_client = token::Client::new(&env, &intent.source_token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &intent.refund_address,
-            &intent.source_amount,
-        );
+        emit_deadline_extended(&env, intent_id, old_deadline, new_deadline);
+
+        Ok(())
+    }
+
+    /// Bid to fill an unassigned intent during its auction window
+    /// A bid offers the receiver at least the intent's destination_amount;
+    /// a relayer's repeat bid replaces its earlier one.
+    pub fn bid(
+        env: Env,
+        relayer: Address,
+        intent_id: BytesN<32>,
+        destination_amount: i128,
+    ) -> Result<(), Error> {
+        relayer.require_auth();
+        require_not_paused(&env)?;
+
+        if get_relayer_type(&env, &relayer) == RelayerType::None {
+            return Err(Error::NotRelayer);
+        }
+
+        let intent = get_intent(&env, &intent_id)?;
+        let zero_bytes32 = BytesN::from_array(&env, &ZERO_BYTES32);
+        if intent.status != IntentStatus::Pending || intent.relayer != zero_bytes32 {
+            return Err(Error::InvalidStatus);
+        }
+
+        let window = get_auction_window(&env);
+        let now = env.ledger().timestamp();
+        if window == 0 || now >= intent.created_at + window || now >= intent.deadline {
+            return Err(Error::AuctionClosed);
+        }
+        if destination_amount < intent.destination_amount {
+            return Err(Error::InvalidAmount);
+        }
+
+        let mut bids = get_bids(&env, &intent_id);
+        let bid = AuctionBid { relayer: relayer.clone(), destination_amount };
+        match bids.iter().position(|b| b.relayer == relayer) {
+            Some(i) => bids.set(i as u32, bid),
+            None => {
+                if bids.len() >= MAX_BATCH_SIZE {
+                    return Err(Error::BatchTooLarge);
+                }
+                bids.push_back(bid);
+            }
+        }
+        set_bids(&env, &intent_id, &bids);
+
+        emit_bid_placed(&env, intent_id, relayer, destination_amount);
+
+        Ok(())
+    }
+
+    /// Close an intent's auction and assign it to the best bid
+    /// Permissionless once the window has passed. The highest destination_amount
+    /// wins (earliest bid on ties) and becomes the intent's destination_amount.
+    /// @return The winning relayer
+    pub fn finalize_auction(env: Env, intent_id: BytesN<32>) -> Result<Address, Error> {
+        let mut intent = get_intent(&env, &intent_id)?;
+        let zero_bytes32 = BytesN::from_array(&env, &ZERO_BYTES32);
+        if intent.status != IntentStatus::Pending || intent.relayer != zero_bytes32 {
+            return Err(Error::InvalidStatus);
+        }
+
+        if env.ledger().timestamp() < intent.created_at + get_auction_window(&env) {
+            return Err(Error::AuctionNotClosed);
+        }
+
+        let mut best: Option<AuctionBid> = None;
+        for bid in get_bids(&env, &intent_id).iter() {
+            let is_better = match &best {
+                Some(current) => bid.destination_amount > current.destination_amount,
+                None => true,
+            };
+            if is_better {
+                best = Some(bid);
+            }
+        }
+        let winner = best.ok_or(Error::NoBids)?;
+
+        intent.relayer = address_to_bytes32(&env, &winner.relayer);
+        intent.destination_amount = winner.destination_amount;
+        set_intent(&env, &intent_id, &intent);
+        remove_bids(&env, &intent_id);
+
+        emit_auction_finalized(&env, intent_id, winner.relayer.clone(), winner.destination_amount);
+
+        Ok(winner.relayer)
+    }
+
+    /// Lock an open intent for the calling relayer for the claim window
+    /// A claim that lapses while the intent is still open counts as a failure
+    /// and puts the relayer on a claim cooldown.
+    pub fn claim_intent(env: Env, relayer: Address, intent_id: BytesN<32>) -> Result<u64, Error> {
+        relayer.require_auth();
+        require_not_paused(&env)?;
+
+        if !is_relayer(&env, &relayer) {
+            return Err(Error::NotRelayer);
+        }
+
+        let intent = get_intent(&env, &intent_id)?;
+        if !intent.status.is_open() {
+            return Err(Error::InvalidStatus);
+        }
+        if intent.relayer != BytesN::from_array(&env, &ZERO_BYTES32) {
+            return Err(Error::NotAssignedRelayer);
+        }
+
+        let now = env.ledger().timestamp();
+        if now < get_claim_cooldown_until(&env, &address_to_bytes32(&env, &relayer)) {
+            return Err(Error::ClaimCooldown);
+        }
+
+        if let Some(claim) = get_intent_claim(&env, &intent_id) {
+            if now < claim.expires_at {
+                return Err(Error::IntentClaimed);
+            }
+            penalize_lapsed_claim(&env, &intent_id, claim);
+        }
+
+        let expires_at = now + get_claim_window(&env);
+        let claim = IntentClaim {
+            relayer: relayer.clone(),
+            expires_at,
+        };
+        set_intent_claim(&env, &intent_id, &claim);
+
+        emit_intent_claimed(&env, intent_id, relayer, expires_at);
+
+        Ok(expires_at)
+    }
+
+    /// Clear a lapsed claim on an intent that is still open, penalizing the claimer
+    /// Permissionless, so stale locks can't outlive their window.
+    pub fn expire_claim(env: Env, intent_id: BytesN<32>) -> Result<(), Error> {
+        let claim = get_intent_claim(&env, &intent_id).ok_or(Error::ClaimNotFound)?;
+        if env.ledger().timestamp() < claim.expires_at {
+            return Err(Error::IntentClaimed);
+        }
+
+        let intent = get_intent(&env, &intent_id)?;
+        if intent.status.is_open() {
+            penalize_lapsed_claim(&env, &intent_id, claim);
+        } else {
+            remove_intent_claim(&env, &intent_id);
+        }
+
+        Ok(())
+    }
+
+    /// Report that an open intent can't be filled as created (e.g. receiver_is_account is wrong)
+    /// Records the report and emits an event only; the sender can then cancel_all
+    /// and the admin can admin_refund. A later report replaces the earlier one.
+    pub fn report_unfillable(
+        env: Env,
+        relayer: Address,
+        intent_id: BytesN<32>,
+        reason: Symbol,
+    ) -> Result<(), Error> {
+        relayer.require_auth();
+
+        if !is_relayer(&env, &relayer) {
+            return Err(Error::NotRelayer);
+        }
+
+        let intent = get_intent(&env, &intent_id)?;
+        if !intent.status.is_open() {
+            return Err(Error::InvalidStatus);
+        }
 
-        emit_intent_refunded(&env, intent_id, intent.refund_address, intent.source_amount);
+        let report = UnfillableReport {
+            relayer: relayer.clone(),
+            reason: reason.clone(),
+            reported_at: env.ledger().timestamp(),
+        };
+        set_unfillable_report(&env, &intent_id, &report);
+
+        emit_unfillable_reported(&env, intent_id, relayer, reason);
 
         Ok(())
     }
@@ -143,16 +484,24 @@ impl RozoIntentsContract {
     // ============ Destination Functions ============
 
     /// Fill intent on destination and send notification to source chain
+    /// @param fill_amount Amount paid to the receiver; anything above destination_amount is recorded as overpay
     /// @param repayment_is_account Whether the repayment_address is a Stellar account (G...) or contract (C...)
+    /// @param delivery_proof Reference to the destination transfer (e.g. tx hash), kept for disputes
+    /// @param messenger_id Messenger to notify through; None uses the intent's preferred_messenger
     pub fn fill_and_notify(
         env: Env,
         relayer: Address,
         intent_data: IntentData,
+        fill_amount: i128,
         repayment_address: BytesN<32>,
         repayment_is_account: bool,
-        messenger_id: u32,
+        delivery_proof: BytesN<32>,
+        messenger_id: Option<u32>,
     ) -> Result<(), Error> {
         relayer.require_auth();
+        require_not_paused(&env)?;
+        // The receiver transfer and adapter call are external; no re-entry while they run
+        acquire_lock(&env)?;
 
         // Verify caller is authorized relayer
         let relayer_type = get_relayer_type(&env, &relayer);
@@ -160,6 +509,18 @@ impl RozoIntentsContract {
             return Err(Error::NotRelayer);
         }
 
+        // Relayers must keep the configured bond posted
+        if let Some((bond_token, min_bond)) = get_min_bond(&env) {
+            if get_bond(&env, &relayer, &bond_token) < min_bond {
+                panic_with_error!(&env, PolicyError::InsufficientBond);
+            }
+        }
+
+        // Sender may restrict fills to more trusted relayer types
+        if relayer_type.rank() < intent_data.min_relayer_type.rank() {
+            return Err(Error::NotAuthorizedRelayer);
+        }
+
         // Verify this is the correct destination chain
         let current_chain_id = get_chain_id(&env);
         if intent_data.destination_chain_id != current_chain_id {
@@ -171,8 +532,43 @@ impl RozoIntentsContract {
             return Err(Error::IntentExpired);
         }
 
-        // Verify relayer authorization
+        // Relayer may overpay the receiver, never underpay
+        if fill_amount < intent_data.destination_amount {
+            return Err(Error::InvalidAmount);
+        }
+
+        // Volume cap pre-check against volume settled in the current window;
+        // the fill itself is counted once it settles in complete_fill
         let relayer_bytes32 = address_to_bytes32(&env, &relayer);
+        if let Some(cap) = get_volume_cap(&env, &relayer_bytes32) {
+            let settled = get_window_volume(&env, &relayer_bytes32, cap.window);
+            if settled + fill_amount > cap.cap {
+                panic_with_error!(&env, PolicyError::VolumeCapExceeded);
+            }
+        }
+
+        // Fill count cap per relayer; the counter rolls back with any later error
+        let rate_limit = get_relayer_rate_limit(&env);
+        if rate_limit.max_fills > 0 && record_relayer_fill(&env, &relayer, rate_limit.window) > rate_limit.max_fills {
+            panic_with_error!(&env, PolicyError::RateLimited);
+        }
+
+        if is_restrict_dest_tokens(&env)
+            && !is_dest_token_allowed(&env, current_chain_id, &intent_data.destination_token)
+        {
+            panic_with_error!(&env, PolicyError::DestinationTokenNotAllowed);
+        }
+
+        // Compliance: blocked receivers can never be filled
+        if is_receiver_blocked(&env, &intent_data.receiver) {
+            panic_with_error!(&env, PolicyError::ReceiverBlocked);
+        }
+
+        if is_intent_frozen(&env, &intent_data.intent_id) {
+            return Err(Error::IntentFrozen);
+        }
+
+        // Verify relayer authorization
         let zero_bytes32 = BytesN::from_array(&env, &ZERO_BYTES32);
 
         if intent_data.relayer != zero_bytes32 {
@@ -199,37 +595,49 @@ impl RozoIntentsContract {
             relayer: relayer.clone(),
             repayment_address: repayment_address.clone(),
             repayment_is_account,
+            overpay: fill_amount - intent_data.destination_amount,
+            delivery_proof,
         };
         set_fill_record(&env, &fill_hash, &record);
+        push_relayer_fill_hash(&env, &relayer, &fill_hash);
 
         // Transfer tokens to receiver
         // Use receiver_is_account flag from IntentData to correctly decode the address type
         let receiver_address = bytes32_to_address_typed(&env, &intent_data.receiver, intent_data.receiver_is_account);
+        if receiver_address == env.current_contract_address() {
+            return Err(Error::InvalidAddress);
+        }
         // Token addresses are always contracts
         let token_address = bytes32_to_address_typed(&env, &intent_data.destination_token, false);
 
         let token_client = token::Client::new(&env, &token_address);
-        token_client.transfer(&relayer, &receiver_address, &intent_data.destination_amount);
-
-        // Get messenger adapter and send notification
-        let adapter = get_messenger_adapter(&env, messenger_id);
-        if adapter.is_none() {
-            return Err(Error::InvalidMessenger);
+        token_client.transfer(&relayer, &receiver_address, &fill_amount);
+
+        // Pick the messenger; without a fallback route it must be usable as-is
+        let messenger_id = messenger_id.or(intent_data.preferred_messenger);
+        let fallbacks = get_messenger_fallbacks(&env, intent_data.source_chain_id);
+        if fallbacks.is_empty() {
+            let primary = messenger_id.ok_or(Error::InvalidMessenger)?;
+            if get_messenger_adapter(&env, primary).is_none() {
+                return Err(Error::InvalidMessenger);
+            }
+            if is_messenger_disabled(&env, primary) {
+                panic_with_error!(&env, PolicyError::MessengerDisabled);
+            }
         }
-        let adapter_address = adapter.unwrap();
 
         // Build payload for cross-chain notification
         // Format: intentId, fillHash, repaymentAddress, relayer (who performed fill), amount, flags
-        let relayer_bytes32 = address_to_bytes32(&env, &relayer);
-        let payload = encode_notify_payload(
+        let payload = build_notify_payload(
             &env,
+            intent_data.source_chain_id,
             &intent_data.intent_id,
             &fill_hash,
             &repayment_address,
             &relayer_bytes32,
-            intent_data.destination_amount,
+            fill_amount,
             repayment_is_account,
-        );
+        )?;
 
         // Get source chain info for cross-chain messaging
         let source_chain = get_chain_name(&env, intent_data.source_chain_id)?;
@@ -239,10 +647,14 @@ impl RozoIntentsContract {
         store_outbound_message(&env, &source_chain, &destination_address, &payload);
 
         // CRITICAL: Actually call the messenger adapter to send the cross-chain message
-        // The adapter contract handles the actual message delivery to the source chain
-        send_via_adapter(&env, &adapter_address, intent_data.source_chain_id, &payload);
+        // The adapter contract handles the actual message delivery to the source chain.
+        // A failing adapter falls through to the route's fallbacks instead of reverting the fill.
+        let sent_via = send_with_fallbacks(&env, messenger_id, &fallbacks, intent_data.source_chain_id, &payload)?;
 
-        emit_fill_and_notify_sent(&env, intent_data.intent_id, relayer, repayment_address, messenger_id);
+        emit_fill_and_notify_sent(&env, intent_data.intent_id, relayer, repayment_address, sent_via);
+
+        trip_circuit_breaker_if_needed(&env);
+        release_lock(&env);
 
         Ok(())
     }
@@ -256,20 +668,7 @@ impl RozoIntentsContract {
     ) -> Result<(), Error> {
         relayer.require_auth();
 
-        // Compute fillHash
-        let fill_hash = compute_fill_hash(&env, &intent_data);
-
-        // Must have been filled previously
-        let record = get_fill_record(&env, &fill_hash);
-        if record.is_none() {
-            return Err(Error::IntentNotFound);
-        }
-        let record = record.unwrap();
-
-        // Only original filler can retry
-        if record.relayer != relayer {
-            return Err(Error::NotAssignedRelayer);
-        }
+        let payload = build_retry_payload(&env, &relayer, &intent_data)?;
 
         // Get messenger adapter
         let adapter = get_messenger_adapter(&env, messenger_id);
@@ -277,18 +676,9 @@ impl RozoIntentsContract {
             return Err(Error::InvalidMessenger);
         }
         let adapter_address = adapter.unwrap();
-
-        // Build payload with relayer identity and address type flags
-        let relayer_bytes32 = address_to_bytes32(&env, &relayer);
-        let payload = encode_notify_payload(
-            &env,
-            &intent_data.intent_id,
-            &fill_hash,
-            &record.repayment_address,
-            &relayer_bytes32,
-            intent_data.destination_amount,
-            record.repayment_is_account,
-        );
+        if is_messenger_disabled(&env, messenger_id) {
+            panic_with_error!(&env, PolicyError::MessengerDisabled);
+        }
 
         let source_chain = get_chain_name(&env, intent_data.source_chain_id)?;
         let destination_address = get_trusted_contract(&env, &source_chain)?;
@@ -304,20 +694,131 @@ impl RozoIntentsContract {
         Ok(())
     }
 
-    // ============ Messenger Callback ============
+    /// Retry notification through each registered messenger until one accepts it
+    /// Messengers are tried in ascending ID order, skipping disabled ones.
+    /// @return The messenger ID that sent the notification
+    pub fn retry_notify_all(env: Env, relayer: Address, intent_data: IntentData) -> Result<u32, Error> {
+        relayer.require_auth();
 
-    /// Receive notification from messenger adapter
-    /// @dev Only registered messenger adapters can call this function
-    /// @param caller The address of the calling contract (must be registered adapter)
-    ///               The adapter MUST call `env.authorize_as_current_contract()` before calling
-    pub fn notify(
-        env: Env,
-        caller: Address,
-        messenger_id: u32,
-        _source_chain_id: u64,
-        message_data: Bytes,
-    ) -> Result<(), Error> {
-        // Verify messenger adapter is registered
+        let payload = build_retry_payload(&env, &relayer, &intent_data)?;
+
+        let source_chain = get_chain_name(&env, intent_data.source_chain_id)?;
+        let destination_address = get_trusted_contract(&env, &source_chain)?;
+
+        for messenger_id in get_messenger_ids(&env).iter() {
+            if is_messenger_disabled(&env, messenger_id) {
+                continue;
+            }
+            let adapter_address = match get_messenger_adapter(&env, messenger_id) {
+                Some(adapter) => adapter,
+                None => continue,
+            };
+            if try_send_via_adapter(&env, &adapter_address, intent_data.source_chain_id, &payload) {
+                store_outbound_message(&env, &source_chain, &destination_address, &payload);
+                emit_retry_notify_sent(&env, intent_data.intent_id, relayer, messenger_id);
+                return Ok(messenger_id);
+            }
+        }
+
+        Err(Error::NoMessengerAvailable)
+    }
+
+    /// Record relayer liveness
+    /// Live relayers are candidates for auto_assign.
+    pub fn heartbeat(env: Env, relayer: Address) -> Result<(), Error> {
+        relayer.require_auth();
+
+        if !is_relayer(&env, &relayer) {
+            return Err(Error::NotRelayer);
+        }
+
+        let relayer_bytes32 = address_to_bytes32(&env, &relayer);
+        let now = env.ledger().timestamp();
+        set_last_heartbeat(&env, &relayer_bytes32, now);
+        add_live_relayer(&env, &relayer_bytes32)?;
+
+        emit_relayer_heartbeat(&env, relayer, now);
+
+        Ok(())
+    }
+
+    /// Publish the calling relayer's endpoint and display name
+    /// Cleared when the relayer is removed.
+    pub fn set_relayer_metadata(env: Env, relayer: Address, endpoint: String, name: String) -> Result<(), Error> {
+        relayer.require_auth();
+
+        if !is_relayer(&env, &relayer) {
+            return Err(Error::NotRelayer);
+        }
+
+        set_relayer_metadata(&env, &relayer, &endpoint, &name);
+        emit_relayer_metadata_set(&env, relayer, endpoint, name);
+
+        Ok(())
+    }
+
+    /// Post refundable collateral; fills require the configured minimum bond
+    /// Credits what actually arrived, so fee-on-transfer tokens bond less than sent.
+    pub fn deposit_bond(env: Env, relayer: Address, token: Address, amount: i128) -> Result<(), Error> {
+        relayer.require_auth();
+
+        if !is_relayer(&env, &relayer) {
+            return Err(Error::NotRelayer);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        let contract_address = env.current_contract_address();
+        let balance_before = token_client.balance(&contract_address);
+        token_client.transfer(&relayer, &contract_address, &amount);
+        let received = token_client.balance(&contract_address) - balance_before;
+
+        let balance = get_bond(&env, &relayer, &token) + received;
+        set_bond(&env, &relayer, &token, balance);
+        emit_bond_deposited(&env, relayer, token, received, balance);
+
+        Ok(())
+    }
+
+    /// Withdraw posted bond; removed relayers can always withdraw what is left
+    pub fn withdraw_bond(env: Env, relayer: Address, token: Address, amount: i128) -> Result<(), Error> {
+        relayer.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let bond = get_bond(&env, &relayer, &token);
+        if amount > bond {
+            panic_with_error!(&env, PolicyError::InsufficientBond);
+        }
+
+        let balance = bond - amount;
+        set_bond(&env, &relayer, &token, balance);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &relayer, &amount);
+
+        emit_bond_withdrawn(&env, relayer, token, amount, balance);
+
+        Ok(())
+    }
+
+    // ============ Messenger Callback ============
+
+    /// Receive notification from messenger adapter
+    /// @dev Only registered messenger adapters can call this function
+    /// @param caller The address of the calling contract (must be registered adapter)
+    ///               The adapter MUST call `env.authorize_as_current_contract()` before calling
+    pub fn notify(
+        env: Env,
+        caller: Address,
+        messenger_id: u32,
+        source_chain_id: u64,
+        message_data: Bytes,
+    ) -> Result<(), Error> {
+        // Verify messenger adapter is registered
         let adapter = get_messenger_adapter(&env, messenger_id);
         if adapter.is_none() {
             return Err(Error::InvalidMessenger);
@@ -335,27 +836,216 @@ impl RozoIntentsContract {
         // This ensures only the actual adapter contract can successfully call this function
         caller.require_auth();
 
+        // 3. Restrict the adapter to the source chains it was registered for
+        let allowed_chains = get_adapter_chains(&env, messenger_id);
+        if !allowed_chains.is_empty() && !allowed_chains.contains(source_chain_id) {
+            return Err(Error::WrongChain);
+        }
+
         // Decode payload (adapter has already verified the message before calling)
         let (fill_hash, intent_id, repayment_address, relayer, amount_paid, repayment_is_account) =
             decode_notify_payload(&env, &message_data)?;
+        check_payload_source_chain(&env, &message_data, &intent_id)?;
 
         // Complete fill with correct address type
-        complete_fill(&env, &intent_id, &fill_hash, &repayment_address, repayment_is_account, relayer, amount_paid)
+        complete_fill(&env, &intent_id, &fill_hash, &repayment_address, repayment_is_account, relayer, amount_paid)?;
+
+        // Remember which messenger delivered a successful completion
+        if get_intent(&env, &intent_id)?.status == IntentStatus::Filled {
+            set_fill_messenger(&env, &fill_hash, messenger_id);
+        }
+
+        Ok(())
+    }
+
+    /// Quote a refund: whether refund would currently succeed, and the net amount
+    /// refund_address would receive after the refund fee
+    pub fn refund_quote(env: Env, intent_id: BytesN<32>) -> Result<(bool, i128), Error> {
+        let intent = get_intent(&env, &intent_id)?;
+        let refundable = intent.status.is_open()
+            && !is_intent_frozen(&env, &intent_id)
+            && env.ledger().timestamp() >= intent.deadline;
+        let (_, refund_amount) = refund_split(&intent, get_refund_fee_bps(&env));
+        Ok((refundable, refund_amount))
+    }
+
+    /// Preview the outcome of delivering a notify payload, without mutating state
+    /// Returns the status complete_fill would set (Filled or Failed), or the error it would return.
+    pub fn preview_notify(env: Env, message_data: Bytes) -> Result<IntentStatus, Error> {
+        let (fill_hash, intent_id, _repayment_address, relayer, amount_paid, _repayment_is_account) =
+            decode_notify_payload(&env, &message_data)?;
+        check_payload_source_chain(&env, &message_data, &intent_id)?;
+
+        let intent = get_intent(&env, &intent_id)?;
+        let (outcome, _) = fill_outcome(&env, &intent_id, &intent, &fill_hash, &relayer, amount_paid)?;
+        Ok(outcome)
+    }
+
+    /// Release a relayer payout once its settlement delay has elapsed
+    /// Permissionless: funds can only go to the payout address recorded by notify.
+    pub fn release_settlement(env: Env, fill_hash: BytesN<32>) -> Result<(), Error> {
+        let settlement = get_pending_settlement(&env, &fill_hash).ok_or(Error::SettlementNotFound)?;
+
+        if env.ledger().timestamp() < settlement.release_at {
+            return Err(Error::SettlementLocked);
+        }
+
+        remove_pending_settlement(&env, &fill_hash);
+        add_settlement_owed(&env, &settlement.token, -settlement.amount);
+        release_reserved_fees(&env, &settlement.token, settlement.reserved);
+
+        let token_client = token::Client::new(&env, &settlement.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &settlement.payout_address,
+            &settlement.amount,
+        );
+
+        emit_settlement_released(
+            &env,
+            settlement.intent_id,
+            fill_hash,
+            settlement.payout_address,
+            settlement.amount,
+        );
+
+        Ok(())
+    }
+
+    /// Pull every payout owed to a repayment address in a token (pull payout mode)
+    /// @return Amount transferred
+    pub fn claim_payout(env: Env, relayer: Address, token: Address) -> Result<i128, Error> {
+        relayer.require_auth();
+
+        let amount = get_payout_owed(&env, &relayer, &token);
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        set_payout_owed(&env, &relayer, &token, 0);
+        add_settlement_owed(&env, &token, -amount);
+        release_reserved_fees(&env, &token, get_payout_reserved(&env, &relayer, &token));
+        set_payout_reserved(&env, &relayer, &token, 0);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &relayer, &amount);
+
+        emit_payout_claimed(&env, relayer, token, amount);
+
+        Ok(amount)
     }
 
     // ============ Admin Functions ============
 
-    /// Set protocol fee (in basis points, max 30)
+    /// Replace the contract code with previously uploaded wasm
+    /// Storage is kept; the new code runs from the next invocation.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        let version = get_contract_version(&env) + 1;
+        set_contract_version(&env, version);
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        emit_contract_upgraded(&env, new_wasm_hash.clone(), version);
+        emit_admin_action(&env, "upgrade", admin, new_wasm_hash);
+        Ok(())
+    }
+
+    /// Propose a new owner; takes effect once they call accept_owner
+    /// Proposing again replaces the pending owner.
+    pub fn propose_owner(env: Env, admin: Address, new_owner: Address) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_pending_owner(&env, &new_owner);
+        emit_owner_proposed(&env, admin.clone(), new_owner.clone());
+        emit_admin_action(&env, "propose_owner", admin, new_owner);
+        Ok(())
+    }
+
+    /// Accept a pending ownership proposal
+    pub fn accept_owner(env: Env, new_owner: Address) -> Result<(), Error> {
+        new_owner.require_auth();
+
+        if get_pending_owner(&env) != Some(new_owner.clone()) {
+            return Err(Error::NotAuthorized);
+        }
+
+        let old_owner = get_owner(&env)?;
+        set_owner(&env, &new_owner);
+        clear_pending_owner(&env);
+        emit_owner_transferred(&env, old_owner, new_owner);
+        Ok(())
+    }
+
+    /// Set protocol fee (in basis points, max MAX_PROTOCOL_FEE_BPS)
+    /// Requires a prior arm_emergency.
     pub fn set_protocol_fee(env: Env, admin: Address, fee_bps: u32) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
 
-        if fee_bps > 30 {
+        if fee_bps > MAX_PROTOCOL_FEE_BPS {
             return Err(Error::InvalidFee);
         }
+        require_armed(&env)?;
 
         set_protocol_fee_storage(&env, fee_bps);
         emit_protocol_fee_set(&env, fee_bps);
+        emit_admin_action(&env, "set_protocol_fee", admin, fee_bps);
+
+        Ok(())
+    }
+
+    /// Cap the source_amount of new intents (0 = unlimited)
+    pub fn set_max_intent_amount(env: Env, admin: Address, max: i128) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        if max < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        set_max_intent_amount(&env, max);
+        emit_max_intent_amount_set(&env, max);
+        emit_admin_action(&env, "set_max_intent_amount", admin, max);
+        Ok(())
+    }
+
+    /// Reject new intents of `token` whose source_amount is below `min` (0 = unrestricted)
+    pub fn set_min_intent_amount(env: Env, admin: Address, token: Address, min: i128) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        if min < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        set_min_intent_amount(&env, &token, min);
+        emit_min_intent_amount_set(&env, token.clone(), min);
+        emit_admin_action(&env, "set_min_intent_amount", admin, (token, min));
+        Ok(())
+    }
+
+    /// Set the minimum time (seconds) a new intent must stay open before its deadline
+    pub fn set_min_duration(env: Env, admin: Address, seconds: u64) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_min_intent_duration(&env, seconds);
+        emit_min_duration_set(&env, seconds);
+        emit_admin_action(&env, "set_min_duration", admin, seconds);
+        Ok(())
+    }
+
+    /// Set the fee kept from user-initiated refunds (basis points of source_amount)
+    /// Must stay below 10000 so a refund always returns something. Requires a prior arm_emergency.
+    pub fn set_refund_fee(env: Env, admin: Address, fee_bps: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if fee_bps >= 10000 {
+            return Err(Error::InvalidFee);
+        }
+        require_armed(&env)?;
+
+        set_refund_fee_bps(&env, fee_bps);
+        emit_refund_fee_set(&env, fee_bps);
+        emit_admin_action(&env, "set_refund_fee", admin, fee_bps);
 
         Ok(())
     }
@@ -363,41 +1053,149 @@ impl RozoIntentsContract {
     /// Set fee recipient
     pub fn set_fee_rcpt(env: Env, admin: Address, recipient: Address) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
         set_fee_recipient(&env, &recipient);
-        emit_fee_recipient_set(&env, recipient);
+        emit_fee_recipient_set(&env, recipient.clone());
+        emit_admin_action(&env, "set_fee_rcpt", admin, recipient);
+        Ok(())
+    }
+
+    /// Route withdraw_fees for one token to its own treasury
+    pub fn set_token_fee_recipient(env: Env, admin: Address, token: Address, recipient: Address) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_token_fee_recipient(&env, &token, &recipient);
+        emit_token_fee_recipient_set(&env, token.clone(), recipient.clone());
+        emit_admin_action(&env, "set_token_fee_recipient", admin, (token, recipient));
         Ok(())
     }
 
     /// Add a relayer with type
     pub fn add_relayer(env: Env, admin: Address, relayer: Address, relayer_type: RelayerType) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
 
         if relayer_type == RelayerType::None {
             return Err(Error::InvalidPayload);
         }
 
         set_relayer_type(&env, &relayer, relayer_type.clone());
-        emit_relayer_added(&env, relayer, relayer_type);
+        emit_relayer_added(&env, relayer.clone(), relayer_type);
+        emit_admin_action(&env, "add_relayer", admin, relayer);
         Ok(())
     }
 
     /// Remove a relayer
     pub fn remove_relayer(env: Env, admin: Address, relayer: Address) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
         set_relayer_type(&env, &relayer, RelayerType::None);
-        emit_relayer_removed(&env, relayer);
+        remove_live_relayer(&env, &address_to_bytes32(&env, &relayer));
+        remove_relayer_metadata(&env, &relayer);
+        emit_relayer_removed(&env, relayer.clone());
+        emit_admin_action(&env, "remove_relayer", admin, relayer);
         Ok(())
     }
 
     /// Set messenger adapter
+    /// The adapter must pass the send_msg probe (see adapter_has_send_msg).
     pub fn set_msger_adapter(env: Env, admin: Address, messenger_id: u32, adapter: Address) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
+        if !adapter_has_send_msg(&env, &adapter) {
+            return Err(Error::InvalidMessenger);
+        }
         set_messenger_adapter(&env, messenger_id, &adapter);
         emit_messenger_adapter_set(&env, messenger_id, adapter);
+        emit_admin_action(&env, "set_msger_adapter", admin, messenger_id);
+        Ok(())
+    }
+
+    /// Point a messenger ID at a new adapter for a clean cutover
+    /// With drain, sends through this messenger stay blocked until enable_messenger,
+    /// so in-flight notifications settle before new ones go out. Inbound notify is unaffected.
+    pub fn replace_messenger_adapter(
+        env: Env,
+        admin: Address,
+        messenger_id: u32,
+        new_adapter: Address,
+        drain: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        if !adapter_has_send_msg(&env, &new_adapter) {
+            return Err(Error::InvalidMessenger);
+        }
+        let old_adapter = get_messenger_adapter(&env, messenger_id);
+        set_messenger_adapter(&env, messenger_id, &new_adapter);
+        if drain {
+            set_messenger_disabled(&env, messenger_id, true);
+        }
+        emit_messenger_adapter_replaced(&env, messenger_id, old_adapter, new_adapter, drain);
+        emit_admin_action(&env, "replace_messenger_adapter", admin, messenger_id);
+        Ok(())
+    }
+
+    /// Cap notify retries per intent ID (global_max) and per fill hash (per_fill_max)
+    /// 0 leaves that cap unlimited.
+    pub fn set_retry_limits(env: Env, admin: Address, global_max: u32, per_fill_max: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_global_max_retries(&env, global_max);
+        set_max_fill_retries(&env, per_fill_max);
+        emit_retry_limits_set(&env, global_max, per_fill_max);
+        emit_admin_action(&env, "set_retry_limits", admin, (global_max, per_fill_max));
+        Ok(())
+    }
+
+    /// Remove outbound debug messages more than `older_than` seconds old
+    /// @return Number of messages removed
+    pub fn purge_outbound(env: Env, admin: Address, older_than: u64) -> Result<u32, Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        let cutoff = env.ledger().timestamp().saturating_sub(older_than);
+        let removed = purge_outbound_messages(&env, cutoff);
+        emit_admin_action(&env, "purge_outbound", admin, removed);
+        Ok(removed)
+    }
+
+    /// Set the messengers fill_and_notify falls back to, in order, when sending to a chain
+    /// An empty list removes the route's fallbacks.
+    pub fn set_messenger_fallbacks(
+        env: Env,
+        admin: Address,
+        chain_id: u64,
+        messenger_ids: Vec<u32>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        if messenger_ids.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+        set_messenger_fallbacks(&env, chain_id, &messenger_ids);
+        emit_messenger_fallbacks_set(&env, chain_id, messenger_ids);
+        emit_admin_action(&env, "set_messenger_fallbacks", admin, chain_id);
+        Ok(())
+    }
+
+    /// Limit which source chains a messenger's adapter may deliver notify from
+    /// An empty list allows every chain.
+    pub fn set_adapter_chains(env: Env, admin: Address, messenger_id: u32, chains: Vec<u64>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_adapter_chains(&env, messenger_id, &chains);
+        emit_adapter_chains_set(&env, messenger_id, chains);
+        emit_admin_action(&env, "set_adapter_chains", admin, messenger_id);
+        Ok(())
+    }
+
+    /// Resume sends through a messenger drained by replace_messenger_adapter
+    pub fn enable_messenger(env: Env, admin: Address, messenger_id: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_messenger_disabled(&env, messenger_id, false);
+        emit_messenger_enabled(&env, messenger_id);
+        emit_admin_action(&env, "enable_messenger", admin, messenger_id);
         Ok(())
     }
 
@@ -409,214 +1207,2092 @@ impl RozoIntentsContract {
         contract_address: String,
     ) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
         set_trusted_contract_storage(&env, &chain_name, &contract_address);
-        emit_trusted_contract_set(&env, chain_name, contract_address);
+        emit_trusted_contract_set(&env, chain_name.clone(), contract_address);
+        emit_admin_action(&env, "set_trusted_contract", admin, chain_name);
         Ok(())
     }
 
     /// Set chain ID to name mapping
-    pub fn set_chain_id_to_name(env: Env, admin: Address, chain_id: u64, chain_name: String) -> Result<(), Error> {
+    /// Re-setting the same name is a no-op; replacing a different name requires force.
+    pub fn set_chain_id_to_name(
+        env: Env,
+        admin: Address,
+        chain_id: u64,
+        chain_name: String,
+        force: bool,
+    ) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
+        if let Ok(existing) = get_chain_name(&env, chain_id) {
+            if existing != chain_name && !force {
+                return Err(Error::ChainAlreadyMapped);
+            }
+        }
         set_chain_name(&env, chain_id, &chain_name);
+        emit_admin_action(&env, "set_chain_id_to_name", admin, chain_id);
+        Ok(())
+    }
+
+    /// Set the type of a chain, which picks the notify payload layout sent to it
+    /// Chains never set are Evm; this contract's own chain is always Stellar.
+    pub fn set_chain_type(env: Env, admin: Address, chain_id: u64, chain_type: ChainType) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_chain_type(&env, chain_id, &chain_type);
+        emit_admin_action(&env, "set_chain_type", admin, (chain_id, chain_type));
+        Ok(())
+    }
+
+    /// Send notify payloads as v2, which carry the source chain ID (default v1)
+    /// Enable once every source chain's deployment decodes v2.
+    pub fn set_notify_payload_v2(env: Env, admin: Address, enabled: bool) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_notify_payload_v2(&env, enabled);
+        emit_admin_action(&env, "set_notify_payload_v2", admin, enabled);
         Ok(())
     }
 
     /// Set Rozo relayer for fallback fills
     pub fn set_rozo_relayer(env: Env, admin: Address, relayer: Address) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
         set_rozo_relayer(&env, &relayer);
-        emit_rozo_relayer_set(&env, relayer);
+        emit_rozo_relayer_set(&env, relayer.clone());
+        emit_admin_action(&env, "set_rozo_relayer", admin, relayer);
         Ok(())
     }
 
     /// Set Rozo relayer threshold (seconds)
     pub fn set_rozo_threshold(env: Env, admin: Address, threshold: u64) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
         set_rozo_relayer_threshold(&env, threshold);
         emit_rozo_threshold_set(&env, threshold);
+        emit_admin_action(&env, "set_rozo_threshold", admin, threshold);
         Ok(())
     }
 
-    /// Admin update intent status
-    pub fn set_intent_status(
-        env: Env,
-        admin: Address,
-        intent_id: BytesN<32>,
-        status: IntentStatus,
-    ) -> Result<(), Error> {
+    /// Arm the guarded admin functions (fee setters, emergency_evacuate)
+    /// They stay callable for ARM_WINDOW_SECS, so a dangerous call takes two deliberate steps.
+    /// @return Timestamp the arming lapses
+    pub fn arm_emergency(env: Env, admin: Address) -> Result<u64, Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
+        let armed_until = env.ledger().timestamp() + ARM_WINDOW_SECS;
+        set_armed_until(&env, armed_until);
+        emit_emergency_armed(&env, admin.clone(), armed_until);
+        emit_admin_action(&env, "arm_emergency", admin, armed_until);
+        Ok(armed_until)
+    }
 
-        let mut intent = get_intent(&env, &intent_id)?;
-        let old_status = intent.status.clone();
-        intent.status = status.clone();
-        set_intent(&env, &intent_id, &intent);
-        emit_intent_status_changed(&env, intent_id, old_status, status, admin);
+    /// Pause intent creation and fills, recording why
+    /// Refunds and in-flight notifications stay available while paused.
+    pub fn pause(env: Env, admin: Address, reason: String) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_paused(&env, true);
+        set_pause_reason(&env, &reason);
+        emit_paused(&env, admin.clone(), reason.clone());
+        emit_admin_action(&env, "pause", admin, reason);
         Ok(())
     }
 
-    /// Admin update intent relayer
-    pub fn set_intent_relayer(
-        env: Env,
-        admin: Address,
-        intent_id: BytesN<32>,
-        relayer: BytesN<32>,
-    ) -> Result<(), Error> {
+    /// Unpause the contract and clear the pause reason
+    pub fn unpause(env: Env, admin: Address) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
-
-        let mut intent = get_intent(&env, &intent_id)?;
-        let old_relayer = intent.relayer.clone();
-        intent.relayer = relayer.clone();
-        set_intent(&env, &intent_id, &intent);
-        emit_intent_relayer_changed(&env, intent_id, old_relayer, relayer, admin);
+        require_owner(&env, &admin)?;
+        set_paused(&env, false);
+        clear_pause_reason(&env);
+        emit_unpaused(&env, admin.clone());
+        emit_admin_action(&env, "unpause", admin, ());
         Ok(())
     }
 
-    /// Admin refund
-    pub fn admin_refund(env: Env, admin: Address, intent_id: BytesN<32>) -> Result<(), Error> {
+    /// Configure the fill-rate circuit breaker (max_fills 0 disables it)
+    /// Once max_fills fills land within window seconds the contract pauses itself.
+    pub fn set_circuit_breaker(env: Env, admin: Address, max_fills: u32, window: u64) -> Result<(), Error> {
         admin.require_auth();
-        require_owner(&env)?;
+        require_owner(&env, &admin)?;
 
-        let mut intent = get_intent(&env, &intent_id)?;
+        if max_fills > 0 && window == 0 {
+            return Err(Error::InvalidPayload);
+        }
 
-        if intent.status == IntentStatus::Filled || intent.status == IntentStatus::Refunded {
-            return Err(Error::InvalidStatus);
+        set_circuit_breaker(&env, &CircuitBreaker { max_fills, window });
+        emit_circuit_breaker_set(&env, max_fills, window);
+        emit_admin_action(&env, "set_circuit_breaker", admin, max_fills);
+        Ok(())
+    }
+
+    /// Cap how many fills each relayer may make within window seconds (max_fills 0 = unlimited)
+    /// Contains the damage a compromised relayer key can do before it is removed.
+    pub fn set_relayer_rate_limit(env: Env, admin: Address, max_fills: u32, window_secs: u64) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if max_fills > 0 && window_secs == 0 {
+            return Err(Error::InvalidPayload);
         }
 
-        intent.status = IntentStatus::Refunded;
-        set_intent(&env, &intent_id, &intent);
+        set_relayer_rate_limit(&env, &RelayerRateLimit { max_fills, window: window_secs });
+        emit_relayer_rate_limit_set(&env, max_fills, window_secs);
+        emit_admin_action(&env, "set_relayer_rate_limit", admin, max_fills);
+        Ok(())
+    }
+
+    /// Clear the circuit breaker's fill window and unpause
+    pub fn reset_circuit_breaker(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        clear_fill_rate(&env);
+        set_paused(&env, false);
+        clear_pause_reason(&env);
+        emit_circuit_breaker_reset(&env, admin.clone());
+        emit_admin_action(&env, "reset_circuit_breaker", admin, ());
+        Ok(())
+    }
+
+    /// Set how long (seconds) a heartbeat keeps a relayer live
+    pub fn set_liveness_window(env: Env, admin: Address, window: u64) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_liveness_window(&env, window);
+        emit_liveness_window_set(&env, window);
+        emit_admin_action(&env, "set_liveness_window", admin, window);
+        Ok(())
+    }
+
+    /// Set how long (seconds) a relayer's claim on an open intent lasts
+    pub fn set_claim_window(env: Env, admin: Address, window: u64) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_claim_window(&env, window);
+        emit_claim_window_set(&env, window);
+        emit_admin_action(&env, "set_claim_window", admin, window);
+        Ok(())
+    }
+
+    /// Set the relayer auction window (seconds after creation, 0 = auctions disabled)
+    pub fn set_auction_window(env: Env, admin: Address, window: u64) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_auction_window(&env, window);
+        emit_auction_window_set(&env, window);
+        emit_admin_action(&env, "set_auction_window", admin, window);
+        Ok(())
+    }
+
+    /// Set delay (seconds) between notify and relayer payout release (0 = pay immediately)
+    pub fn set_settlement_delay(env: Env, admin: Address, delay: u64) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_settlement_delay(&env, delay);
+        emit_settlement_delay_set(&env, delay);
+        emit_admin_action(&env, "set_settlement_delay", admin, delay);
+        Ok(())
+    }
+
+    /// Credit relayer payouts for claim_payout instead of transferring them on notify
+    /// Tolerates repayment addresses whose token transfers would fail.
+    pub fn set_pull_payouts(env: Env, admin: Address, enabled: bool) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_pull_payouts(&env, enabled);
+        emit_pull_payouts_set(&env, enabled);
+        emit_admin_action(&env, "set_pull_payouts", admin, enabled);
+        Ok(())
+    }
+
+    /// Cap the number of simultaneously pending intents (0 = unlimited)
+    pub fn set_max_pending(env: Env, admin: Address, max: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_max_pending_intents(&env, max);
+        emit_max_pending_intents_set(&env, max);
+        emit_admin_action(&env, "set_max_pending", admin, max);
+        Ok(())
+    }
+
+    /// Set the flat creation fee senders escrow on top of source_amount (0 = none)
+    /// It becomes protocol fees when the intent fills and is returned on refund.
+    /// Requires a prior arm_emergency.
+    pub fn set_creation_fee(env: Env, admin: Address, fee: i128) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if fee < 0 {
+            return Err(Error::InvalidFee);
+        }
+        require_armed(&env)?;
+
+        set_creation_fee(&env, fee);
+        emit_creation_fee_set(&env, fee);
+        emit_admin_action(&env, "set_creation_fee", admin, fee);
+        Ok(())
+    }
+
+    /// Enable or disable reimbursing relayer overpay from accumulated fees
+    pub fn set_overpay_reimburse(env: Env, admin: Address, enabled: bool) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_overpay_reimbursed(&env, enabled);
+        emit_overpay_reimburse_set(&env, enabled);
+        emit_admin_action(&env, "set_overpay_reimburse", admin, enabled);
+        Ok(())
+    }
+
+    /// Record the destination-chain token equivalent to a local source token
+    pub fn set_asset_equivalence(
+        env: Env,
+        admin: Address,
+        local_token: Address,
+        chain_id: u64,
+        remote_token: BytesN<32>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_asset_equivalence(&env, &local_token, chain_id, &remote_token);
+        emit_asset_equivalence_set(&env, local_token.clone(), chain_id, remote_token.clone());
+        emit_admin_action(&env, "set_asset_equivalence", admin, (local_token, chain_id, remote_token));
+        Ok(())
+    }
+
+    /// Add a source token to the allow-list
+    /// While the list is non-empty, create_intent only escrows listed tokens.
+    pub fn add_allowed_token(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_token_listed(&env, &token, true);
+        emit_source_token_allowed_set(&env, token.clone(), true);
+        emit_admin_action(&env, "add_allowed_token", admin, token);
+        Ok(())
+    }
+
+    /// Remove a source token from the allow-list
+    /// Removing the last listed token lifts the restriction entirely.
+    pub fn remove_allowed_token(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_token_listed(&env, &token, false);
+        emit_source_token_allowed_set(&env, token.clone(), false);
+        emit_admin_action(&env, "remove_allowed_token", admin, token);
+        Ok(())
+    }
+
+    /// Allow or disallow a destination token for fills on a destination chain
+    pub fn set_dest_token_allowed(
+        env: Env,
+        admin: Address,
+        chain_id: u64,
+        token: BytesN<32>,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_dest_token_allowed(&env, chain_id, &token, allowed);
+        emit_dest_token_allowed_set(&env, chain_id, token.clone(), allowed);
+        emit_admin_action(&env, "set_dest_token_allowed", admin, (chain_id, token, allowed));
+        Ok(())
+    }
+
+    /// Require fill_and_notify's destination_token to be on the allow-list
+    pub fn set_restrict_dest_tokens(env: Env, admin: Address, restrict: bool) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_restrict_dest_tokens(&env, restrict);
+        emit_restrict_dest_tokens_set(&env, restrict);
+        emit_admin_action(&env, "set_restrict_dest_tokens", admin, restrict);
+        Ok(())
+    }
+
+    /// Require create_intent's destination_token to match the configured equivalent
+    /// Pairs without a configured equivalent are rejected while this is on.
+    pub fn set_strict_asset_match(env: Env, admin: Address, strict: bool) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_strict_asset_match(&env, strict);
+        emit_strict_asset_match_set(&env, strict);
+        emit_admin_action(&env, "set_strict_asset_match", admin, strict);
+        Ok(())
+    }
+
+    /// Cap how much a relayer may settle within a rolling window (cap 0 removes the limit)
+    pub fn set_relayer_volume_cap(
+        env: Env,
+        admin: Address,
+        relayer: Address,
+        cap: i128,
+        window_seconds: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if cap < 0 || (cap > 0 && window_seconds == 0) {
+            return Err(Error::InvalidAmount);
+        }
+
+        let relayer_bytes32 = address_to_bytes32(&env, &relayer);
+        if cap == 0 {
+            remove_volume_cap(&env, &relayer_bytes32);
+        } else {
+            set_volume_cap(&env, &relayer_bytes32, &VolumeCap { cap, window: window_seconds });
+        }
+
+        emit_relayer_volume_cap_set(&env, relayer.clone(), cap, window_seconds);
+        emit_admin_action(&env, "set_relayer_volume_cap", admin, relayer);
+        Ok(())
+    }
+
+    /// Replace the volume fee tiers (empty = always use the base protocol fee)
+    /// Tiers must be strictly ascending by min_volume; the highest tier reached
+    /// by a token's cumulative filled volume sets the fee for its next fill.
+    /// Requires a prior arm_emergency.
+    pub fn set_fee_tiers(env: Env, admin: Address, tiers: Vec<FeeTier>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if tiers.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+        let mut previous: Option<i128> = None;
+        for tier in tiers.iter() {
+            if tier.fee_bps > MAX_PROTOCOL_FEE_BPS {
+                return Err(Error::InvalidFee);
+            }
+            if tier.min_volume < 0 || previous.is_some_and(|min| tier.min_volume <= min) {
+                return Err(Error::InvalidAmount);
+            }
+            previous = Some(tier.min_volume);
+        }
+        require_armed(&env)?;
+
+        set_fee_tiers(&env, &tiers);
+        emit_fee_tiers_set(&env, tiers.clone());
+        emit_admin_action(&env, "set_fee_tiers", admin, tiers.len());
+        Ok(())
+    }
+
+    /// Set the share of the protocol fee paid to referrers (basis points of the fee, max 10000)
+    /// Requires a prior arm_emergency.
+    pub fn set_referral_share(env: Env, admin: Address, share_bps: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if share_bps > 10000 {
+            return Err(Error::InvalidFee);
+        }
+        require_armed(&env)?;
+
+        set_referral_share_bps(&env, share_bps);
+        emit_referral_share_set(&env, share_bps);
+        emit_admin_action(&env, "set_referral_share", admin, share_bps);
+        Ok(())
+    }
+
+    /// Set the share of the protocol fee rebated to the filling relayer (basis points of the fee, max 10000)
+    /// The rebate is paid with the relayer payout, so relayer payout plus fees
+    /// never exceeds source_amount. Requires a prior arm_emergency.
+    pub fn set_relayer_rebate_bps(env: Env, admin: Address, rebate_bps: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if rebate_bps > 10000 {
+            return Err(Error::InvalidFee);
+        }
+        require_armed(&env)?;
+
+        set_relayer_rebate_bps(&env, rebate_bps);
+        emit_relayer_rebate_set(&env, rebate_bps);
+        emit_admin_action(&env, "set_relayer_rebate_bps", admin, rebate_bps);
+        Ok(())
+    }
+
+    /// Set how many fill hashes are kept per relayer (0 = keep up to MAX_BATCH_SIZE)
+    pub fn set_fill_index_limit(env: Env, admin: Address, limit: u32) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if limit > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+        set_fill_index_limit(&env, limit);
+        emit_fill_index_limit_set(&env, limit);
+        emit_admin_action(&env, "set_fill_index_limit", admin, limit);
+        Ok(())
+    }
+
+    /// Assign an open intent to the live relayer with the most completed fills
+    pub fn auto_assign(env: Env, admin: Address, intent_id: BytesN<32>) -> Result<BytesN<32>, Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        let mut intent = get_intent(&env, &intent_id)?;
+        let zero_bytes32 = BytesN::from_array(&env, &ZERO_BYTES32);
+        if intent.status != IntentStatus::Pending || intent.relayer != zero_bytes32 {
+            return Err(Error::InvalidStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        let window = get_liveness_window(&env);
+        let mut best: Option<(BytesN<32>, u64)> = None;
+        for candidate in get_live_relayers(&env).iter() {
+            if now > get_last_heartbeat(&env, &candidate) + window {
+                continue;
+            }
+            // Relayers serving a claim penalty lose priority
+            if now < get_claim_cooldown_until(&env, &candidate) {
+                continue;
+            }
+            let fills = get_relayer_fill_count(&env, &candidate);
+            // Ties keep the earliest registered relayer
+            let is_better = match &best {
+                Some((_, best_fills)) => fills > *best_fills,
+                None => true,
+            };
+            if is_better {
+                best = Some((candidate, fills));
+            }
+        }
+
+        let (relayer, _) = best.ok_or(Error::NoLiveRelayer)?;
+
+        let old_relayer = intent.relayer.clone();
+        intent.relayer = relayer.clone();
+        set_intent(&env, &intent_id, &intent);
+        emit_intent_relayer_changed(&env, intent_id.clone(), old_relayer, relayer.clone(), admin.clone());
+        emit_admin_action(&env, "auto_assign", admin, intent_id);
+
+        Ok(relayer)
+    }
+
+    /// Rewrite a stored intent under the current schema, backfilling new fields
+    /// @return true if the intent was migrated, false if already current
+    pub fn migrate_intent_schema(env: Env, admin: Address, intent_id: BytesN<32>) -> Result<bool, Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        let migrated = migrate_intent(&env, &intent_id)?;
+        emit_admin_action(&env, "migrate_intent_schema", admin, intent_id);
+        Ok(migrated)
+    }
+
+    /// Migrate several intents in one call
+    /// @return Number of intents actually migrated
+    pub fn batch_migrate(env: Env, admin: Address, intent_ids: Vec<BytesN<32>>) -> Result<u32, Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if intent_ids.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut migrated = 0u32;
+        for intent_id in intent_ids.iter() {
+            if migrate_intent(&env, &intent_id)? {
+                migrated += 1;
+            }
+        }
+        emit_admin_action(&env, "batch_migrate", admin, migrated);
+        Ok(migrated)
+    }
+
+    /// Block fills to a receiver (bytes32, as carried in IntentData)
+    pub fn blacklist_receiver(env: Env, admin: Address, receiver: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_receiver_blocked(&env, &receiver, true);
+        emit_receiver_blacklisted(&env, receiver.clone(), admin.clone());
+        emit_admin_action(&env, "blacklist_receiver", admin, receiver);
+        Ok(())
+    }
+
+    /// Allow fills to a previously blocked receiver
+    pub fn unblacklist_receiver(env: Env, admin: Address, receiver: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_receiver_blocked(&env, &receiver, false);
+        emit_receiver_unblacklisted(&env, receiver.clone(), admin.clone());
+        emit_admin_action(&env, "unblacklist_receiver", admin, receiver);
+        Ok(())
+    }
+
+    /// Freeze a single intent: blocks fills, refunds and notify completion
+    /// until unfrozen. Leaves the rest of the contract running.
+    pub fn freeze_intent(env: Env, admin: Address, intent_id: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_intent_frozen(&env, &intent_id, true);
+        emit_intent_frozen(&env, intent_id.clone(), admin.clone());
+        emit_admin_action(&env, "freeze_intent", admin, intent_id);
+        Ok(())
+    }
+
+    /// Lift a freeze placed by freeze_intent
+    pub fn unfreeze_intent(env: Env, admin: Address, intent_id: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        set_intent_frozen(&env, &intent_id, false);
+        emit_intent_unfrozen(&env, intent_id.clone(), admin.clone());
+        emit_admin_action(&env, "unfreeze_intent", admin, intent_id);
+        Ok(())
+    }
+
+    /// Admin update intent status
+    /// Escrow follows the status: it is released when the intent leaves a status that
+    /// holds escrow and counted again when it returns to one.
+    pub fn set_intent_status(
+        env: Env,
+        admin: Address,
+        intent_id: BytesN<32>,
+        status: IntentStatus,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        let mut intent = get_intent(&env, &intent_id)?;
+        let old_status = intent.status.clone();
+        if old_status.is_open() && !status.is_open() {
+            decrement_pending_intents(&env);
+            remove_from_expiry_bucket(&env, intent.deadline, &intent_id);
+            remove_token_intent(&env, &intent.source_token, &intent_id);
+        } else if !old_status.is_open() && status.is_open() {
+            increment_pending_intents(&env);
+            add_to_expiry_bucket(&env, intent.deadline, &intent_id);
+            push_token_intent(&env, &intent.source_token, &intent_id);
+        }
+        let escrow = intent.source_amount + intent.creation_fee;
+        if old_status.holds_escrow() && !status.holds_escrow() {
+            add_escrowed(&env, &intent.source_token, -escrow);
+        } else if !old_status.holds_escrow() && status.holds_escrow() {
+            add_escrowed(&env, &intent.source_token, escrow);
+        }
+        intent.status = status.clone();
+        set_intent(&env, &intent_id, &intent);
+        emit_intent_status_changed(&env, intent_id.clone(), old_status, status, admin.clone());
+        emit_admin_action(&env, "set_intent_status", admin, intent_id);
+        Ok(())
+    }
+
+    /// Admin update intent relayer
+    pub fn set_intent_relayer(
+        env: Env,
+        admin: Address,
+        intent_id: BytesN<32>,
+        relayer: BytesN<32>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        let mut intent = get_intent(&env, &intent_id)?;
+        let old_relayer = intent.relayer.clone();
+        intent.relayer = relayer.clone();
+        set_intent(&env, &intent_id, &intent);
+        emit_intent_relayer_changed(&env, intent_id.clone(), old_relayer, relayer, admin.clone());
+        emit_admin_action(&env, "set_intent_relayer", admin, intent_id);
+        Ok(())
+    }
+
+    /// Admin refund
+    pub fn admin_refund(env: Env, admin: Address, intent_id: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        acquire_lock(&env)?;
+
+        let mut intent = get_intent(&env, &intent_id)?;
+
+        if matches!(
+            intent.status,
+            IntentStatus::Filled | IntentStatus::Refunded | IntentStatus::Unrecoverable
+        ) {
+            return Err(Error::InvalidStatus);
+        }
+
+        refund_intent(&env, &intent_id, &mut intent, 0);
+        release_lock(&env);
+        emit_admin_action(&env, "admin_refund", admin, intent_id);
+
+        Ok(())
+    }
+
+    /// Remove Failed intents from storage, optionally refunding their escrow first
+    /// Without refund the source amount stays in the contract but is no longer
+    /// counted as escrow; use it only for intents settled out-of-band.
+    /// Any non-Failed intent in the batch aborts the whole call.
+    /// @return Number of intents removed
+    pub fn cleanup_failed(
+        env: Env,
+        admin: Address,
+        intent_ids: Vec<BytesN<32>>,
+        refund: bool,
+    ) -> Result<u32, Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if intent_ids.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        for intent_id in intent_ids.iter() {
+            let mut intent = get_intent(&env, &intent_id)?;
+            if intent.status != IntentStatus::Failed {
+                return Err(Error::InvalidStatus);
+            }
+
+            if refund {
+                refund_intent(&env, &intent_id, &mut intent, 0);
+            } else {
+                add_escrowed(&env, &intent.source_token, -(intent.source_amount + intent.creation_fee));
+            }
+            remove_intent(&env, &intent_id);
+
+            emit_intent_cleaned(&env, intent_id, refund);
+        }
+
+        let cleaned = intent_ids.len();
+        emit_admin_action(&env, "cleanup_failed", admin, (cleaned, refund));
+        Ok(cleaned)
+    }
+
+    /// Move the contract's balance of `token` to a recovery address
+    /// Only while paused and armed. Open intents of the token become Unrecoverable,
+    /// at most MAX_BATCH_SIZE per call; call again until none are left. The call that
+    /// clears the last of them zeroes accrued fees and sweeps the balance, minus escrow
+    /// of Failed intents and settlements, payouts and bonds owed to relayers, which
+    /// stay claimable.
+    /// @return Number of intents marked Unrecoverable
+    pub fn emergency_evacuate(
+        env: Env,
+        admin: Address,
+        token: Address,
+        safe_address: Address,
+    ) -> Result<u32, Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+        if !is_paused(&env) {
+            return Err(Error::NotPaused);
+        }
+        require_armed(&env)?;
+
+        let intent_ids = get_token_intents(&env, &token);
+        let mut marked: u32 = 0;
+        for intent_id in intent_ids.iter().take(MAX_BATCH_SIZE as usize) {
+            remove_token_intent(&env, &token, &intent_id);
+            let mut intent = match get_intent(&env, &intent_id) {
+                Ok(intent) => intent,
+                Err(_) => continue,
+            };
+            if !intent.status.is_open() {
+                continue;
+            }
+            intent.status = IntentStatus::Unrecoverable;
+            set_intent(&env, &intent_id, &intent);
+            remove_intent_claim(&env, &intent_id);
+            remove_from_expiry_bucket(&env, intent.deadline, &intent_id);
+            decrement_pending_intents(&env);
+            add_escrowed(&env, &token, -(intent.source_amount + intent.creation_fee));
+            marked += 1;
+        }
+        if intent_ids.len() > MAX_BATCH_SIZE {
+            return Ok(marked);
+        }
+
+        // Reserved fees are owed through settlements/payouts that stay claimable
+        set_accumulated_fees(&env, &token, get_reserved_fees(&env, &token));
+
+        // What's left in escrow belongs to Failed intents, which cleanup_failed still refunds
+        let token_client = token::Client::new(&env, &token);
+        let contract_address = env.current_contract_address();
+        let owed = get_escrowed(&env, &token) + get_settlement_owed(&env, &token) + get_total_bonded(&env, &token);
+        let amount = (token_client.balance(&contract_address) - owed).max(0);
+        if amount > 0 {
+            token_client.transfer(&contract_address, &safe_address, &amount);
+        }
+
+        emit_emergency_evacuated(&env, token.clone(), safe_address, amount, marked);
+        emit_admin_action(&env, "emergency_evacuate", admin, token);
+        Ok(marked)
+    }
+
+    /// Withdraw accumulated fees
+    /// Pays out get_withdrawable_fees; anything the balance can't back stays accrued.
+    pub fn withdraw_fees(env: Env, admin: Address, token: Address) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        let fee_recipient = match get_token_fee_recipient(&env, &token) {
+            Some(recipient) => recipient,
+            None => get_fee_recipient(&env)?,
+        };
+        let amount = withdrawable_fees(&env, &token);
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        set_accumulated_fees(&env, &token, get_accumulated_fees(&env, &token) - amount);
+        add_total_withdrawn(&env, &token, amount);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &fee_recipient, &amount);
+
+        emit_fees_withdrawn(&env, token.clone(), fee_recipient, amount);
+        emit_admin_action(&env, "withdraw_fees", admin, token);
+
+        Ok(())
+    }
+
+    /// Withdraw part of the accumulated fees to an arbitrary destination (e.g. a bridge)
+    /// `amount` may not exceed get_withdrawable_fees.
+    pub fn withdraw_fees_to(
+        env: Env,
+        admin: Address,
+        token: Address,
+        destination: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if amount <= 0 || amount > withdrawable_fees(&env, &token) {
+            return Err(Error::InvalidAmount);
+        }
+
+        set_accumulated_fees(&env, &token, get_accumulated_fees(&env, &token) - amount);
+        add_total_withdrawn(&env, &token, amount);
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &destination, &amount);
+
+        emit_fees_withdrawn(&env, token.clone(), destination, amount);
+        emit_admin_action(&env, "withdraw_fees_to", admin, token);
+
+        Ok(())
+    }
+
+    /// Require relayers to hold at least `amount` of `token` in bond to fill (0 removes it)
+    pub fn set_min_bond(env: Env, admin: Address, token: Address, amount: i128) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        if amount == 0 {
+            remove_min_bond(&env);
+        } else {
+            set_min_bond(&env, &token, amount);
+        }
+        emit_min_bond_set(&env, token.clone(), amount);
+        emit_admin_action(&env, "set_min_bond", admin, (token, amount));
+        Ok(())
+    }
+
+    /// Slash a misbehaving relayer's bond into accumulated fees
+    pub fn slash_bond(env: Env, admin: Address, relayer: Address, token: Address, amount: i128) -> Result<(), Error> {
+        admin.require_auth();
+        require_owner(&env, &admin)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let bond = get_bond(&env, &relayer, &token);
+        if amount > bond {
+            panic_with_error!(&env, PolicyError::InsufficientBond);
+        }
+
+        let balance = bond - amount;
+        set_bond(&env, &relayer, &token, balance);
+        set_accumulated_fees(&env, &token, get_accumulated_fees(&env, &token) + amount);
+
+        emit_bond_slashed(&env, relayer.clone(), token, amount, balance);
+        emit_admin_action(&env, "slash_bond", admin, relayer);
+        Ok(())
+    }
+
+    // ============ View Functions ============
+
+    /// Get the contract version, incremented on every upgrade
+    pub fn get_version(env: Env) -> u32 {
+        get_contract_version(&env)
+    }
+
+    /// Check whether initialize has been called
+    pub fn is_initialized(env: Env) -> bool {
+        has_owner(&env)
+    }
+
+    /// Get intent details
+    pub fn get_intent(env: Env, intent_id: BytesN<32>) -> Result<Intent, Error> {
+        get_intent(&env, &intent_id)
+    }
+
+    /// Get an intent's status with expiry applied
+    /// A Pending intent at or past its deadline reads as Expired, as if touch_expired
+    /// had run; stored status is not changed.
+    pub fn get_intent_effective_status(env: Env, intent_id: BytesN<32>) -> Result<IntentStatus, Error> {
+        let intent = get_intent(&env, &intent_id)?;
+        if intent.status == IntentStatus::Pending && env.ledger().timestamp() >= intent.deadline {
+            return Ok(IntentStatus::Expired);
+        }
+        Ok(intent.status)
+    }
+
+    /// Get several intents in one call, None for IDs that don't exist
+    pub fn get_intents(env: Env, intent_ids: Vec<BytesN<32>>) -> Result<Vec<Option<Intent>>, Error> {
+        if intent_ids.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut intents = Vec::new(&env);
+        for intent_id in intent_ids.iter() {
+            intents.push_back(get_intent(&env, &intent_id).ok());
+        }
+        Ok(intents)
+    }
+
+    /// Get an intent's lifecycle timestamps (0 for transitions that haven't happened)
+    /// @return (created_at, filled_at, refunded_at)
+    pub fn get_intent_timeline(env: Env, intent_id: BytesN<32>) -> Result<(u64, u64, u64), Error> {
+        let intent = get_intent(&env, &intent_id)?;
+        Ok((intent.created_at, intent.filled_at, intent.refunded_at))
+    }
+
+    /// Check if an intent exists (cheap, never errors)
+    pub fn intent_exists(env: Env, intent_id: BytesN<32>) -> bool {
+        has_intent(&env, &intent_id)
+    }
+
+    /// Get the canonical IntentData for an intent
+    /// Relayers can pass this straight to fill_and_notify on the destination chain.
+    pub fn get_intent_data(env: Env, intent_id: BytesN<32>) -> Result<IntentData, Error> {
+        let intent = get_intent(&env, &intent_id)?;
+        Ok(intent_data_from_intent(&env, &intent))
+    }
+
+    /// Estimate a relayer's economics for filling an intent at its minimum amount
+    /// @param relayer If set, rejects relayers that may not fill this intent
+    /// @return (destination_cost, expected_source_payout), including the relayer rebate but
+    /// before any overpay reimbursement
+    pub fn fill_profitability(
+        env: Env,
+        intent_id: BytesN<32>,
+        relayer: Option<Address>,
+    ) -> Result<(i128, i128), Error> {
+        let intent = get_intent(&env, &intent_id)?;
+        if !intent.status.is_open() {
+            return Err(Error::InvalidStatus);
+        }
+
+        if let Some(relayer) = relayer {
+            let zero_bytes32 = BytesN::from_array(&env, &ZERO_BYTES32);
+            if intent.relayer != zero_bytes32
+                && intent.relayer != address_to_bytes32(&env, &relayer)
+                && !is_rozo_fallback(&env, &relayer, intent.created_at)
+            {
+                return Err(Error::NotAssignedRelayer);
+            }
+        }
+
+        let (fee, _, rebate) = fee_shares(&env, &intent);
+        Ok((intent.destination_amount, intent.source_amount - fee + rebate))
+    }
+
+    /// Rescale an amount between token decimals, e.g. 6-decimal USDC to 7-decimal USDC
+    /// @param reject_on_dust Fail with PrecisionLoss instead of truncating
+    /// @return (normalized, dust) where dust is the truncated remainder in from_decimals units
+    pub fn normalize_amount(
+        _env: Env,
+        amount: i128,
+        from_decimals: u32,
+        to_decimals: u32,
+        reject_on_dust: bool,
+    ) -> Result<(i128, i128), Error> {
+        let (normalized, dust) = normalize_amount(amount, from_decimals, to_decimals)?;
+        if reject_on_dust && dust != 0 {
+            return Err(Error::PrecisionLoss);
+        }
+        Ok((normalized, dust))
+    }
+
+    /// Render a raw token amount as a decimal string, e.g. (1000000000, 7) -> "100.0000000"
+    /// Pure helper so UIs display amounts exactly as the contract computes them.
+    pub fn format_amount(env: Env, amount: i128, decimals: u32) -> Result<String, Error> {
+        format_amount(&env, amount, decimals)
+    }
+
+    /// Get a relayer's published (endpoint, name)
+    pub fn get_relayer_metadata(env: Env, relayer: Address) -> Option<(String, String)> {
+        get_relayer_metadata(&env, &relayer)
+    }
+
+    /// Get relayer type
+    pub fn get_relayer_type(env: Env, address: Address) -> RelayerType {
+        get_relayer_type(&env, &address)
+    }
+
+    /// Check if address is a relayer
+    pub fn is_relayer(env: Env, address: Address) -> bool {
+        is_relayer(&env, &address)
+    }
+
+    /// Get registered relayer counts as (rozo, external, observer)
+    /// There is no Observer type yet, so the last slot is always 0.
+    pub fn get_relayer_type_counts(env: Env) -> (u32, u32, u32) {
+        (
+            get_relayer_type_count(&env, &RelayerType::Rozo),
+            get_relayer_type_count(&env, &RelayerType::External),
+            0,
+        )
+    }
+
+    /// Get the fill hash algorithm
+    pub fn get_hash_algo(env: Env) -> HashAlgo {
+        get_hash_algo(&env)
+    }
+
+    /// Get the maximum intent source amount (0 = unlimited)
+    pub fn get_max_intent_amount(env: Env) -> i128 {
+        get_max_intent_amount(&env)
+    }
+
+    /// Get a token's minimum intent source amount (0 = unrestricted)
+    pub fn get_min_intent_amount(env: Env, token: Address) -> i128 {
+        get_min_intent_amount(&env, &token)
+    }
+
+    /// Get the minimum intent duration (seconds)
+    pub fn get_min_duration(env: Env) -> u64 {
+        get_min_intent_duration(&env)
+    }
+
+    /// Get the relayer rebate (basis points of the protocol fee)
+    pub fn get_relayer_rebate_bps(env: Env) -> u32 {
+        get_relayer_rebate_bps(&env)
+    }
+
+    /// Get the refund fee (basis points)
+    pub fn get_refund_fee(env: Env) -> u32 {
+        get_refund_fee_bps(&env)
+    }
+
+    /// Get protocol fee
+    pub fn get_protocol_fee(env: Env) -> u32 {
+        get_protocol_fee_storage(&env)
+    }
+
+    /// Get the current owner
+    pub fn get_owner(env: Env) -> Result<Address, Error> {
+        get_owner(&env)
+    }
+
+    /// Get the owner proposed by propose_owner, if not yet accepted
+    pub fn get_pending_owner(env: Env) -> Option<Address> {
+        get_pending_owner(&env)
+    }
+
+    /// Get fee recipient
+    pub fn get_fee_rcpt(env: Env) -> Result<Address, Error> {
+        get_fee_recipient(&env)
+    }
+
+    /// Get the volume fee tiers
+    pub fn get_fee_tiers(env: Env) -> Vec<FeeTier> {
+        get_fee_tiers(&env)
+    }
+
+    /// Get the fee (basis points) the next fill in a token will be charged
+    pub fn get_effective_fee(env: Env, token: Address) -> u32 {
+        effective_fee_bps(&env, &token)
+    }
+
+    /// Get cumulative source volume filled for a token
+    pub fn get_total_volume(env: Env, token: Address) -> i128 {
+        get_total_volume(&env, &token)
+    }
+
+    /// Get the token-specific fee recipient, if one overrides the global recipient
+    pub fn get_token_fee_recipient(env: Env, token: Address) -> Option<Address> {
+        get_token_fee_recipient(&env, &token)
+    }
+
+    /// List every token whose accumulated fees withdraw_fees would pay to `recipient`
+    /// Honors token-specific recipients over the global one; tokens with no fees are omitted.
+    pub fn get_pending_fees_for_recipient(env: Env, recipient: Address) -> Vec<(Address, i128)> {
+        let global_recipient = get_fee_recipient(&env).ok();
+        let mut pending = Vec::new(&env);
+        for token in get_fee_tokens(&env).iter() {
+            let routed_to = get_token_fee_recipient(&env, &token).or(global_recipient.clone());
+            if routed_to != Some(recipient.clone()) {
+                continue;
+            }
+            let amount = withdrawable_fees(&env, &token);
+            if amount > 0 {
+                pending.push_back((token, amount));
+            }
+        }
+        pending
+    }
+
+    /// Get the flat creation fee charged per intent
+    pub fn get_creation_fee(env: Env) -> i128 {
+        get_creation_fee(&env)
+    }
+
+    /// Get accumulated fees for a token
+    pub fn get_accum_fees(env: Env, token: Address) -> i128 {
+        get_accumulated_fees(&env, &token)
+    }
+
+    /// Get rebates/reimbursements reserved out of accumulated fees for held payouts
+    pub fn get_reserved_fees(env: Env, token: Address) -> i128 {
+        get_reserved_fees(&env, &token)
+    }
+
+    /// Get accumulated fees net of rebates/reimbursements reserved for held payouts,
+    /// capped by the balance left once escrow, settlements/payouts owed to relayers
+    /// and relayer bonds are reserved.
+    pub fn get_withdrawable_fees(env: Env, token: Address) -> i128 {
+        withdrawable_fees(&env, &token)
+    }
+
+    /// Get lifetime relayer payouts for a token, counted when a fill completes
+    pub fn get_total_relayer_payouts(env: Env, token: Address) -> i128 {
+        get_total_relayer_payouts(&env, &token)
+    }
+
+    /// Get lifetime fees withdrawn for a token
+    pub fn get_total_withdrawn(env: Env, token: Address) -> i128 {
+        get_total_withdrawn(&env, &token)
+    }
+
+    /// Get Rozo relayer
+    pub fn get_rozo_relayer(env: Env) -> Option<Address> {
+        get_rozo_relayer(&env)
+    }
+
+    /// Get Rozo relayer threshold
+    pub fn get_rozo_threshold(env: Env) -> u64 {
+        get_rozo_relayer_threshold(&env)
+    }
+
+    /// Check if the Rozo relayer may currently fill this intent as fallback
+    pub fn is_rozo_fallback_active(env: Env, intent_id: BytesN<32>) -> bool {
+        if get_rozo_relayer(&env).is_none() {
+            return false;
+        }
+        match get_intent(&env, &intent_id) {
+            Ok(intent) => intent.status == IntentStatus::Pending && rozo_fallback_open(&env, intent.created_at),
+            Err(_) => false,
+        }
+    }
+
+    /// Smallest amount_paid a fill can report and still complete the intent
+    pub fn min_fill_amount(env: Env, intent_id: BytesN<32>) -> Result<i128, Error> {
+        let intent = get_intent(&env, &intent_id)?;
+        Ok(min_fill_amount(&intent))
+    }
+
+    /// Get messenger adapter
+    pub fn get_msger_adapter(env: Env, messenger_id: u32) -> Option<Address> {
+        get_messenger_adapter(&env, messenger_id)
+    }
+
+    /// Get the notify retry limits: (per intent ID, per fill hash), 0 = unlimited
+    pub fn get_retry_limits(env: Env) -> (u32, u32) {
+        (get_global_max_retries(&env), get_max_fill_retries(&env))
+    }
+
+    /// Get how many notify retries were sent for an intent ID, across fill hashes
+    pub fn get_intent_retry_count(env: Env, intent_id: BytesN<32>) -> u32 {
+        get_intent_retry_count(&env, &intent_id)
+    }
+
+    /// Get how many notify retries were sent for a fill hash
+    pub fn get_fill_retry_count(env: Env, fill_hash: BytesN<32>) -> u32 {
+        get_fill_retry_count(&env, &fill_hash)
+    }
+
+    /// Get the ordered fallback messengers for notifications to a chain
+    pub fn get_messenger_fallbacks(env: Env, chain_id: u64) -> Vec<u32> {
+        get_messenger_fallbacks(&env, chain_id)
+    }
+
+    /// Get when the current arm_emergency lapses (0 = never armed)
+    pub fn get_armed_until(env: Env) -> u64 {
+        get_armed_until(&env)
+    }
+
+    /// Get the source chains a messenger's adapter may deliver from (empty = any)
+    pub fn get_adapter_chains(env: Env, messenger_id: u32) -> Vec<u64> {
+        get_adapter_chains(&env, messenger_id)
+    }
+
+    /// Check whether sends through a messenger are blocked
+    pub fn is_messenger_disabled(env: Env, messenger_id: u32) -> bool {
+        is_messenger_disabled(&env, messenger_id)
+    }
+
+    /// Get the messenger that delivered the notify completing a fill (source chain)
+    pub fn get_fill_messenger(env: Env, fill_hash: BytesN<32>) -> Option<u32> {
+        get_fill_messenger(&env, &fill_hash)
+    }
+
+    /// Check whether the fill for this IntentData has been recorded on this chain
+    pub fn is_intent_data_filled(env: Env, intent_data: IntentData) -> bool {
+        has_fill_record(&env, &compute_fill_hash(&env, &intent_data))
+    }
+
+    /// Get fill record
+    pub fn get_fill_record(env: Env, fill_hash: BytesN<32>) -> Option<FillRecord> {
+        get_fill_record(&env, &fill_hash)
+    }
+
+    /// Get max pending intents (0 = unlimited)
+    pub fn get_max_pending(env: Env) -> u32 {
+        get_max_pending_intents(&env)
+    }
+
+    /// Get number of intents currently pending
+    pub fn get_pending_count(env: Env) -> u32 {
+        get_pending_intent_count(&env)
+    }
+
+    /// Check if relayer overpay is reimbursed from fees
+    pub fn is_overpay_reimbursed(env: Env) -> bool {
+        is_overpay_reimbursed(&env)
+    }
+
+    /// Get a relayer's posted bond in a token
+    pub fn get_bond(env: Env, relayer: Address, token: Address) -> i128 {
+        get_bond(&env, &relayer, &token)
+    }
+
+    /// Get the bond (token, amount) relayers must hold to fill, if any
+    pub fn get_min_bond(env: Env) -> Option<(Address, i128)> {
+        get_min_bond(&env)
+    }
+
+    /// Get total a token owes: escrow, accumulated fees, pending settlements and relayer bonds
+    pub fn get_obligations(env: Env, token: Address) -> i128 {
+        total_obligations(&env, &token)
+    }
+
+    /// Check whether the contract's balance of a token covers all its obligations
+    /// A false result indicates a bug or an external drain.
+    pub fn is_solvent(env: Env, token: Address) -> bool {
+        let balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        balance >= total_obligations(&env, &token)
+    }
+
+    /// Get circuit breaker config
+    pub fn get_circuit_breaker(env: Env) -> CircuitBreaker {
+        get_circuit_breaker(&env)
+    }
+
+    /// Get the per-relayer fill rate limit
+    pub fn get_relayer_rate_limit(env: Env) -> RelayerRateLimit {
+        get_relayer_rate_limit(&env)
+    }
+
+    /// Get referral share (basis points of the protocol fee)
+    pub fn get_referral_share(env: Env) -> u32 {
+        get_referral_share_bps(&env)
+    }
+
+    /// Get settlement delay
+    pub fn get_settlement_delay(env: Env) -> u64 {
+        get_settlement_delay(&env)
+    }
+
+    /// Get the destination token configured as equivalent to a local token on a chain
+    pub fn get_asset_equivalence(env: Env, local_token: Address, chain_id: u64) -> Option<BytesN<32>> {
+        get_asset_equivalence(&env, &local_token, chain_id)
+    }
+
+    /// Check whether create_intent accepts a source token
+    /// True for every token while the allow-list is empty.
+    pub fn is_token_allowed(env: Env, token: Address) -> bool {
+        get_allowed_token_count(&env) == 0 || is_token_listed(&env, &token)
+    }
+
+    /// Check whether a destination token is allowed for fills on a chain
+    pub fn is_dest_token_allowed(env: Env, chain_id: u64, token: BytesN<32>) -> bool {
+        is_dest_token_allowed(&env, chain_id, &token)
+    }
+
+    /// Check whether fill_and_notify enforces the destination token allow-list
+    pub fn is_restrict_dest_tokens(env: Env) -> bool {
+        is_restrict_dest_tokens(&env)
+    }
+
+    /// Check whether create_intent enforces asset equivalence
+    pub fn is_strict_asset_match(env: Env) -> bool {
+        is_strict_asset_match(&env)
+    }
+
+    /// Get the latest relayer report that an intent is unfillable
+    pub fn get_unfillable_report(env: Env, intent_id: BytesN<32>) -> Option<UnfillableReport> {
+        get_unfillable_report(&env, &intent_id)
+    }
+
+    /// Get payouts owed to a repayment address in a token (pull payout mode)
+    pub fn get_payout_owed(env: Env, payee: Address, token: Address) -> i128 {
+        get_payout_owed(&env, &payee, &token)
+    }
+
+    /// Check whether relayer payouts are credited for claim_payout
+    pub fn is_pull_payouts(env: Env) -> bool {
+        is_pull_payouts(&env)
+    }
+
+    /// Check whether outbound notify payloads are sent as v2
+    pub fn is_notify_payload_v2(env: Env) -> bool {
+        is_notify_payload_v2(&env)
+    }
+
+    /// Get a pending settlement by fill hash
+    pub fn get_pending_settlement(env: Env, fill_hash: BytesN<32>) -> Option<PendingSettlement> {
+        get_pending_settlement(&env, &fill_hash)
+    }
+
+    /// Page through the intents a sender created (oldest first)
+    /// The index is append-only: refunded or filled entries stay listed, so
+    /// callers filter by reading each intent's status. `limit` is capped at MAX_BATCH_SIZE.
+    pub fn get_intents_by_sender(env: Env, sender: Address, start: u32, limit: u32) -> Vec<BytesN<32>> {
+        let intents = get_sender_intents(&env, &sender);
+        let end = start.saturating_add(limit.min(MAX_BATCH_SIZE)).min(intents.len());
+        if start >= end {
+            return Vec::new(&env);
+        }
+        intents.slice(start..end)
+    }
+
+    /// Get open intents whose deadline falls in [from, to), bucket by bucket (earliest bucket first)
+    /// Only the deadline buckets covering the range are read; at most MAX_EXPIRY_BUCKET_SCAN
+    /// buckets per call and `limit` (capped at MAX_BATCH_SIZE) results are returned.
+    pub fn get_expiring_intents(env: Env, from: u64, to: u64, limit: u32) -> Result<Vec<BytesN<32>>, Error> {
+        let mut result = Vec::new(&env);
+        if from >= to {
+            return Ok(result);
+        }
+        let first = expiry_bucket_of(from);
+        let last = expiry_bucket_of(to - 1);
+        if last - first >= MAX_EXPIRY_BUCKET_SCAN {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let limit = limit.min(MAX_BATCH_SIZE);
+        for bucket in first..=last {
+            for intent_id in get_expiry_bucket(&env, bucket).iter() {
+                if result.len() >= limit {
+                    return Ok(result);
+                }
+                // Edge buckets can hold deadlines just outside the range
+                let intent = match get_intent(&env, &intent_id) {
+                    Ok(intent) => intent,
+                    Err(_) => continue,
+                };
+                if intent.deadline >= from && intent.deadline < to {
+                    result.push_back(intent_id);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Get the open intents indexed under one deadline bucket (deadline / EXPIRY_BUCKET_SECS)
+    pub fn get_expiry_bucket(env: Env, bucket: u64) -> Vec<BytesN<32>> {
+        get_expiry_bucket(&env, bucket)
+    }
+
+    /// Get how many intents a sender has ever created
+    /// Lifetime total for analytics; refunds, fills and cleanup never lower it.
+    pub fn get_sender_intent_count(env: Env, sender: Address) -> u64 {
+        get_sender_intent_count(&env, &sender)
+    }
+
+    /// Get fill hashes recorded for a relayer on this chain (oldest first)
+    pub fn get_fills_by_relayer(env: Env, relayer: Address) -> Vec<BytesN<32>> {
+        get_relayer_fill_hashes(&env, &relayer)
+    }
+
+    /// Get fill index limit
+    pub fn get_fill_index_limit(env: Env) -> u32 {
+        get_fill_index_limit(&env)
+    }
+
+    /// Get a relayer's volume cap, if any
+    pub fn get_relayer_volume_cap(env: Env, relayer: Address) -> Option<VolumeCap> {
+        get_volume_cap(&env, &address_to_bytes32(&env, &relayer))
+    }
+
+    /// Get volume a relayer has settled in its current window
+    pub fn get_relayer_volume(env: Env, relayer: Address) -> i128 {
+        let relayer_bytes32 = address_to_bytes32(&env, &relayer);
+        match get_volume_cap(&env, &relayer_bytes32) {
+            Some(cap) => get_window_volume(&env, &relayer_bytes32, cap.window),
+            None => 0,
+        }
+    }
+
+    /// Get number of fills completed on this chain by a relayer
+    pub fn get_relayer_fills(env: Env, relayer: BytesN<32>) -> u64 {
+        get_relayer_fill_count(&env, &relayer)
+    }
+
+    /// Get timestamp of a relayer's last heartbeat (0 if never)
+    pub fn get_last_heartbeat(env: Env, relayer: BytesN<32>) -> u64 {
+        get_last_heartbeat(&env, &relayer)
+    }
+
+    /// Get liveness window (seconds)
+    pub fn get_liveness_window(env: Env) -> u64 {
+        get_liveness_window(&env)
+    }
+
+    /// Get the active or lapsed claim on an intent, if any
+    pub fn get_intent_claim(env: Env, intent_id: BytesN<32>) -> Option<IntentClaim> {
+        get_intent_claim(&env, &intent_id)
+    }
+
+    /// Get the relayer auction window (seconds)
+    pub fn get_auction_window(env: Env) -> u64 {
+        get_auction_window(&env)
+    }
+
+    /// Get the bids placed on an intent's open auction
+    pub fn get_bids(env: Env, intent_id: BytesN<32>) -> Vec<AuctionBid> {
+        get_bids(&env, &intent_id)
+    }
+
+    /// Get the claim window (seconds)
+    pub fn get_claim_window(env: Env) -> u64 {
+        get_claim_window(&env)
+    }
+
+    /// Get how many of a relayer's claims lapsed without a fill
+    pub fn get_relayer_failures(env: Env, relayer: BytesN<32>) -> u64 {
+        get_relayer_failure_count(&env, &relayer)
+    }
+
+    /// Get the timestamp until which a relayer may not claim intents
+    pub fn get_claim_cooldown(env: Env, relayer: BytesN<32>) -> u64 {
+        get_claim_cooldown_until(&env, &relayer)
+    }
+
+    /// Check if fills to a receiver are blocked
+    pub fn is_receiver_blocked(env: Env, receiver: BytesN<32>) -> bool {
+        is_receiver_blocked(&env, &receiver)
+    }
+
+    /// Check if an intent is frozen by the admin
+    pub fn is_intent_frozen(env: Env, intent_id: BytesN<32>) -> bool {
+        is_intent_frozen(&env, &intent_id)
+    }
+
+    /// Check if the contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        is_paused(&env)
+    }
+
+    /// Get the reason given for the current pause (None when not paused)
+    pub fn get_pause_reason(env: Env) -> Option<String> {
+        get_pause_reason(&env)
+    }
+}
+
+// ============ Helper Functions ============
+
+/// Count a fill against the circuit breaker and pause if the window's limit is reached
+/// Trips on the fill that reaches the limit (rather than rejecting the next one) because
+/// an erroring call would roll back the pause flag along with everything else.
+fn trip_circuit_breaker_if_needed(env: &Env) {
+    let breaker = get_circuit_breaker(env);
+    if breaker.max_fills == 0 {
+        return;
+    }
+
+    let fills = record_fill_rate(env, breaker.window);
+    if fills >= breaker.max_fills && !is_paused(env) {
+        set_paused(env, true);
+        set_pause_reason(env, &String::from_str(env, "circuit breaker: fill rate exceeded"));
+        emit_auto_paused(env, fills, breaker.window);
+    }
+}
+
+/// Probe whether an address exposes the adapter's send_msg entrypoint
+/// Sends an empty payload to chain 0, which conforming adapters must treat as a
+/// no-op or reject with their own contract error; contracts without send_msg (or
+/// that trap) fail. Only reachable from the owner-only adapter setters.
+fn adapter_has_send_msg(env: &Env, adapter: &Address) -> bool {
+    let args: Vec<Val> = soroban_sdk::vec![env, 0u64.into_val(env), Bytes::new(env).into_val(env)];
+    match env.try_invoke_contract::<(), InvokeError>(adapter, &symbol_short!("send_msg"), args) {
+        Ok(Ok(())) => true,
+        // send_msg exists and rejected the probe itself
+        Err(Ok(InvokeError::Contract(_))) => true,
+        _ => false,
+    }
+}
+
+/// Reserved fees are counted once, inside the settlements/payouts they are owed through
+fn total_obligations(env: &Env, token: &Address) -> i128 {
+    get_escrowed(env, token) + get_accumulated_fees(env, token) - get_reserved_fees(env, token)
+        + get_settlement_owed(env, token)
+        + get_total_bonded(env, token)
+}
+
+/// Accumulated fees the contract can pay out without touching other obligations
+/// Rebates and reimbursements reserved for held payouts are not withdrawable.
+fn withdrawable_fees(env: &Env, token: &Address) -> i128 {
+    let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+    let reserved = get_escrowed(env, token)
+        + get_settlement_owed(env, token)
+        + get_total_bonded(env, token);
+    (get_accumulated_fees(env, token) - get_reserved_fees(env, token))
+        .min(balance - reserved)
+        .max(0)
+}
+
+/// Settle a reservation once the payout carrying it has been paid
+fn release_reserved_fees(env: &Env, token: &Address, amount: i128) {
+    if amount > 0 {
+        set_accumulated_fees(env, token, get_accumulated_fees(env, token) - amount);
+        add_reserved_fees(env, token, -amount);
+    }
+}
+
+fn validate_intent_params(env: &Env, params: &CreateIntentParams) -> Result<(), Error> {
+    if params.source_amount <= 0 || params.destination_amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if get_allowed_token_count(env) > 0 && !is_token_listed(env, &params.source_token) {
+        panic_with_error!(env, PolicyError::TokenNotAllowed);
+    }
+    let max_amount = get_max_intent_amount(env);
+    if max_amount > 0 && params.source_amount > max_amount {
+        panic_with_error!(env, PolicyError::AmountTooLarge);
+    }
+    if params.source_amount < get_min_intent_amount(env, &params.source_token) {
+        return Err(Error::InvalidAmount);
+    }
+    if params.deadline <= env.ledger().timestamp() {
+        return Err(Error::InvalidDeadline);
+    }
+    if params.deadline < env.ledger().timestamp() + get_min_intent_duration(env) {
+        panic_with_error!(env, PolicyError::DeadlineTooSoon);
+    }
+    // Only Stellar destinations check the relayer type at fill time; on any other
+    // chain the requirement would fail every settlement after the receiver was paid
+    if params.min_relayer_type != RelayerType::None && !is_stellar_chain(env, params.destination_chain_id) {
+        return Err(Error::WrongChain);
+    }
+    if is_strict_asset_match(env) {
+        let expected = get_asset_equivalence(env, &params.source_token, params.destination_chain_id);
+        if expected != Some(params.destination_token.clone()) {
+            return Err(Error::AssetMismatch);
+        }
+    }
+    Ok(())
+}
+
+/// Pull the source tokens from the sender, store the intent and emit IntentCreated
+/// Callers must have validated params and checked the intent ID is unused.
+fn open_intent(env: &Env, sender: &Address, params: CreateIntentParams) -> Result<(), Error> {
+    // Transfer tokens (plus any creation fee) from sender to contract
+    // Escrow what actually arrived: fee-on-transfer tokens deliver less than sent,
+    // and the shortfall comes out of source_amount, not the creation fee.
+    let creation_fee = get_creation_fee(env);
+    let token_client = token::Client::new(env, &params.source_token);
+    let contract_address = env.current_contract_address();
+    let balance_before = token_client.balance(&contract_address);
+    token_client.transfer(sender, &contract_address, &(params.source_amount + creation_fee));
+    let escrow_amount = token_client.balance(&contract_address) - balance_before;
+    if escrow_amount <= creation_fee {
+        return Err(Error::TransferFailed);
+    }
+    let source_amount = escrow_amount - creation_fee;
+
+    // Store intent
+    let intent = Intent {
+        intent_id: params.intent_id.clone(),
+        sender: sender.clone(),
+        refund_address: params.refund_address.clone(),
+        source_token: params.source_token.clone(),
+        source_amount,
+        destination_chain_id: params.destination_chain_id,
+        destination_token: params.destination_token.clone(),
+        receiver: params.receiver.clone(),
+        receiver_is_account: params.receiver_is_account,
+        destination_amount: params.destination_amount,
+        deadline: params.deadline,
+        created_at: env.ledger().timestamp(),
+        status: IntentStatus::Pending,
+        relayer: params.relayer.clone(),
+        schema_version: INTENT_SCHEMA_VERSION,
+        referrer: params.referrer.clone(),
+        preferred_messenger: params.preferred_messenger,
+        filled_at: 0,
+        refunded_at: 0,
+        min_relayer_type: params.min_relayer_type.clone(),
+        creation_fee,
+    };
+    set_intent(env, &params.intent_id, &intent);
+    set_intent_params_hash(env, &params.intent_id, &create_params_hash(env, sender, &params));
+    push_sender_intent(env, sender, &params.intent_id);
+    increment_sender_intent_count(env, sender);
+    push_token_intent(env, &params.source_token, &params.intent_id);
+    add_to_expiry_bucket(env, params.deadline, &params.intent_id);
+    increment_pending_intents(env);
+    add_escrowed(env, &params.source_token, escrow_amount);
+
+    emit_intent_created(
+        env,
+        params.intent_id,
+        sender.clone(),
+        params.source_token,
+        source_amount,
+        params.destination_chain_id,
+        params.receiver,
+        params.destination_amount,
+        params.deadline,
+        params.relayer,
+        params.referrer,
+    );
+
+    Ok(())
+}
+
+fn migrate_intent(env: &Env, intent_id: &BytesN<32>) -> Result<bool, Error> {
+    let mut fields = get_intent_fields(env, intent_id)?;
+
+    let version_key = Symbol::new(env, "schema_version");
+    let from_version = match fields.get(version_key.clone()) {
+        Some(raw) => u32::try_from_val(env, &raw).map_err(|_| Error::InvalidPayload)?,
+        None => 0,
+    };
+    if from_version == INTENT_SCHEMA_VERSION {
+        return Ok(false);
+    }
+
+    // Backfill every field the old layout lacks, then decode under the current schema
+    for (name, default) in intent_field_defaults(env).iter() {
+        if !fields.contains_key(name.clone()) {
+            fields.set(name, default);
+        }
+    }
+    fields.set(version_key, INTENT_SCHEMA_VERSION.into_val(env));
+
+    let intent = Intent::try_from_val(env, &fields.to_val()).map_err(|_| Error::InvalidPayload)?;
+    set_intent(env, intent_id, &intent);
+    emit_intent_migrated(env, intent_id.clone(), from_version, INTENT_SCHEMA_VERSION);
+
+    Ok(true)
+}
+
+/// Defaults for Intent fields added after schema version 0
+fn intent_field_defaults(env: &Env) -> Vec<(Symbol, Val)> {
+    soroban_sdk::vec![
+        env,
+        (Symbol::new(env, "referrer"), Option::<Address>::None.into_val(env)),
+        (Symbol::new(env, "preferred_messenger"), Option::<u32>::None.into_val(env)),
+        (Symbol::new(env, "filled_at"), 0u64.into_val(env)),
+        (Symbol::new(env, "refunded_at"), 0u64.into_val(env)),
+        (Symbol::new(env, "min_relayer_type"), RelayerType::None.into_val(env)),
+        (Symbol::new(env, "creation_fee"), 0i128.into_val(env)),
+    ]
+}
+
+/// Drop a lapsed claim, count a failure for the claimer and start their cooldown
+fn penalize_lapsed_claim(env: &Env, intent_id: &BytesN<32>, claim: IntentClaim) {
+    let relayer_bytes32 = address_to_bytes32(env, &claim.relayer);
+    let failures = get_relayer_failure_count(env, &relayer_bytes32) + 1;
+    set_relayer_failure_count(env, &relayer_bytes32, failures);
+
+    let cooldown_until = env.ledger().timestamp() + CLAIM_PENALTY_PERIOD;
+    set_claim_cooldown_until(env, &relayer_bytes32, cooldown_until);
+    remove_intent_claim(env, intent_id);
+
+    emit_claim_lapsed(env, intent_id.clone(), claim.relayer, failures, cooldown_until);
+}
+
+/// Mark an unsettled intent Refunded and return its escrow to the refund address
+/// `fee_bps` of the source amount is kept as protocol fees (0 for admin refunds).
+/// Callers must have checked the intent is refundable.
+fn refund_intent(env: &Env, intent_id: &BytesN<32>, intent: &mut Intent, fee_bps: u32) {
+    if intent.status.is_open() {
+        decrement_pending_intents(env);
+        remove_from_expiry_bucket(env, intent.deadline, intent_id);
+        remove_token_intent(env, &intent.source_token, intent_id);
+    }
+    intent.status = IntentStatus::Refunded;
+    intent.refunded_at = env.ledger().timestamp();
+    set_intent(env, intent_id, intent);
+    remove_intent_claim(env, intent_id);
+    add_escrowed(env, &intent.source_token, -(intent.source_amount + intent.creation_fee));
+
+    let (refund_fee, refund_amount) = refund_split(intent, fee_bps);
+    if refund_fee > 0 {
+        let fees = get_accumulated_fees(env, &intent.source_token) + refund_fee;
+        set_accumulated_fees(env, &intent.source_token, fees);
+    }
+
+    // Transfer tokens back, creation fee included
+    let token_client = token::Client::new(env, &intent.source_token);
+    token_client.transfer(
+        &env.current_contract_address(),
+        &intent.refund_address,
+        &refund_amount,
+    );
+
+    emit_intent_refunded(env, intent_id.clone(), intent.refund_address.clone(), refund_amount);
+}
+
+/// Split a refund into (fee kept, amount returned to refund_address)
+/// The fee comes out of source_amount; the creation fee is always returned.
+fn refund_split(intent: &Intent, fee_bps: u32) -> (i128, i128) {
+    let refund_fee = (intent.source_amount * fee_bps as i128) / 10000;
+    (refund_fee, intent.source_amount - refund_fee + intent.creation_fee)
+}
+
+/// Hash identifying a creation request, checked by create_intent_idempotent retries
+fn create_params_hash(env: &Env, sender: &Address, params: &CreateIntentParams) -> BytesN<32> {
+    env.crypto().sha256(&(sender.clone(), params.clone()).to_xdr(env)).into()
+}
+
+/// Check whether a stored intent was created by `sender` with exactly these params
+/// Fallback for intents created before create_params_hash was recorded.
+fn intent_matches_params(intent: &Intent, sender: &Address, params: &CreateIntentParams) -> bool {
+    intent.sender == *sender
+        && intent.refund_address == params.refund_address
+        && intent.source_token == params.source_token
+        && intent.source_amount == params.source_amount
+        && intent.destination_chain_id == params.destination_chain_id
+        && intent.destination_token == params.destination_token
+        && intent.receiver == params.receiver
+        && intent.receiver_is_account == params.receiver_is_account
+        && intent.destination_amount == params.destination_amount
+        && intent.deadline == params.deadline
+        && intent.relayer == params.relayer
+        && intent.referrer == params.referrer
+        && intent.preferred_messenger == params.preferred_messenger
+        && intent.min_relayer_type == params.min_relayer_type
+}
+
+/// Send a cross-chain message via the messenger adapter
+/// This calls the adapter contract's send_message function to actually deliver the message
+fn send_via_adapter(env: &Env, adapter: &Address, destination_chain_id: u64, payload: &Bytes) {
+    // Create a client to call the messenger adapter contract
+    // The adapter implements IMessengerAdapter with send_message(destination_chain_id, payload)
+    // We use a cross-contract call to invoke the adapter
+    let args: soroban_sdk::Vec<soroban_sdk::Val> = soroban_sdk::vec![
+        env,
+        destination_chain_id.into_val(env),
+        payload.into_val(env)
+    ];
+
+    // Invoke the adapter's send_message function
+    // This will handle the actual cross-chain messaging (via Axelar, Rozo relayer network, etc.)
+    env.invoke_contract::<()>(adapter, &soroban_sdk::symbol_short!("send_msg"), args);
+}
+
+/// Send via the messenger adapter, reporting failure instead of trapping
+/// A failed send rolls back only the adapter's own changes.
+fn try_send_via_adapter(env: &Env, adapter: &Address, destination_chain_id: u64, payload: &Bytes) -> bool {
+    let args: soroban_sdk::Vec<soroban_sdk::Val> = soroban_sdk::vec![
+        env,
+        destination_chain_id.into_val(env),
+        payload.into_val(env)
+    ];
+    matches!(
+        env.try_invoke_contract::<(), InvokeError>(adapter, &symbol_short!("send_msg"), args),
+        Ok(Ok(()))
+    )
+}
+
+/// Send through `primary`, then each fallback in order, until an adapter accepts
+/// Unregistered and disabled messengers are skipped.
+/// @return The messenger ID that sent the message
+fn send_with_fallbacks(
+    env: &Env,
+    primary: Option<u32>,
+    fallbacks: &Vec<u32>,
+    destination_chain_id: u64,
+    payload: &Bytes,
+) -> Result<u32, Error> {
+    let mut candidates = Vec::new(env);
+    if let Some(primary) = primary {
+        candidates.push_back(primary);
+    }
+    for messenger_id in fallbacks.iter() {
+        if Some(messenger_id) != primary {
+            candidates.push_back(messenger_id);
+        }
+    }
+
+    for messenger_id in candidates.iter() {
+        if is_messenger_disabled(env, messenger_id) {
+            continue;
+        }
+        if let Some(adapter) = get_messenger_adapter(env, messenger_id) {
+            if try_send_via_adapter(env, &adapter, destination_chain_id, payload) {
+                return Ok(messenger_id);
+            }
+        }
+    }
+
+    Err(Error::AllMessengersFailed)
+}
+
+/// Rebuild the notify payload for an already-recorded fill, counting the retry
+/// Only the relayer that performed the fill may resend it, within the retry limits.
+fn build_retry_payload(env: &Env, relayer: &Address, intent_data: &IntentData) -> Result<Bytes, Error> {
+    let fill_hash = compute_fill_hash(env, intent_data);
+
+    // Must have been filled previously
+    let record = get_fill_record(env, &fill_hash).ok_or(Error::IntentNotFound)?;
+
+    // Only original filler can retry
+    if record.relayer != *relayer {
+        return Err(Error::NotAssignedRelayer);
+    }
+
+    // Per-intent cap spans every fill hash seen for the intent ID
+    let global_max = get_global_max_retries(env);
+    if global_max > 0 && get_intent_retry_count(env, &intent_data.intent_id) >= global_max {
+        return Err(Error::RetryLimitReached);
+    }
+    let per_fill_max = get_max_fill_retries(env);
+    if per_fill_max > 0 && get_fill_retry_count(env, &fill_hash) >= per_fill_max {
+        return Err(Error::RetryLimitReached);
+    }
+    increment_retry_counts(env, &intent_data.intent_id, &fill_hash);
+
+    // Build payload with relayer identity and address type flags
+    let relayer_bytes32 = address_to_bytes32(env, relayer);
+    build_notify_payload(
+        env,
+        intent_data.source_chain_id,
+        &intent_data.intent_id,
+        &fill_hash,
+        &record.repayment_address,
+        &relayer_bytes32,
+        intent_data.destination_amount + record.overpay,
+        record.repayment_is_account,
+    )
+}
+
+/// Smallest amount_paid complete_fill accepts for the intent
+fn min_fill_amount(intent: &Intent) -> i128 {
+    intent.destination_amount
+}
+
+/// Status a delivered fill would leave the intent in (Filled or Failed), plus the
+/// expected fill hash; reads state only, so settle_fill and preview_notify agree
+fn fill_outcome(
+    env: &Env,
+    intent_id: &BytesN<32>,
+    intent: &Intent,
+    fill_hash: &BytesN<32>,
+    relayer: &BytesN<32>,
+    amount_paid: i128,
+) -> Result<(IntentStatus, BytesN<32>), Error> {
+    // Status must be PENDING (or EXPIRED but not yet refunded)
+    if !intent.status.is_open() {
+        return Err(Error::InvalidStatus);
+    }
 
-        // Transfer tokens back
-        let token_client = token::Client::new(&env, &intent.source_token);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &intent.refund_address,
-            &intent.source_amount,
-        );
+    // Frozen intents can't settle; the message can be retried after unfreezing
+    if is_intent_frozen(env, intent_id) {
+        return Err(Error::IntentFrozen);
+    }
 
-        emit_intent_refunded(&env, intent_id, intent.refund_address, intent.source_amount);
+    // Compute expected fillHash from stored intent
+    let expected_fill_hash = compute_fill_hash(env, &intent_data_from_intent(env, intent));
 
-        Ok(())
+    // Verify fillHash matches
+    if *fill_hash != expected_fill_hash {
+        return Ok((IntentStatus::Failed, expected_fill_hash));
     }
 
-    /// Withdraw accumulated fees
-    pub fn withdraw_fees(env: Env, admin: Address, token: Address) -> Result<(), Error> {
-        admin.require_auth();
-        require_owner(&env)?;
-
-        let fee_recipient = get_fee_recipient(&env)?;
-        let amount = get_accumulated_fees(&env, &token);
+    // The destination checks min_relayer_type against relayer-supplied intent data,
+    // which the fill hash doesn't cover; hold the reported relayer to the stored one.
+    // Skipped for non-Stellar destinations, whose relayers aren't in this registry
+    if intent.min_relayer_type != RelayerType::None
+        && is_stellar_chain(env, intent.destination_chain_id)
+        && relayer_type_for_bytes32(env, relayer).rank() < intent.min_relayer_type.rank()
+    {
+        return Ok((IntentStatus::Failed, expected_fill_hash));
+    }
 
-        if amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
+    // Verify amount paid meets minimum
+    if amount_paid < min_fill_amount(intent) {
+        return Ok((IntentStatus::Failed, expected_fill_hash));
+    }
 
-        set_accumulated_fees(&env, &token, 0);
+    Ok((IntentStatus::Filled, expected_fill_hash))
+}
 
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &fee_recipient, &amount);
+/// Mark an open intent Failed after a rejected fill; its escrow stays for refund
+fn fail_intent(env: &Env, intent_id: &BytesN<32>, intent: &mut Intent, expected: BytesN<32>, actual: BytesN<32>) {
+    intent.status = IntentStatus::Failed;
+    set_intent(env, intent_id, intent);
+    decrement_pending_intents(env);
+    remove_from_expiry_bucket(env, intent.deadline, intent_id);
+    remove_token_intent(env, &intent.source_token, intent_id);
+    emit_intent_failed(env, intent_id.clone(), expected, actual);
+}
 
-        emit_fees_withdrawn(&env, token, fee_recipient, amount);
+/// Settle a notified fill while holding the reentrancy lock
+fn complete_fill(
+    env: &Env,
+    intent_id: &BytesN<32>,
+    fill_hash: &BytesN<32>,
+    repayment_address: &BytesN<32>,
+    repayment_is_account: bool,
+    relayer: BytesN<32>,
+    amount_paid: i128,
+) -> Result<(), Error> {
+    acquire_lock(env)?;
+    let result = settle_fill(env, intent_id, fill_hash, repayment_address, repayment_is_account, relayer, amount_paid);
+    release_lock(env);
+    result
+}
 
-        Ok(())
+/// Complete or fail an intent for a delivered fill; see complete_fill
+fn settle_fill(
+    env: &Env,
+    intent_id: &BytesN<32>,
+    fill_hash: &BytesN<32>,
+    repayment_address: &BytesN<32>,
+    repayment_is_account: bool,
+    relayer: BytesN<32>,
+    amount_paid: i128,
+) -> Result<(), Error> {
+    let mut intent = get_intent(env, intent_id)?;
+    let (outcome, expected_fill_hash) = fill_outcome(env, intent_id, &intent, fill_hash, &relayer, amount_paid)?;
+    if outcome == IntentStatus::Failed {
+        fail_intent(env, intent_id, &mut intent, expected_fill_hash, fill_hash.clone());
+        return Ok(());
     }
 
-    // ============ View Functions ============
+    // Calculate fee and payout; the relayer rebate is paid with the payout
+    let (fee_amount, referral_amount, rebate_amount) = fee_shares(env, &intent);
+    let mut relayer_payout = intent.source_amount - fee_amount + rebate_amount;
 
-    /// Get intent details
-    pub fn get_intent(env: Env, intent_id: BytesN<32>) -> Result<Intent, Error> {
-        get_intent(&env, &intent_id)
+    if let Some(referrer) = intent.referrer.clone() {
+        if referral_amount > 0 {
+            let token_client = token::Client::new(env, &intent.source_token);
+            token_client.transfer(&env.current_contract_address(), &referrer, &referral_amount);
+            emit_referral_paid(env, intent_id.clone(), referrer, referral_amount);
+        }
     }
 
-    /// Get relayer type
-    pub fn get_relayer_type(env: Env, address: Address) -> RelayerType {
-        get_relayer_type(&env, &address)
+    // Update accumulated fees
+    let mut fees = get_accumulated_fees(env, &intent.source_token) + fee_amount - referral_amount - rebate_amount
+        + intent.creation_fee;
+
+    // Reimburse relayer overpay out of unreserved accumulated fees when policy allows
+    // Assumes source and destination tokens are 1:1 (same stablecoin)
+    let mut reimbursement = 0i128;
+    let overpay = amount_paid - intent.destination_amount;
+    if overpay > 0 && is_overpay_reimbursed(env) {
+        reimbursement = overpay.min(fees - get_reserved_fees(env, &intent.source_token)).max(0);
+        if reimbursement > 0 {
+            fees -= reimbursement;
+            relayer_payout += reimbursement;
+            emit_overpay_reimbursed(env, intent_id.clone(), reimbursement);
+        }
     }
 
-    /// Check if address is a relayer
-    pub fn is_relayer(env: Env, address: Address) -> bool {
-        is_relayer(&env, &address)
+    // A held payout keeps its rebate and reimbursement in accumulated fees, reserved until paid
+    let delay = get_settlement_delay(env);
+    let reserved = if delay > 0 || is_pull_payouts(env) {
+        rebate_amount + reimbursement
+    } else {
+        0
+    };
+    if reserved > 0 {
+        fees += reserved;
+        add_reserved_fees(env, &intent.source_token, reserved);
     }
+    set_accumulated_fees(env, &intent.source_token, fees);
 
-    /// Get protocol fee
-    pub fn get_protocol_fee(env: Env) -> u32 {
-        get_protocol_fee_storage(&env)
+    // Net amount this fill adds to accumulated fees (reserved amounts belong to the relayer)
+    let fee_accrued = fee_amount - referral_amount - rebate_amount - reimbursement + intent.creation_fee;
+    if fee_accrued != 0 {
+        emit_fee_accrued(env, intent.source_token.clone(), fee_accrued, intent_id.clone());
     }
 
-    /// Get fee recipient
-    pub fn get_fee_rcpt(env: Env) -> Result<Address, Error> {
-        get_fee_recipient(&env)
+    // Update status
+    intent.status = IntentStatus::Filled;
+    intent.filled_at = env.ledger().timestamp();
+    set_intent(env, intent_id, &intent);
+    decrement_pending_intents(env);
+    remove_from_expiry_bucket(env, intent.deadline, intent_id);
+    remove_token_intent(env, &intent.source_token, intent_id);
+    remove_intent_claim(env, intent_id);
+    add_escrowed(env, &intent.source_token, -(intent.source_amount + intent.creation_fee));
+    add_total_volume(env, &intent.source_token, intent.source_amount);
+    add_total_relayer_payouts(env, &intent.source_token, relayer_payout);
+
+    // Track relayer reputation
+    let fills = get_relayer_fill_count(env, &relayer);
+    set_relayer_fill_count(env, &relayer, fills + 1);
+
+    // Count settled volume toward the relayer's cap; failed fills never reach here
+    if let Some(cap) = get_volume_cap(env, &relayer) {
+        add_window_volume(env, &relayer, cap.window, amount_paid);
     }
 
-    /// Get accumulated fees for a token
-    pub fn get_accum_fees(env: Env, token: Address) -> i128 {
-        get_accumulated_fees(&env, &token)
+    // Pay relayer using repaymentAddress with correct address type
+    let payout_address = bytes32_to_address_typed(env, repayment_address, repayment_is_account);
+    if payout_address == env.current_contract_address() {
+        return Err(Error::InvalidAddress);
     }
-
-    /// Get Rozo relayer
-    pub fn get_rozo_relayer(env: Env) -> Option<Address> {
-        get_rozo_relayer(&env)
+    if delay > 0 {
+        // Hold the payout until release_settlement is called after the delay
+        let release_at = env.ledger().timestamp() + delay;
+        let settlement = PendingSettlement {
+            intent_id: intent_id.clone(),
+            token: intent.source_token.clone(),
+            payout_address,
+            amount: relayer_payout,
+            reserved,
+            release_at,
+        };
+        set_pending_settlement(env, fill_hash, &settlement);
+        add_settlement_owed(env, &intent.source_token, relayer_payout);
+        emit_settlement_scheduled(env, intent_id.clone(), fill_hash.clone(), relayer_payout, release_at);
+    } else if is_pull_payouts(env) {
+        // Relayer pulls later via claim_payout
+        let owed = get_payout_owed(env, &payout_address, &intent.source_token) + relayer_payout;
+        set_payout_owed(env, &payout_address, &intent.source_token, owed);
+        let held = get_payout_reserved(env, &payout_address, &intent.source_token) + reserved;
+        set_payout_reserved(env, &payout_address, &intent.source_token, held);
+        add_settlement_owed(env, &intent.source_token, relayer_payout);
+        emit_payout_credited(env, intent_id.clone(), payout_address, relayer_payout);
+    } else {
+        let token_client = token::Client::new(env, &intent.source_token);
+        token_client.transfer(&env.current_contract_address(), &payout_address, &relayer_payout);
     }
 
-    /// Get Rozo relayer threshold
-    pub fn get_rozo_threshold(env: Env) -> u64 {
-        get_rozo_relayer_threshold(&env)
-    }
+    emit_intent_filled(
+        env,
+        intent_id.clone(),
+        relayer,
+        repayment_address.clone(),
+        amount_paid,
+        rebate_amount,
+        intent.filled_at,
+    );
 
-    /// Get messenger adapter
-    pub fn get_msger_adapter(env: Env, messenger_id: u32) -> Option<Address> {
-        get_messenger_adapter(&env, messenger_id)
-    }
+    Ok(())
+}
 
-    /// Get fill record
-    pub fn get_fill_record(env: Env, fill_hash: BytesN<32>) -> Option<FillRecord> {
-        get_fill_record(&env, &fill_hash)
+/// Rescale `amount` from one decimals precision to another
+/// Scaling down truncates toward zero; the truncated remainder is returned as dust
+/// so callers can tell when rounding occurred. Overflow is an InvalidAmount error.
+fn normalize_amount(amount: i128, from_decimals: u32, to_decimals: u32) -> Result<(i128, i128), Error> {
+    if to_decimals >= from_decimals {
+        let factor = 10i128
+            .checked_pow(to_decimals - from_decimals)
+            .ok_or(Error::InvalidAmount)?;
+        let normalized = amount.checked_mul(factor).ok_or(Error::InvalidAmount)?;
+        Ok((normalized, 0))
+    } else {
+        let factor = 10i128
+            .checked_pow(from_decimals - to_decimals)
+            .ok_or(Error::InvalidAmount)?;
+        Ok((amount / factor, amount % factor))
     }
 }
 
-// ============ Helper Functions ============
+/// Format `amount` with `decimals` fractional digits (no rounding, no trimming)
+fn format_amount(env: &Env, amount: i128, decimals: u32) -> Result<String, Error> {
+    if decimals > MAX_DISPLAY_DECIMALS {
+        return Err(Error::InvalidAmount);
+    }
+    let decimals = decimals as usize;
+
+    // Digits least significant first, zero-padded so there is always an integer digit
+    let mut digits = [0u8; 40];
+    let mut len = 0;
+    let mut value = amount.unsigned_abs();
+    loop {
+        digits[len] = b'0' + (value % 10) as u8;
+        value /= 10;
+        len += 1;
+        if value == 0 {
+            break;
+        }
+    }
+    while len < decimals + 1 {
+        digits[len] = b'0';
+        len += 1;
+    }
 
-/// Send a cross-chain message via the messenger adapter
-/// This calls the adapter contract's send_message function to actually deliver the message
-fn send_via_adapter(env: &Env, adapter: &Address, destination_chain_id: u64, payload: &Bytes) {
-    // Create a client to call the messenger adapter contract
-    // The adapter implements IMessengerAdapter with send_message(destination_chain_id, payload)
-    // We use a cross-contract call to invoke the adapter
-    let args: soroban_sdk::Vec<soroban_sdk::Val> = soroban_sdk::vec![
-        env,
-        destination_chain_id.into_val(env),
-        payload.into_val(env)
-    ];
+    // Sign + 39 integer digits + '.' + 38 fractional digits
+    let mut out = [0u8; 80];
+    let mut pos = 0;
+    if amount < 0 {
+        out[pos] = b'-';
+        pos += 1;
+    }
+    for i in (0..len).rev() {
+        if decimals > 0 && i + 1 == decimals {
+            out[pos] = b'.';
+            pos += 1;
+        }
+        out[pos] = digits[i];
+        pos += 1;
+    }
 
-    // Invoke the adapter's send_message function
-    // This will handle the actual cross-chain messaging (via Axelar, Rozo relayer network, etc.)
-    env.invoke_contract::<()>(adapter, &soroban_sdk::symbol_short!("send_msg"), args);
+    Ok(String::from_bytes(env, &out[..pos]))
 }
 
-fn complete_fill(
-    env: &Env,
-    intent_id: &BytesN<32>,
-    fill_hash: &BytesN<32>,
-    repayment_address: &BytesN<32>,
-    repayment_is_account: bool,
-    relayer: BytesN<32>,
-    amount_paid: i128,
-) -> Result<(), Error> {
-    let mut intent = get_intent(env, intent_id)?;
+/// Protocol fee charged on a source amount at the current fee rate
+fn protocol_fee_for(env: &Env, token: &Address, source_amount: i128) -> i128 {
+    let fee_bps = effective_fee_bps(env, token);
+    (source_amount * fee_bps as i128) / 10000
+}
 
-    // Status must be PENDING
-    if intent.status != IntentStatus::Pending {
-        return Err(Error::InvalidStatus);
+/// Protocol fee for settling an intent now, with the referral and relayer rebate shares of it
+/// The referrer's share is capped at the whole fee and the rebate comes out of what's
+/// left after it, so the shares never exceed the fee.
+/// @return (fee, referral, rebate)
+fn fee_shares(env: &Env, intent: &Intent) -> (i128, i128, i128) {
+    let fee = protocol_fee_for(env, &intent.source_token, intent.source_amount);
+    let referral = if intent.referrer.is_some() {
+        (fee * get_referral_share_bps(env) as i128) / 10000
+    } else {
+        0
+    };
+    let rebate = ((fee * get_relayer_rebate_bps(env) as i128) / 10000).min(fee - referral);
+    (fee, referral, rebate)
+}
+
+/// Fee rate for the next fill in a token: highest volume tier reached, else the base fee
+fn effective_fee_bps(env: &Env, token: &Address) -> u32 {
+    let volume = get_total_volume(env, token);
+    let mut fee_bps = get_protocol_fee_storage(env);
+    for tier in get_fee_tiers(env).iter() {
+        if volume < tier.min_volume {
+            break;
+        }
+        fee_bps = tier.fee_bps;
     }
+    fee_bps
+}
 
-    // Compute expected fillHash from stored intent
-    // Include receiver_is_account from stored intent for hash verification
-    let expected_data = IntentData {
+/// Build the IntentData a destination-chain relayer must submit for this intent
+/// Includes receiver_is_account from the stored intent for hash verification.
+/// sender, refund_address and source_token are fill hash inputs only and carry no
+/// address type: refunds pay the stored Addresses, never these bytes32.
+fn intent_data_from_intent(env: &Env, intent: &Intent) -> IntentData {
+    IntentData {
         intent_id: intent.intent_id.clone(),
         sender: address_to_bytes32(env, &intent.sender),
         refund_address: address_to_bytes32(env, &intent.refund_address),
@@ -631,46 +3307,9 @@ fn complete_fill(
         created_at: intent.created_at,
         relayer: intent.relayer.clone(),
         receiver_is_account: intent.receiver_is_account,
-    };
-    let expected_fill_hash = compute_fill_hash(env, &expected_data);
-
-    // Verify fillHash matches
-    if *fill_hash != expected_fill_hash {
-        intent.status = IntentStatus::Failed;
-        set_intent(env, intent_id, &intent);
-        emit_intent_failed(env, intent_id.clone(), expected_fill_hash, fill_hash.clone());
-        return Ok(());
-    }
-
-    // Verify amount paid meets minimum
-    if amount_paid < intent.destination_amount {
-        intent.status = IntentStatus::Failed;
-        set_intent(env, intent_id, &intent);
-        emit_intent_failed(env, intent_id.clone(), expected_fill_hash, fill_hash.clone());
-        return Ok(());
+        preferred_messenger: intent.preferred_messenger,
+        min_relayer_type: intent.min_relayer_type.clone(),
     }
-
-    // Calculate fee and payout
-    let fee_bps = get_protocol_fee_storage(env);
-    let fee_amount = (intent.source_amount * fee_bps as i128) / 10000;
-    let relayer_payout = intent.source_amount - fee_amount;
-
-    // Update accumulated fees
-    let current_fees = get_accumulated_fees(env, &intent.source_token);
-    set_accumulated_fees(env, &intent.source_token, current_fees + fee_amount);
-
-    // Update status
-    intent.status = IntentStatus::Filled;
-    set_intent(env, intent_id, &intent);
-
-    // Pay relayer using repaymentAddress with correct address type
-    let payout_address = bytes32_to_address_typed(env, repayment_address, repayment_is_account);
-    let token_client = token::Client::new(env, &intent.source_token);
-    token_client.transfer(&env.current_contract_address(), &payout_address, &relayer_payout);
-
-    emit_intent_filled(env, intent_id.clone(), relayer, repayment_address.clone(), amount_paid);
-
-    Ok(())
 }
 
 fn is_rozo_fallback(env: &Env, caller: &Address, created_at: u64) -> bool {
@@ -683,6 +3322,23 @@ fn is_rozo_fallback(env: &Env, caller: &Address, created_at: u64) -> bool {
         return false;
     }
 
+    rozo_fallback_open(env, created_at)
+}
+
+/// Registered type of a relayer identified by bytes32
+/// The bytes32 carries no address type, so both reconstructions are looked up.
+fn relayer_type_for_bytes32(env: &Env, relayer: &BytesN<32>) -> RelayerType {
+    let as_account = get_relayer_type(env, &bytes32_to_address_typed(env, relayer, true));
+    let as_contract = get_relayer_type(env, &bytes32_to_address_typed(env, relayer, false));
+    if as_account.rank() >= as_contract.rank() {
+        as_account
+    } else {
+        as_contract
+    }
+}
+
+/// Whether the Rozo fallback window has opened for an intent created at `created_at`
+fn rozo_fallback_open(env: &Env, created_at: u64) -> bool {
     let threshold = get_rozo_relayer_threshold(env);
     if threshold == 0 {
         return false;
@@ -692,6 +3348,13 @@ fn is_rozo_fallback(env: &Env, caller: &Address, created_at: u64) -> bool {
 }
 
 fn compute_fill_hash(env: &Env, intent_data: &IntentData) -> BytesN<32> {
+    match get_hash_algo(env) {
+        HashAlgo::Sha256 => sha256_fill_hash(env, intent_data),
+        HashAlgo::Keccak256 => keccak256_fill_hash(env, intent_data),
+    }
+}
+
+fn sha256_fill_hash(env: &Env, intent_data: &IntentData) -> BytesN<32> {
     // Build bytes to hash
     let mut data = Bytes::new(env);
 
@@ -727,6 +3390,43 @@ fn compute_fill_hash(env: &Env, intent_data: &IntentData) -> BytesN<32> {
     env.crypto().sha256(&data).into()
 }
 
+/// Fill hash matching the EVM `keccak256(abi.encode(intentData))`
+/// Every field takes one 32-byte word: uints are left-padded big-endian,
+/// bytes32 as-is, bool as 0/1 in the last byte.
+fn keccak256_fill_hash(env: &Env, intent_data: &IntentData) -> BytesN<32> {
+    let mut data = Bytes::new(env);
+
+    data.append(&Bytes::from_array(env, &intent_data.intent_id.to_array()));
+    data.append(&Bytes::from_array(env, &intent_data.sender.to_array()));
+    data.append(&Bytes::from_array(env, &intent_data.refund_address.to_array()));
+    data.append(&Bytes::from_array(env, &intent_data.source_token.to_array()));
+    data.append(&Bytes::from_array(env, &abi_word_i128(intent_data.source_amount)));
+    data.append(&Bytes::from_array(env, &abi_word_u64(intent_data.source_chain_id)));
+    data.append(&Bytes::from_array(env, &abi_word_u64(intent_data.destination_chain_id)));
+    data.append(&Bytes::from_array(env, &intent_data.destination_token.to_array()));
+    data.append(&Bytes::from_array(env, &intent_data.receiver.to_array()));
+    data.append(&Bytes::from_array(env, &abi_word_i128(intent_data.destination_amount)));
+    data.append(&Bytes::from_array(env, &abi_word_u64(intent_data.deadline)));
+    data.append(&Bytes::from_array(env, &abi_word_u64(intent_data.created_at)));
+    data.append(&Bytes::from_array(env, &intent_data.relayer.to_array()));
+    data.append(&Bytes::from_array(env, &abi_word_u64(intent_data.receiver_is_account as u64)));
+
+    env.crypto().keccak256(&data).into()
+}
+
+fn abi_word_u64(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn abi_word_i128(value: i128) -> [u8; 32] {
+    // Sign-extend like a Solidity int256; amounts are validated positive anyway
+    let mut word = if value < 0 { [0xffu8; 32] } else { [0u8; 32] };
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
 /// Convert Stellar Address to bytes32 for cross-chain compatibility
 ///
 /// This function extracts the raw 32-byte identifier from a Stellar address:
@@ -734,47 +3434,59 @@ fn compute_fill_hash(env: &Env, intent_data: &IntentData) -> BytesN<32> {
 /// - For Contract addresses (C...): extracts the 32-byte contract ID
 ///
 /// IMPORTANT: This encoding does NOT preserve the address type (account vs contract).
-/// The caller must track the address type separately if round-trip conversion is needed.
-/// For cross-chain use, this is typically handled by context (e.g., receiver addresses
-/// are known to be of a specific type based on the destination chain).
-///
-/// XDR format for ScAddress:
-/// - Account: 4 bytes (discriminant=0) + 4 bytes (PublicKeyType::Ed25519=0) + 32 bytes (Ed25519 key) = 40 bytes
-/// - Contract: 4 bytes (discriminant=1) + 32 bytes (contract ID) = 36 bytes
+/// It is only for values compared or hashed as bytes32: relayer ids and the
+/// sender/refund_address/source_token fields of IntentData. Anything turned back into
+/// an Address travels with its type flag instead: receiver (receiver_is_account),
+/// repayment address (repayment_is_account, supplied by the relayer) and destination
+/// token (always a contract). New reconstruction paths must use address_to_bytes32_typed.
 fn address_to_bytes32(env: &Env, addr: &Address) -> BytesN<32> {
+    address_to_bytes32_typed(env, addr).0
+}
+
+/// Convert Stellar Address to bytes32 plus its address type
+///
+/// Returns the raw 32-byte identifier and `is_account` (true for G..., false for C...),
+/// read from the ScAddress XDR discriminant. Passing both to bytes32_to_address_typed
+/// reconstructs the original Address.
+///
+/// Address::to_xdr serializes the ScVal, so the ScAddress follows a 4-byte ScValType::Address tag:
+/// - Account: 4 bytes (ScVal tag) + 4 bytes (discriminant=0) + 4 bytes (PublicKeyType::Ed25519=0)
+///   + 32 bytes (Ed25519 key) = 44 bytes
+/// - Contract: 4 bytes (ScVal tag) + 4 bytes (discriminant=1) + 32 bytes (contract ID) = 40 bytes
+fn address_to_bytes32_typed(env: &Env, addr: &Address) -> (BytesN<32>, bool) {
     let addr_bytes = addr.to_xdr(env);
     let mut result = [0u8; 32];
     let len = addr_bytes.len();
 
     // Check discriminant to determine address type
-    // First 4 bytes are the SCAddress discriminant (big-endian u32)
-    let discriminant = if len >= 4 {
-        addr_bytes.get(3).unwrap_or(0)
+    // Bytes 4..8 are the SCAddress discriminant (big-endian u32)
+    let discriminant = if len >= 8 {
+        addr_bytes.get(7).unwrap_or(0)
     } else {
         0
     };
 
     if discriminant == 0 {
         // Account address (G...)
-        // XDR: 4 bytes discriminant + 4 bytes PublicKeyType + 32 bytes Ed25519 key
-        if len >= 40 {
-            // Copy all 32 bytes of the Ed25519 key (starting at offset 8)
-            for i in 0..32 {
-                result[i] = addr_bytes.get((8 + i) as u32).unwrap_or(0);
+        // XDR: ScVal tag + discriminant + PublicKeyType + 32 bytes Ed25519 key
+        if len >= 44 {
+            // Copy all 32 bytes of the Ed25519 key (starting at offset 12)
+            for (i, byte) in result.iter_mut().enumerate() {
+                *byte = addr_bytes.get((12 + i) as u32).unwrap_or(0);
             }
         }
     } else {
         // Contract address (C...)
-        // XDR: 4 bytes discriminant + 32 bytes contract ID
-        if len >= 36 {
-            // Copy all 32 bytes of the contract ID (starting at offset 4)
-            for i in 0..32 {
-                result[i] = addr_bytes.get((4 + i) as u32).unwrap_or(0);
+        // XDR: ScVal tag + discriminant + 32 bytes contract ID
+        if len >= 40 {
+            // Copy all 32 bytes of the contract ID (starting at offset 8)
+            for (i, byte) in result.iter_mut().enumerate() {
+                *byte = addr_bytes.get((8 + i) as u32).unwrap_or(0);
             }
         }
     }
 
-    BytesN::from_array(env, &result)
+    (BytesN::from_array(env, &result), discriminant == 0)
 }
 
 /// Convert bytes32 to Stellar Address
@@ -786,40 +3498,29 @@ fn address_to_bytes32(env: &Env, addr: &Address) -> BytesN<32> {
 /// @param bytes The 32-byte identifier (Ed25519 key or contract ID)
 /// @param is_account If true, creates an Account address (G...); if false, creates a Contract address (C...)
 ///
-/// XDR format for ScAddress:
-/// - Account: 4 bytes (discriminant=0) + 4 bytes (PublicKeyType::Ed25519=0) + 32 bytes (Ed25519 key) = 40 bytes
-/// - Contract: 4 bytes (discriminant=1) + 32 bytes (contract ID) = 36 bytes
+/// Address::from_xdr expects the ScVal encoding (see address_to_bytes32_typed):
+/// - Account: ScVal tag (18) + discriminant (0) + PublicKeyType::Ed25519 (0) + 32-byte key = 44 bytes
+/// - Contract: ScVal tag (18) + discriminant (1) + 32-byte contract ID = 40 bytes
 fn bytes32_to_address_typed(env: &Env, bytes: &BytesN<32>, is_account: bool) -> Address {
     let bytes_arr = bytes.to_array();
 
     if is_account {
         // Reconstruct account address
-        let mut account_xdr = [0u8; 40];
-        account_xdr[0] = 0;
-        account_xdr[1] = 0;
-        account_xdr[2] = 0;
-        account_xdr[3] = 0; // ScAddress::Account variant
-        account_xdr[4] = 0;
-        account_xdr[5] = 0;
-        account_xdr[6] = 0;
-        account_xdr[7] = 0; // PublicKeyType::Ed25519 variant
-        for i in 0..32 {
-            account_xdr[8 + i] = bytes_arr[i];
-        }
+        let mut account_xdr = [0u8; 44];
+        account_xdr[3] = SCV_ADDRESS_TAG;
+        account_xdr[7] = 0; // ScAddress::Account variant
+        account_xdr[11] = 0; // PublicKeyType::Ed25519 variant
+        account_xdr[12..44].copy_from_slice(&bytes_arr);
 
         let xdr = Bytes::from_array(env, &account_xdr);
         Address::from_xdr(env, &xdr)
             .expect("Failed to construct account address from bytes32")
     } else {
         // Reconstruct contract address
-        let mut contract_xdr = [0u8; 36];
-        contract_xdr[0] = 0;
-        contract_xdr[1] = 0;
-        contract_xdr[2] = 0;
-        contract_xdr[3] = 1; // ScAddress::Contract variant
-        for i in 0..32 {
-            contract_xdr[4 + i] = bytes_arr[i];
-        }
+        let mut contract_xdr = [0u8; 40];
+        contract_xdr[3] = SCV_ADDRESS_TAG;
+        contract_xdr[7] = 1; // ScAddress::Contract variant
+        contract_xdr[8..40].copy_from_slice(&bytes_arr);
 
         let xdr = Bytes::from_array(env, &contract_xdr);
         Address::from_xdr(env, &xdr)
@@ -829,9 +3530,11 @@ fn bytes32_to_address_typed(env: &Env, bytes: &BytesN<32>, is_account: bool) ->
 
 
 /// Encode notify payload for cross-chain notification
-/// Format: intentId (32) + fillHash (32) + repaymentAddress (32) + relayer (32) + amount (32) + flags (32)
+/// Format: version (32) + intentId (32) + fillHash (32) + repaymentAddress (32) + relayer (32)
+///         + amount (32) + flags (32)
 /// Flags byte 31: repayment_is_account (1 = account, 0 = contract)
-/// Total: 192 bytes
+/// Total: 224 bytes (v1), the abi.encode layout RozoIntents.sol decodes
+/// Rejects negative amounts: zero-padding a negative i128 would decode as a large positive value.
 fn encode_notify_payload(
     env: &Env,
     intent_id: &BytesN<32>,
@@ -840,8 +3543,13 @@ fn encode_notify_payload(
     relayer: &BytesN<32>,
     amount: i128,
     repayment_is_account: bool,
-) -> Bytes {
-    let mut payload = Bytes::new(env);
+) -> Result<Bytes, Error> {
+    if amount < 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    // Version (32 bytes) - uint8 tag padded to a word
+    let mut payload = Bytes::from_array(env, &abi_word_u64(NOTIFY_PAYLOAD_V1 as u64));
 
     // Intent ID (32 bytes)
     payload.append(&Bytes::from_array(env, &intent_id.to_array()));
@@ -867,35 +3575,190 @@ fn encode_notify_payload(
     flags_bytes[31] = if repayment_is_account { 1 } else { 0 };
     payload.append(&Bytes::from_array(env, &flags_bytes));
 
-    payload
+    Ok(payload)
+}
+
+/// Encode the compact notify payload used when both chains are Stellar
+/// Format: version (1) + intentId (32) + fillHash (32) + repaymentAddress (32) + relayer (32)
+///         + amount (16) + flags (1)
+/// Total: 146 bytes (v1)
+fn encode_compact_notify_payload(
+    env: &Env,
+    intent_id: &BytesN<32>,
+    fill_hash: &BytesN<32>,
+    repayment_address: &BytesN<32>,
+    relayer: &BytesN<32>,
+    amount: i128,
+    repayment_is_account: bool,
+) -> Result<Bytes, Error> {
+    if amount < 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    let mut payload = Bytes::new(env);
+    payload.push_back(NOTIFY_PAYLOAD_V1);
+    payload.append(&Bytes::from_array(env, &intent_id.to_array()));
+    payload.append(&Bytes::from_array(env, &fill_hash.to_array()));
+    payload.append(&Bytes::from_array(env, &repayment_address.to_array()));
+    payload.append(&Bytes::from_array(env, &relayer.to_array()));
+    payload.append(&Bytes::from_array(env, &amount.to_be_bytes()));
+    payload.append(&Bytes::from_array(env, &[if repayment_is_account { 1u8 } else { 0u8 }]));
+
+    Ok(payload)
+}
+
+/// Encode a notify payload for the source chain: compact when it is a Stellar
+/// chain (see is_stellar_chain), EVM-padded otherwise
+/// v1 unless set_notify_payload_v2 is on; v2 is the v1 layout with source_chain_id
+/// inserted after the version tag.
+fn build_notify_payload(
+    env: &Env,
+    source_chain_id: u64,
+    intent_id: &BytesN<32>,
+    fill_hash: &BytesN<32>,
+    repayment_address: &BytesN<32>,
+    relayer: &BytesN<32>,
+    amount: i128,
+    repayment_is_account: bool,
+) -> Result<Bytes, Error> {
+    let v2 = is_notify_payload_v2(env);
+    if is_stellar_chain(env, source_chain_id) {
+        let v1 = encode_compact_notify_payload(
+            env,
+            intent_id,
+            fill_hash,
+            repayment_address,
+            relayer,
+            amount,
+            repayment_is_account,
+        )?;
+        if !v2 {
+            return Ok(v1);
+        }
+        let mut payload = Bytes::from_array(env, &[NOTIFY_PAYLOAD_V2]);
+        payload.append(&Bytes::from_array(env, &source_chain_id.to_be_bytes()));
+        payload.append(&v1.slice(1..));
+        Ok(payload)
+    } else {
+        let v1 =
+            encode_notify_payload(env, intent_id, fill_hash, repayment_address, relayer, amount, repayment_is_account)?;
+        if !v2 {
+            return Ok(v1);
+        }
+        let mut payload = Bytes::from_array(env, &abi_word_u64(NOTIFY_PAYLOAD_V2 as u64));
+        payload.append(&Bytes::from_array(env, &abi_word_u64(source_chain_id)));
+        payload.append(&v1.slice(32..));
+        Ok(payload)
+    }
 }
 
 /// Decode notify payload from cross-chain notification
-/// Format: intentId (32) + fillHash (32) + repaymentAddress (32) + relayer (32) + amount (32) + flags (32)
+/// EVM format: version (32) + intentId (32) + fillHash (32) + repaymentAddress (32) + relayer (32)
+///             + amount (32) + flags (32), abi.encode'd by the EVM contract
 /// Flags byte 31: repayment_is_account (1 = account, 0 = contract)
+/// Both layouts branch on their leading version tag: v1 is what encode_notify_payload /
+/// encode_compact_notify_payload write; v2 puts source_chain_id after the tag (a word for
+/// EVM, 8 bytes compact), read back by notify_payload_source_chain_id. Unknown tags are rejected.
 /// Returns: (fillHash, intentId, repaymentAddress, relayer, amount, repayment_is_account)
 fn decode_notify_payload(
     env: &Env,
     payload: &Bytes,
 ) -> Result<(BytesN<32>, BytesN<32>, BytesN<32>, BytesN<32>, i128, bool), Error> {
-    if payload.len() != 192 {
-        return Err(Error::InvalidPayload);
+    let len = payload.len();
+    if len > NOTIFY_PAYLOAD_V2_LEN {
+        return Err(Error::PayloadTooLong);
+    }
+    if len >= NOTIFY_PAYLOAD_LEN {
+        // The version word is a uint8: anything above its low byte is malformed
+        if (0..31u32).any(|i| payload.get(i) != Some(0)) {
+            return Err(Error::InvalidPayload);
+        }
+        let (expected_len, body_offset) = match payload.get(31).unwrap_or(0) {
+            NOTIFY_PAYLOAD_V1 => (NOTIFY_PAYLOAD_LEN, 32u32),
+            NOTIFY_PAYLOAD_V2 => (NOTIFY_PAYLOAD_V2_LEN, 64u32),
+            _ => return Err(Error::InvalidPayload),
+        };
+        if len < expected_len {
+            return Err(Error::PayloadTooShort);
+        }
+        if len > expected_len {
+            return Err(Error::PayloadTooLong);
+        }
+        // Amount is the last 16 bytes of the fifth body word
+        return Ok(decode_notify_fields(env, payload, body_offset, body_offset + 144));
+    }
+    if len < COMPACT_NOTIFY_PAYLOAD_LEN {
+        return Err(Error::PayloadTooShort);
+    }
+
+    let (expected_len, body_offset) = match payload.get(0).unwrap_or(0) {
+        NOTIFY_PAYLOAD_V1 => (COMPACT_NOTIFY_PAYLOAD_LEN, 1u32),
+        NOTIFY_PAYLOAD_V2 => (COMPACT_NOTIFY_PAYLOAD_V2_LEN, 9u32),
+        _ => return Err(Error::InvalidPayload),
+    };
+    if len < expected_len {
+        return Err(Error::PayloadTooShort);
+    }
+    if len > expected_len {
+        return Err(Error::PayloadTooLong);
+    }
+
+    Ok(decode_notify_fields(env, payload, body_offset, body_offset + 128))
+}
+
+/// Whether a chain is a Stellar network: this contract's own chain, or one the owner
+/// configured as ChainType::Stellar
+fn is_stellar_chain(env: &Env, chain_id: u64) -> bool {
+    chain_id == get_chain_id(env) || get_chain_type(env, chain_id) == ChainType::Stellar
+}
+
+/// Source chain ID carried by a v2 notify payload (None for v1)
+/// Only meaningful once decode_notify_payload has accepted the payload.
+fn notify_payload_source_chain_id(payload: &Bytes) -> Option<u64> {
+    let (offset, word_offset) = match payload.len() {
+        NOTIFY_PAYLOAD_V2_LEN => (32u32, 24u32),
+        COMPACT_NOTIFY_PAYLOAD_V2_LEN => (1u32, 0u32),
+        _ => return None,
+    };
+    let mut chain_id = [0u8; 8];
+    for (i, byte) in chain_id.iter_mut().enumerate() {
+        *byte = payload.get(offset + word_offset + i as u32).unwrap_or(0);
+    }
+    Some(u64::from_be_bytes(chain_id))
+}
+
+/// Reject a v2 payload whose source_chain_id is not the chain the intent was created on
+/// A mismatch means the message was meant for another deployment.
+fn check_payload_source_chain(env: &Env, payload: &Bytes, intent_id: &BytesN<32>) -> Result<(), Error> {
+    if let Some(source_chain_id) = notify_payload_source_chain_id(payload) {
+        let intent = get_intent(env, intent_id)?;
+        if source_chain_id != intent_data_from_intent(env, &intent).source_chain_id {
+            return Err(Error::WrongChain);
+        }
     }
+    Ok(())
+}
 
+/// Read the fields shared by every notify layout
+/// The four bytes32 fields start at `offset`, the 16-byte amount at `amount_offset`,
+/// and the last byte is repayment_is_account. Callers must have checked the length.
+fn decode_notify_fields(
+    env: &Env,
+    payload: &Bytes,
+    offset: u32,
+    amount_offset: u32,
+) -> (BytesN<32>, BytesN<32>, BytesN<32>, BytesN<32>, i128, bool) {
     let mut intent_id_arr = [0u8; 32];
     let mut fill_hash_arr = [0u8; 32];
     let mut repayment_arr = [0u8; 32];
     let mut relayer_arr = [0u8; 32];
-    let mut amount_arr = [0u8; 32];
-    let mut flags_arr = [0u8; 32];
 
-    for i in 0..32 {
-        intent_id_arr[i] = payload.get(i as u32).unwrap_or(0);
-        fill_hash_arr[i] = payload.get((32 + i) as u32).unwrap_or(0);
-        repayment_arr[i] = payload.get((64 + i) as u32).unwrap_or(0);
-        relayer_arr[i] = payload.get((96 + i) as u32).unwrap_or(0);
-        amount_arr[i] = payload.get((128 + i) as u32).unwrap_or(0);
-        flags_arr[i] = payload.get((160 + i) as u32).unwrap_or(0);
+    for i in 0..32u32 {
+        let idx = i as usize;
+        intent_id_arr[idx] = payload.get(offset + i).unwrap_or(0);
+        fill_hash_arr[idx] = payload.get(offset + 32 + i).unwrap_or(0);
+        repayment_arr[idx] = payload.get(offset + 64 + i).unwrap_or(0);
+        relayer_arr[idx] = payload.get(offset + 96 + i).unwrap_or(0);
     }
 
     let intent_id = BytesN::from_array(env, &intent_id_arr);
@@ -903,15 +3766,17 @@ fn decode_notify_payload(
     let repayment_address = BytesN::from_array(env, &repayment_arr);
     let relayer = BytesN::from_array(env, &relayer_arr);
 
-    // Amount: take last 16 bytes for i128
+    // Amount: big-endian i128
     let mut amount_i128_arr = [0u8; 16];
-    amount_i128_arr.copy_from_slice(&amount_arr[16..32]);
+    for (i, byte) in amount_i128_arr.iter_mut().enumerate() {
+        *byte = payload.get(amount_offset + i as u32).unwrap_or(0);
+    }
     let amount = i128::from_be_bytes(amount_i128_arr);
 
-    // Flags: byte 31 is repayment_is_account
-    let repayment_is_account = flags_arr[31] != 0;
+    // Flags: last byte is repayment_is_account
+    let repayment_is_account = payload.get(payload.len() - 1).unwrap_or(0) != 0;
 
-    Ok((fill_hash, intent_id, repayment_address, relayer, amount, repayment_is_account))
+    (fill_hash, intent_id, repayment_address, relayer, amount, repayment_is_account)
 }
 
 #[cfg(test)]
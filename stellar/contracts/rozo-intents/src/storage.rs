@@ -1,12 +1,27 @@
-use crate::errors::Error;
-use crate::types::{FillRecord, Intent, OutboundMessage, RelayerType};
-use soroban_sdk::{symbol_short, Address, Bytes, BytesN, Env, String, Vec};
+use crate::errors::{Error, PolicyError};
+use crate::types::{
+    AuctionBid, ChainType, CircuitBreaker, FeeTier, FillRateWindow, FillRecord, HashAlgo, Intent, IntentClaim, OutboundMessage, PendingSettlement,
+    RelayerRateLimit, RelayerType, UnfillableReport, VolumeCap, VolumeWindow, EXPIRY_BUCKET_SECS, MAX_BATCH_SIZE,
+};
+use soroban_sdk::{panic_with_error, symbol_short, Address, Bytes, BytesN, Env, Map, String, Symbol, Val, Vec};
 
 // Storage keys using symbol_short!
+fn version_key() -> soroban_sdk::Symbol {
+    symbol_short!("VERSION")
+}
+
+fn lock_key() -> soroban_sdk::Symbol {
+    symbol_short!("LOCK")
+}
+
 fn owner_key() -> soroban_sdk::Symbol {
     symbol_short!("OWNER")
 }
 
+fn pending_owner_key() -> soroban_sdk::Symbol {
+    symbol_short!("PEND_OWN")
+}
+
 fn fee_rcpt_key() -> soroban_sdk::Symbol {
     symbol_short!("FEE_RCPT")
 }
@@ -31,23 +46,315 @@ fn chain_id_key() -> soroban_sdk::Symbol {
     symbol_short!("CHAIN_ID")
 }
 
+fn armed_until_key() -> soroban_sdk::Symbol {
+    symbol_short!("ARMED")
+}
+
+fn max_intent_amount_key() -> soroban_sdk::Symbol {
+    symbol_short!("MAX_AMT")
+}
+
+fn min_intent_amount_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("MIN_AMT"), token.clone())
+}
+
+fn min_duration_key() -> soroban_sdk::Symbol {
+    symbol_short!("MIN_DUR")
+}
+
+fn refund_fee_key() -> soroban_sdk::Symbol {
+    symbol_short!("REFND_FEE")
+}
+
+fn hash_algo_key() -> soroban_sdk::Symbol {
+    symbol_short!("HASH_ALGO")
+}
+
+fn live_relayers_key() -> soroban_sdk::Symbol {
+    symbol_short!("LIVE_REL")
+}
+
+fn liveness_window_key() -> soroban_sdk::Symbol {
+    symbol_short!("LIVE_WIN")
+}
+
+fn fill_index_limit_key() -> soroban_sdk::Symbol {
+    symbol_short!("FILL_LIM")
+}
+
+fn settlement_delay_key() -> soroban_sdk::Symbol {
+    symbol_short!("SETL_DLY")
+}
+
+fn max_pending_key() -> soroban_sdk::Symbol {
+    symbol_short!("MAX_PEND")
+}
+
+fn pending_count_key() -> soroban_sdk::Symbol {
+    symbol_short!("PEND_CNT")
+}
+
+fn creation_fee_key() -> soroban_sdk::Symbol {
+    symbol_short!("CREAT_FEE")
+}
+
+fn overpay_reimburse_key() -> soroban_sdk::Symbol {
+    symbol_short!("OVP_RMB")
+}
+
+fn breaker_key() -> soroban_sdk::Symbol {
+    symbol_short!("BREAKER")
+}
+
+fn fill_rate_key() -> soroban_sdk::Symbol {
+    symbol_short!("FILL_RT")
+}
+
+fn rate_limit_key() -> soroban_sdk::Symbol {
+    symbol_short!("RATE_LIM")
+}
+
+fn relayer_rate_key(relayer: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("REL_RATE"), relayer.clone())
+}
+
+fn rebate_key() -> soroban_sdk::Symbol {
+    symbol_short!("REBATE")
+}
+
+fn referral_share_key() -> soroban_sdk::Symbol {
+    symbol_short!("REF_SHR")
+}
+
+fn fee_tiers_key() -> soroban_sdk::Symbol {
+    symbol_short!("FEE_TIERS")
+}
+
+fn pull_payouts_key() -> soroban_sdk::Symbol {
+    symbol_short!("PULL_PAY")
+}
+
+fn min_bond_key() -> soroban_sdk::Symbol {
+    symbol_short!("MIN_BOND")
+}
+
+fn claim_window_key() -> soroban_sdk::Symbol {
+    symbol_short!("CLAIM_WIN")
+}
+
+fn auction_window_key() -> soroban_sdk::Symbol {
+    symbol_short!("AUCT_WIN")
+}
+
+fn allowed_token_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("ALW_TOK"), token.clone())
+}
+
+fn allowed_token_count_key() -> soroban_sdk::Symbol {
+    symbol_short!("ALW_CNT")
+}
+
+fn restrict_dest_key() -> soroban_sdk::Symbol {
+    symbol_short!("RSTR_DST")
+}
+
+fn strict_asset_key() -> soroban_sdk::Symbol {
+    symbol_short!("STRICT_AS")
+}
+
+fn paused_key() -> soroban_sdk::Symbol {
+    symbol_short!("PAUSED")
+}
+
+fn pause_reason_key() -> soroban_sdk::Symbol {
+    symbol_short!("PAUSE_RS")
+}
+
 // Key builders
 fn intent_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
     (symbol_short!("INTENT"), intent_id.clone())
 }
 
+fn params_hash_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("PARAMHSH"), intent_id.clone())
+}
+
+fn sender_intents_key(sender: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("SNDR_INTS"), sender.clone())
+}
+
+fn sender_intent_count_key(sender: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("SND_CNT"), sender.clone())
+}
+
+fn token_intents_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("TOK_INTS"), token.clone())
+}
+
+fn expiry_bucket_key(bucket: u64) -> (soroban_sdk::Symbol, u64) {
+    (symbol_short!("EXP_BKT"), bucket)
+}
+
 fn relayer_key(relayer: &Address) -> (soroban_sdk::Symbol, Address) {
     (symbol_short!("RELAYER"), relayer.clone())
 }
 
+fn relayer_metadata_key(relayer: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("REL_META"), relayer.clone())
+}
+
+fn relayer_count_key(rank: u32) -> (soroban_sdk::Symbol, u32) {
+    (symbol_short!("REL_CNT"), rank)
+}
+
 fn messenger_adapter_key(messenger_id: u32) -> (soroban_sdk::Symbol, u32) {
     (symbol_short!("MSG_ADP"), messenger_id)
 }
 
+fn adapter_chains_key(messenger_id: u32) -> (soroban_sdk::Symbol, u32) {
+    (symbol_short!("ADP_CHNS"), messenger_id)
+}
+
+fn messenger_fallbacks_key(chain_id: u64) -> (soroban_sdk::Symbol, u64) {
+    (symbol_short!("MSG_FBK"), chain_id)
+}
+
+fn global_max_retries_key() -> soroban_sdk::Symbol {
+    symbol_short!("MAX_RTRY")
+}
+
+fn max_fill_retries_key() -> soroban_sdk::Symbol {
+    symbol_short!("MAX_FRTRY")
+}
+
+fn intent_retries_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("RTRY_INT"), intent_id.clone())
+}
+
+fn fill_retries_key(fill_hash: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("RTRY_FIL"), fill_hash.clone())
+}
+
+fn messenger_ids_key() -> soroban_sdk::Symbol {
+    symbol_short!("MSG_IDS")
+}
+
+fn messenger_disabled_key(messenger_id: u32) -> (soroban_sdk::Symbol, u32) {
+    (symbol_short!("MSG_OFF"), messenger_id)
+}
+
+fn fill_messenger_key(fill_hash: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("FILL_MSG"), fill_hash.clone())
+}
+
 fn fill_record_key(fill_hash: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
     (symbol_short!("FILL"), fill_hash.clone())
 }
 
+fn heartbeat_key(relayer: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("HEARTBT"), relayer.clone())
+}
+
+fn relayer_fills_key(relayer: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("REL_FILL"), relayer.clone())
+}
+
+fn relayer_fill_index_key(relayer: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("FILLS_BY"), relayer.clone())
+}
+
+fn settlement_key(fill_hash: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("SETTLE"), fill_hash.clone())
+}
+
+fn blocked_receiver_key(receiver: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("BLOCKED"), receiver.clone())
+}
+
+fn frozen_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("FROZEN"), intent_id.clone())
+}
+
+fn bids_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("BIDS"), intent_id.clone())
+}
+
+fn claim_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("CLAIM"), intent_id.clone())
+}
+
+fn relayer_failures_key(relayer: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("REL_FAIL"), relayer.clone())
+}
+
+fn claim_cooldown_key(relayer: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("CLM_COOL"), relayer.clone())
+}
+
+fn unfillable_key(intent_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("UNFILL"), intent_id.clone())
+}
+
+fn asset_equiv_key(local_token: &Address, chain_id: u64) -> (soroban_sdk::Symbol, Address, u64) {
+    (symbol_short!("ASSET_EQ"), local_token.clone(), chain_id)
+}
+
+fn dest_token_key(chain_id: u64, token: &BytesN<32>) -> (soroban_sdk::Symbol, u64, BytesN<32>) {
+    (symbol_short!("DST_TOK"), chain_id, token.clone())
+}
+
+fn escrow_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("ESCROW"), token.clone())
+}
+
+fn settlement_owed_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("SETL_OWE"), token.clone())
+}
+
+fn payout_owed_key(payee: &Address, token: &Address) -> (soroban_sdk::Symbol, Address, Address) {
+    (symbol_short!("PAYOUT"), payee.clone(), token.clone())
+}
+
+fn reserved_fees_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("FEE_RSV"), token.clone())
+}
+
+fn payout_reserved_key(payee: &Address, token: &Address) -> (soroban_sdk::Symbol, Address, Address) {
+    (symbol_short!("PAY_RSV"), payee.clone(), token.clone())
+}
+
+fn bond_key(relayer: &Address, token: &Address) -> (soroban_sdk::Symbol, Address, Address) {
+    (symbol_short!("BOND"), relayer.clone(), token.clone())
+}
+
+fn total_bonded_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("BOND_TOT"), token.clone())
+}
+
+fn token_fee_rcpt_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("TOK_RCPT"), token.clone())
+}
+
+fn total_volume_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("TOT_VOL"), token.clone())
+}
+
+fn total_withdrawn_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("WITHDRWN"), token.clone())
+}
+
+fn total_payouts_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
+    (symbol_short!("TOT_PAID"), token.clone())
+}
+
+fn volume_cap_key(relayer: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("VOL_CAP"), relayer.clone())
+}
+
+fn volume_window_key(relayer: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+    (symbol_short!("VOL_WIN"), relayer.clone())
+}
+
 fn trusted_key(chain_name: &String) -> (soroban_sdk::Symbol, String) {
     (symbol_short!("TRUSTED"), chain_name.clone())
 }
@@ -56,6 +363,18 @@ fn chain_name_key(chain_id: u64) -> (soroban_sdk::Symbol, u64) {
     (symbol_short!("CHAIN"), chain_id)
 }
 
+fn chain_type_key(chain_id: u64) -> (soroban_sdk::Symbol, u64) {
+    (symbol_short!("CHAIN_TY"), chain_id)
+}
+
+fn notify_v2_key() -> soroban_sdk::Symbol {
+    symbol_short!("NOTIFY_V2")
+}
+
+fn fee_tokens_key() -> soroban_sdk::Symbol {
+    symbol_short!("FEE_TOKS")
+}
+
 fn fees_key(token: &Address) -> (soroban_sdk::Symbol, Address) {
     (symbol_short!("FEES"), token.clone())
 }
@@ -76,11 +395,44 @@ pub fn set_owner(env: &Env, owner: &Address) {
     env.storage().instance().set(&owner_key(), owner);
 }
 
-pub fn require_owner(env: &Env) -> Result<(), Error> {
-    let _owner = get_owner(env)?;
+pub fn require_owner(env: &Env, admin: &Address) -> Result<(), Error> {
+    let owner = get_owner(env)?;
+    if *admin != owner {
+        return Err(Error::NotOwner);
+    }
+    Ok(())
+}
+
+// Emergency Arming (guarded admin functions need a recent arm_emergency)
+pub fn get_armed_until(env: &Env) -> u64 {
+    env.storage().instance().get(&armed_until_key()).unwrap_or(0)
+}
+
+pub fn set_armed_until(env: &Env, timestamp: u64) {
+    env.storage().instance().set(&armed_until_key(), &timestamp);
+}
+
+pub fn require_armed(env: &Env) -> Result<(), Error> {
+    let armed_until = get_armed_until(env);
+    if armed_until == 0 || env.ledger().timestamp() > armed_until {
+        return Err(Error::NotArmed);
+    }
     Ok(())
 }
 
+// Pending Owner (two-step ownership transfer)
+pub fn get_pending_owner(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&pending_owner_key())
+}
+
+pub fn set_pending_owner(env: &Env, owner: &Address) {
+    env.storage().instance().set(&pending_owner_key(), owner);
+}
+
+pub fn clear_pending_owner(env: &Env) {
+    env.storage().instance().remove(&pending_owner_key());
+}
+
 // Fee Recipient
 pub fn get_fee_recipient(env: &Env) -> Result<Address, Error> {
     env.storage()
@@ -93,6 +445,17 @@ pub fn set_fee_recipient(env: &Env, recipient: &Address) {
     env.storage().instance().set(&fee_rcpt_key(), recipient);
 }
 
+// Per-token fee recipients (override the global recipient for withdraw_fees)
+pub fn get_token_fee_recipient(env: &Env, token: &Address) -> Option<Address> {
+    env.storage().persistent().get(&token_fee_rcpt_key(token))
+}
+
+pub fn set_token_fee_recipient(env: &Env, token: &Address, recipient: &Address) {
+    env.storage()
+        .persistent()
+        .set(&token_fee_rcpt_key(token), recipient);
+}
+
 // Protocol Fee
 pub fn get_protocol_fee_storage(env: &Env) -> u32 {
     env.storage().instance().get(&proto_fee_key()).unwrap_or(0)
@@ -102,6 +465,51 @@ pub fn set_protocol_fee_storage(env: &Env, fee_bps: u32) {
     env.storage().instance().set(&proto_fee_key(), &fee_bps);
 }
 
+// Maximum Intent Source Amount (0 = unlimited)
+pub fn get_max_intent_amount(env: &Env) -> i128 {
+    env.storage().instance().get(&max_intent_amount_key()).unwrap_or(0)
+}
+
+pub fn set_max_intent_amount(env: &Env, max: i128) {
+    env.storage().instance().set(&max_intent_amount_key(), &max);
+}
+
+// Minimum Intent Source Amount per token (0 = unrestricted)
+pub fn get_min_intent_amount(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&min_intent_amount_key(token))
+        .unwrap_or(0)
+}
+
+pub fn set_min_intent_amount(env: &Env, token: &Address, min: i128) {
+    if min == 0 {
+        env.storage().persistent().remove(&min_intent_amount_key(token));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&min_intent_amount_key(token), &min);
+    }
+}
+
+// Minimum Intent Duration (seconds between creation and deadline, 0 = none)
+pub fn get_min_intent_duration(env: &Env) -> u64 {
+    env.storage().instance().get(&min_duration_key()).unwrap_or(0)
+}
+
+pub fn set_min_intent_duration(env: &Env, seconds: u64) {
+    env.storage().instance().set(&min_duration_key(), &seconds);
+}
+
+// Refund Fee (basis points kept from user-initiated refunds)
+pub fn get_refund_fee_bps(env: &Env) -> u32 {
+    env.storage().instance().get(&refund_fee_key()).unwrap_or(0)
+}
+
+pub fn set_refund_fee_bps(env: &Env, fee_bps: u32) {
+    env.storage().instance().set(&refund_fee_key(), &fee_bps);
+}
+
 // Rozo Relayer
 pub fn get_rozo_relayer(env: &Env) -> Option<Address> {
     env.storage().instance().get(&rozo_relayer_key())
@@ -134,108 +542,1220 @@ pub fn set_chain_id(env: &Env, chain_id: u64) {
     env.storage().instance().set(&chain_id_key(), &chain_id);
 }
 
-// Intents
-pub fn has_intent(env: &Env, intent_id: &BytesN<32>) -> bool {
-    env.storage().persistent().has(&intent_key(intent_id))
+// Contract Version (bumped by every upgrade; deployments start at 1)
+pub fn get_contract_version(env: &Env) -> u32 {
+    env.storage().instance().get(&version_key()).unwrap_or(1)
 }
 
-pub fn get_intent(env: &Env, intent_id: &BytesN<32>) -> Result<Intent, Error> {
-    env.storage()
-        .persistent()
-        .get(&intent_key(intent_id))
-        .ok_or(Error::IntentNotFound)
+pub fn set_contract_version(env: &Env, version: u32) {
+    env.storage().instance().set(&version_key(), &version);
 }
 
-pub fn set_intent(env: &Env, intent_id: &BytesN<32>, intent: &Intent) {
-    env.storage()
-        .persistent()
-        .set(&intent_key(intent_id), intent);
+// Reentrancy Lock (temporary storage; a failed call rolls it back with everything else)
+pub fn acquire_lock(env: &Env) -> Result<(), Error> {
+    if env.storage().temporary().has(&lock_key()) {
+        return Err(Error::Reentrancy);
+    }
+    env.storage().temporary().set(&lock_key(), &true);
+    Ok(())
 }
 
-// Relayers (now returns RelayerType)
-pub fn get_relayer_type(env: &Env, relayer: &Address) -> RelayerType {
-    env.storage()
-        .instance()
-        .get(&relayer_key(relayer))
-        .unwrap_or(RelayerType::None)
+pub fn release_lock(env: &Env) {
+    env.storage().temporary().remove(&lock_key());
 }
 
-pub fn set_relayer_type(env: &Env, relayer: &Address, relayer_type: RelayerType) {
-    env.storage()
-        .instance()
-        .set(&relayer_key(relayer), &relayer_type);
+// Fill Hash Algorithm (contracts initialized before the selector existed use Sha256)
+pub fn get_hash_algo(env: &Env) -> HashAlgo {
+    env.storage().instance().get(&hash_algo_key()).unwrap_or(HashAlgo::Sha256)
 }
 
-pub fn is_relayer(env: &Env, relayer: &Address) -> bool {
-    get_relayer_type(env, relayer) != RelayerType::None
+pub fn set_hash_algo(env: &Env, algo: &HashAlgo) {
+    env.storage().instance().set(&hash_algo_key(), algo);
 }
 
-// Messenger Adapters (by messengerId)
-pub fn get_messenger_adapter(env: &Env, messenger_id: u32) -> Option<Address> {
+// Referral Share (basis points of the protocol fee)
+pub fn get_referral_share_bps(env: &Env) -> u32 {
     env.storage()
         .instance()
-        .get(&messenger_adapter_key(messenger_id))
+        .get(&referral_share_key())
+        .unwrap_or(0)
 }
 
-pub fn set_messenger_adapter(env: &Env, messenger_id: u32, adapter: &Address) {
+pub fn set_referral_share_bps(env: &Env, share_bps: u32) {
     env.storage()
         .instance()
-        .set(&messenger_adapter_key(messenger_id), adapter);
+        .set(&referral_share_key(), &share_bps);
 }
 
-// Fill Records (destination chain - for double-fill prevention)
-pub fn has_fill_record(env: &Env, fill_hash: &BytesN<32>) -> bool {
-    env.storage().persistent().has(&fill_record_key(fill_hash))
+// Relayer Rebate (basis points of the protocol fee returned to the filling relayer)
+pub fn get_relayer_rebate_bps(env: &Env) -> u32 {
+    env.storage().instance().get(&rebate_key()).unwrap_or(0)
 }
 
-pub fn get_fill_record(env: &Env, fill_hash: &BytesN<32>) -> Option<FillRecord> {
-    env.storage().persistent().get(&fill_record_key(fill_hash))
+pub fn set_relayer_rebate_bps(env: &Env, rebate_bps: u32) {
+    env.storage().instance().set(&rebate_key(), &rebate_bps);
 }
 
-pub fn set_fill_record(env: &Env, fill_hash: &BytesN<32>, record: &FillRecord) {
-    env.storage()
-        .persistent()
-        .set(&fill_record_key(fill_hash), record);
+// Creation Fee (flat amount in the intent's source token)
+pub fn get_creation_fee(env: &Env) -> i128 {
+    env.storage().instance().get(&creation_fee_key()).unwrap_or(0)
 }
 
-// Trusted Contracts
-pub fn get_trusted_contract(env: &Env, chain_name: &String) -> Result<String, Error> {
-    env.storage()
-        .instance()
-        .get(&trusted_key(chain_name))
-        .ok_or(Error::UntrustedSource)
+pub fn set_creation_fee(env: &Env, fee: i128) {
+    env.storage().instance().set(&creation_fee_key(), &fee);
 }
 
-pub fn set_trusted_contract_storage(env: &Env, chain_name: &String, contract_address: &String) {
+// Overpay Reimbursement Policy
+pub fn is_overpay_reimbursed(env: &Env) -> bool {
     env.storage()
         .instance()
-        .set(&trusted_key(chain_name), contract_address);
+        .get(&overpay_reimburse_key())
+        .unwrap_or(false)
 }
 
-// Chain Names (chain_id -> chain_name mapping)
-pub fn get_chain_name(env: &Env, chain_id: u64) -> Result<String, Error> {
+pub fn set_overpay_reimbursed(env: &Env, enabled: bool) {
     env.storage()
         .instance()
-        .get(&chain_name_key(chain_id))
-        .ok_or(Error::ChainNotFound)
+        .set(&overpay_reimburse_key(), &enabled);
 }
 
-pub fn set_chain_name(env: &Env, chain_id: u64, chain_name: &String) {
-    env.storage()
-        .instance()
-        .set(&chain_name_key(chain_id), chain_name);
+// Pause
+pub fn is_paused(env: &Env) -> bool {
+    env.storage().instance().get(&paused_key()).unwrap_or(false)
 }
 
-// Accumulated Fees
-pub fn get_accumulated_fees(env: &Env, token: &Address) -> i128 {
-    env.storage()
-        .persistent()
+pub fn set_paused(env: &Env, paused: bool) {
+    env.storage().instance().set(&paused_key(), &paused);
+}
+
+pub fn require_not_paused(env: &Env) -> Result<(), Error> {
+    if is_paused(env) {
+        return Err(Error::Paused);
+    }
+    Ok(())
+}
+
+pub fn get_pause_reason(env: &Env) -> Option<String> {
+    env.storage().instance().get(&pause_reason_key())
+}
+
+pub fn set_pause_reason(env: &Env, reason: &String) {
+    env.storage().instance().set(&pause_reason_key(), reason);
+}
+
+pub fn clear_pause_reason(env: &Env) {
+    env.storage().instance().remove(&pause_reason_key());
+}
+
+// Circuit Breaker (fill rate limit that auto-pauses)
+pub fn get_circuit_breaker(env: &Env) -> CircuitBreaker {
+    env.storage()
+        .instance()
+        .get(&breaker_key())
+        .unwrap_or(CircuitBreaker { max_fills: 0, window: 0 })
+}
+
+pub fn set_circuit_breaker(env: &Env, breaker: &CircuitBreaker) {
+    env.storage().instance().set(&breaker_key(), breaker);
+}
+
+/// Record a fill in the current window and return the window's fill count
+pub fn record_fill_rate(env: &Env, window: u64) -> u32 {
+    let now = env.ledger().timestamp();
+    let current: Option<FillRateWindow> = env.storage().instance().get(&fill_rate_key());
+    let updated = match current {
+        Some(w) if now < w.window_start + window => FillRateWindow {
+            window_start: w.window_start,
+            count: w.count + 1,
+        },
+        _ => FillRateWindow {
+            window_start: now,
+            count: 1,
+        },
+    };
+    env.storage().instance().set(&fill_rate_key(), &updated);
+    updated.count
+}
+
+pub fn clear_fill_rate(env: &Env) {
+    env.storage().instance().remove(&fill_rate_key());
+}
+
+// Per-relayer fill rate limit (max_fills 0 = unlimited)
+pub fn get_relayer_rate_limit(env: &Env) -> RelayerRateLimit {
+    env.storage()
+        .instance()
+        .get(&rate_limit_key())
+        .unwrap_or(RelayerRateLimit { max_fills: 0, window: 0 })
+}
+
+pub fn set_relayer_rate_limit(env: &Env, limit: &RelayerRateLimit) {
+    env.storage().instance().set(&rate_limit_key(), limit);
+}
+
+/// Record a fill in the relayer's current window and return the window's fill count
+pub fn record_relayer_fill(env: &Env, relayer: &Address, window: u64) -> u32 {
+    let now = env.ledger().timestamp();
+    let current: Option<FillRateWindow> = env.storage().persistent().get(&relayer_rate_key(relayer));
+    let updated = match current {
+        Some(w) if now < w.window_start + window => FillRateWindow {
+            window_start: w.window_start,
+            count: w.count + 1,
+        },
+        _ => FillRateWindow {
+            window_start: now,
+            count: 1,
+        },
+    };
+    env.storage()
+        .persistent()
+        .set(&relayer_rate_key(relayer), &updated);
+    updated.count
+}
+
+// Pending Intent Cap (0 = unlimited, counts all open intents: Pending or Expired)
+pub fn get_max_pending_intents(env: &Env) -> u32 {
+    env.storage().instance().get(&max_pending_key()).unwrap_or(0)
+}
+
+pub fn set_max_pending_intents(env: &Env, max: u32) {
+    env.storage().instance().set(&max_pending_key(), &max);
+}
+
+pub fn get_pending_intent_count(env: &Env) -> u32 {
+    env.storage().instance().get(&pending_count_key()).unwrap_or(0)
+}
+
+pub fn increment_pending_intents(env: &Env) {
+    let count = get_pending_intent_count(env);
+    env.storage().instance().set(&pending_count_key(), &(count + 1));
+}
+
+pub fn decrement_pending_intents(env: &Env) {
+    let count = get_pending_intent_count(env);
+    env.storage()
+        .instance()
+        .set(&pending_count_key(), &count.saturating_sub(1));
+}
+
+pub fn require_pending_capacity(env: &Env) {
+    let max = get_max_pending_intents(env);
+    if max > 0 && get_pending_intent_count(env) >= max {
+        panic_with_error!(env, PolicyError::TooManyPendingIntents);
+    }
+}
+
+// Intents
+pub fn has_intent(env: &Env, intent_id: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&intent_key(intent_id))
+}
+
+pub fn get_intent(env: &Env, intent_id: &BytesN<32>) -> Result<Intent, Error> {
+    env.storage()
+        .persistent()
+        .get(&intent_key(intent_id))
+        .ok_or(Error::IntentNotFound)
+}
+
+pub fn set_intent(env: &Env, intent_id: &BytesN<32>, intent: &Intent) {
+    env.storage()
+        .persistent()
+        .set(&intent_key(intent_id), intent);
+}
+
+pub fn remove_intent(env: &Env, intent_id: &BytesN<32>) {
+    env.storage().persistent().remove(&intent_key(intent_id));
+    env.storage().persistent().remove(&params_hash_key(intent_id));
+}
+
+/// Hash of the sender and CreateIntentParams an intent was created with
+/// (absent for intents created before it was recorded)
+pub fn get_intent_params_hash(env: &Env, intent_id: &BytesN<32>) -> Option<BytesN<32>> {
+    env.storage().persistent().get(&params_hash_key(intent_id))
+}
+
+pub fn set_intent_params_hash(env: &Env, intent_id: &BytesN<32>, hash: &BytesN<32>) {
+    env.storage().persistent().set(&params_hash_key(intent_id), hash);
+}
+
+/// Raw field map of a stored intent, whatever schema version wrote it
+/// Decoding a struct with the wrong layout traps, so migration works on the map.
+pub fn get_intent_fields(env: &Env, intent_id: &BytesN<32>) -> Result<Map<Symbol, Val>, Error> {
+    env.storage()
+        .persistent()
+        .get(&intent_key(intent_id))
+        .ok_or(Error::IntentNotFound)
+}
+
+#[cfg(test)]
+pub fn set_raw_intent<V: soroban_sdk::IntoVal<Env, Val>>(env: &Env, intent_id: &BytesN<32>, value: &V) {
+    let raw: Val = value.into_val(env);
+    env.storage().persistent().set(&intent_key(intent_id), &raw);
+}
+
+// Sender Intent Index (append-only; read intent status to filter settled entries)
+pub fn get_sender_intents(env: &Env, sender: &Address) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&sender_intents_key(sender))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn push_sender_intent(env: &Env, sender: &Address, intent_id: &BytesN<32>) {
+    let mut intents = get_sender_intents(env, sender);
+    intents.push_back(intent_id.clone());
+    env.storage()
+        .persistent()
+        .set(&sender_intents_key(sender), &intents);
+}
+
+// Sender Lifetime Intent Count (never decremented)
+pub fn get_sender_intent_count(env: &Env, sender: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&sender_intent_count_key(sender))
+        .unwrap_or(0)
+}
+
+pub fn increment_sender_intent_count(env: &Env, sender: &Address) {
+    let count = get_sender_intent_count(env, sender);
+    env.storage()
+        .persistent()
+        .set(&sender_intent_count_key(sender), &(count + 1));
+}
+
+// Token Intent Index: open intents per source token
+// Entries are removed when an intent reaches a terminal status.
+pub fn get_token_intents(env: &Env, token: &Address) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&token_intents_key(token))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn push_token_intent(env: &Env, token: &Address, intent_id: &BytesN<32>) {
+    let mut intents = get_token_intents(env, token);
+    intents.push_back(intent_id.clone());
+    env.storage()
+        .persistent()
+        .set(&token_intents_key(token), &intents);
+}
+
+pub fn remove_token_intent(env: &Env, token: &Address, intent_id: &BytesN<32>) {
+    let mut intents = get_token_intents(env, token);
+    let index = match intents.first_index_of(intent_id) {
+        Some(index) => index,
+        None => return,
+    };
+    intents.remove(index);
+    if intents.is_empty() {
+        env.storage().persistent().remove(&token_intents_key(token));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&token_intents_key(token), &intents);
+    }
+}
+
+// Expiry Buckets: open intents indexed by deadline / EXPIRY_BUCKET_SECS
+// Entries are removed when an intent reaches a terminal status.
+pub fn expiry_bucket_of(deadline: u64) -> u64 {
+    deadline / EXPIRY_BUCKET_SECS
+}
+
+pub fn get_expiry_bucket(env: &Env, bucket: u64) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&expiry_bucket_key(bucket))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn add_to_expiry_bucket(env: &Env, deadline: u64, intent_id: &BytesN<32>) {
+    let bucket = expiry_bucket_of(deadline);
+    let mut intents = get_expiry_bucket(env, bucket);
+    if intents.contains(intent_id) {
+        return;
+    }
+    intents.push_back(intent_id.clone());
+    env.storage()
+        .persistent()
+        .set(&expiry_bucket_key(bucket), &intents);
+}
+
+pub fn remove_from_expiry_bucket(env: &Env, deadline: u64, intent_id: &BytesN<32>) {
+    let bucket = expiry_bucket_of(deadline);
+    let mut intents = get_expiry_bucket(env, bucket);
+    let index = match intents.first_index_of(intent_id) {
+        Some(index) => index,
+        None => return,
+    };
+    intents.remove(index);
+    if intents.is_empty() {
+        env.storage().persistent().remove(&expiry_bucket_key(bucket));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&expiry_bucket_key(bucket), &intents);
+    }
+}
+
+// Relayers (now returns RelayerType)
+pub fn get_relayer_type(env: &Env, relayer: &Address) -> RelayerType {
+    env.storage()
+        .instance()
+        .get(&relayer_key(relayer))
+        .unwrap_or(RelayerType::None)
+}
+
+pub fn set_relayer_type(env: &Env, relayer: &Address, relayer_type: RelayerType) {
+    let previous = get_relayer_type(env, relayer);
+    if previous != relayer_type {
+        // Keep the per-type tallies in step; None is never counted
+        if previous != RelayerType::None {
+            let count = get_relayer_type_count(env, &previous);
+            set_relayer_type_count(env, &previous, count.saturating_sub(1));
+        }
+        if relayer_type != RelayerType::None {
+            let count = get_relayer_type_count(env, &relayer_type);
+            set_relayer_type_count(env, &relayer_type, count + 1);
+        }
+    }
+    env.storage()
+        .instance()
+        .set(&relayer_key(relayer), &relayer_type);
+}
+
+pub fn get_relayer_type_count(env: &Env, relayer_type: &RelayerType) -> u32 {
+    env.storage()
+        .instance()
+        .get(&relayer_count_key(relayer_type.rank()))
+        .unwrap_or(0)
+}
+
+fn set_relayer_type_count(env: &Env, relayer_type: &RelayerType, count: u32) {
+    env.storage()
+        .instance()
+        .set(&relayer_count_key(relayer_type.rank()), &count);
+}
+
+pub fn is_relayer(env: &Env, relayer: &Address) -> bool {
+    get_relayer_type(env, relayer) != RelayerType::None
+}
+
+// Relayer Metadata (endpoint, name) published by the relayer itself
+pub fn get_relayer_metadata(env: &Env, relayer: &Address) -> Option<(String, String)> {
+    env.storage().persistent().get(&relayer_metadata_key(relayer))
+}
+
+pub fn set_relayer_metadata(env: &Env, relayer: &Address, endpoint: &String, name: &String) {
+    env.storage()
+        .persistent()
+        .set(&relayer_metadata_key(relayer), &(endpoint.clone(), name.clone()));
+}
+
+pub fn remove_relayer_metadata(env: &Env, relayer: &Address) {
+    env.storage().persistent().remove(&relayer_metadata_key(relayer));
+}
+
+// Relayer Liveness (heartbeats keyed by bytes32 relayer identity)
+pub const DEFAULT_LIVENESS_WINDOW: u64 = 600;
+
+pub fn get_last_heartbeat(env: &Env, relayer: &BytesN<32>) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&heartbeat_key(relayer))
+        .unwrap_or(0)
+}
+
+pub fn set_last_heartbeat(env: &Env, relayer: &BytesN<32>, timestamp: u64) {
+    env.storage()
+        .persistent()
+        .set(&heartbeat_key(relayer), &timestamp);
+}
+
+pub fn get_live_relayers(env: &Env) -> Vec<BytesN<32>> {
+    env.storage()
+        .instance()
+        .get(&live_relayers_key())
+        .unwrap_or(Vec::new(env))
+}
+
+/// Register a relayer as an auto_assign candidate (bounded by MAX_BATCH_SIZE)
+/// Relayers whose last heartbeat is outside the liveness window are dropped first.
+pub fn add_live_relayer(env: &Env, relayer: &BytesN<32>) -> Result<(), Error> {
+    let now = env.ledger().timestamp();
+    let window = get_liveness_window(env);
+    let mut relayers = Vec::new(env);
+    for candidate in get_live_relayers(env).iter() {
+        if candidate == *relayer || now <= get_last_heartbeat(env, &candidate) + window {
+            relayers.push_back(candidate);
+        }
+    }
+    if !relayers.contains(relayer) {
+        if relayers.len() >= MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+        relayers.push_back(relayer.clone());
+    }
+    env.storage().instance().set(&live_relayers_key(), &relayers);
+    Ok(())
+}
+
+pub fn remove_live_relayer(env: &Env, relayer: &BytesN<32>) {
+    let mut relayers = get_live_relayers(env);
+    if let Some(index) = relayers.first_index_of(relayer) {
+        relayers.remove(index);
+        env.storage().instance().set(&live_relayers_key(), &relayers);
+    }
+}
+
+pub fn get_liveness_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&liveness_window_key())
+        .unwrap_or(DEFAULT_LIVENESS_WINDOW)
+}
+
+pub fn set_liveness_window(env: &Env, window: u64) {
+    env.storage().instance().set(&liveness_window_key(), &window);
+}
+
+// Relayer Stats (completed fills on this chain, keyed by bytes32 relayer identity)
+pub fn get_relayer_fill_count(env: &Env, relayer: &BytesN<32>) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&relayer_fills_key(relayer))
+        .unwrap_or(0)
+}
+
+pub fn set_relayer_fill_count(env: &Env, relayer: &BytesN<32>, count: u64) {
+    env.storage()
+        .persistent()
+        .set(&relayer_fills_key(relayer), &count);
+}
+
+// Relayer Failures (claims that lapsed unfilled, keyed by bytes32 relayer identity)
+pub fn get_relayer_failure_count(env: &Env, relayer: &BytesN<32>) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&relayer_failures_key(relayer))
+        .unwrap_or(0)
+}
+
+pub fn set_relayer_failure_count(env: &Env, relayer: &BytesN<32>, count: u64) {
+    env.storage()
+        .persistent()
+        .set(&relayer_failures_key(relayer), &count);
+}
+
+// Intent Claims (open intents only)
+pub const DEFAULT_CLAIM_WINDOW: u64 = 300;
+pub const CLAIM_PENALTY_PERIOD: u64 = 3600;
+
+pub fn get_intent_claim(env: &Env, intent_id: &BytesN<32>) -> Option<IntentClaim> {
+    env.storage().persistent().get(&claim_key(intent_id))
+}
+
+pub fn set_intent_claim(env: &Env, intent_id: &BytesN<32>, claim: &IntentClaim) {
+    env.storage().persistent().set(&claim_key(intent_id), claim);
+}
+
+pub fn remove_intent_claim(env: &Env, intent_id: &BytesN<32>) {
+    env.storage().persistent().remove(&claim_key(intent_id));
+}
+
+pub fn get_claim_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&claim_window_key())
+        .unwrap_or(DEFAULT_CLAIM_WINDOW)
+}
+
+pub fn set_claim_window(env: &Env, window: u64) {
+    env.storage().instance().set(&claim_window_key(), &window);
+}
+
+// Relayer Auctions (source chain)
+pub fn get_auction_window(env: &Env) -> u64 {
+    env.storage().instance().get(&auction_window_key()).unwrap_or(0)
+}
+
+pub fn set_auction_window(env: &Env, window: u64) {
+    env.storage().instance().set(&auction_window_key(), &window);
+}
+
+pub fn get_bids(env: &Env, intent_id: &BytesN<32>) -> Vec<AuctionBid> {
+    env.storage()
+        .persistent()
+        .get(&bids_key(intent_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_bids(env: &Env, intent_id: &BytesN<32>, bids: &Vec<AuctionBid>) {
+    env.storage().persistent().set(&bids_key(intent_id), bids);
+}
+
+pub fn remove_bids(env: &Env, intent_id: &BytesN<32>) {
+    env.storage().persistent().remove(&bids_key(intent_id));
+}
+
+pub fn get_claim_cooldown_until(env: &Env, relayer: &BytesN<32>) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&claim_cooldown_key(relayer))
+        .unwrap_or(0)
+}
+
+pub fn set_claim_cooldown_until(env: &Env, relayer: &BytesN<32>, until: u64) {
+    env.storage()
+        .persistent()
+        .set(&claim_cooldown_key(relayer), &until);
+}
+
+// Relayer Volume Caps (keyed by bytes32 relayer identity)
+pub fn get_volume_cap(env: &Env, relayer: &BytesN<32>) -> Option<VolumeCap> {
+    env.storage().persistent().get(&volume_cap_key(relayer))
+}
+
+pub fn set_volume_cap(env: &Env, relayer: &BytesN<32>, cap: &VolumeCap) {
+    env.storage().persistent().set(&volume_cap_key(relayer), cap);
+}
+
+pub fn remove_volume_cap(env: &Env, relayer: &BytesN<32>) {
+    env.storage().persistent().remove(&volume_cap_key(relayer));
+}
+
+/// Volume filled in the relayer's current window (0 once the window has elapsed)
+pub fn get_window_volume(env: &Env, relayer: &BytesN<32>, window: u64) -> i128 {
+    let current: Option<VolumeWindow> = env.storage().persistent().get(&volume_window_key(relayer));
+    match current {
+        Some(w) if env.ledger().timestamp() < w.window_start + window => w.volume,
+        _ => 0,
+    }
+}
+
+/// Add filled volume, starting a fresh window if the previous one elapsed
+pub fn add_window_volume(env: &Env, relayer: &BytesN<32>, window: u64, amount: i128) {
+    let now = env.ledger().timestamp();
+    let current: Option<VolumeWindow> = env.storage().persistent().get(&volume_window_key(relayer));
+    let updated = match current {
+        Some(w) if now < w.window_start + window => VolumeWindow {
+            window_start: w.window_start,
+            volume: w.volume + amount,
+        },
+        _ => VolumeWindow {
+            window_start: now,
+            volume: amount,
+        },
+    };
+    env.storage()
+        .persistent()
+        .set(&volume_window_key(relayer), &updated);
+}
+
+// Messenger Adapters (by messengerId)
+pub fn get_messenger_adapter(env: &Env, messenger_id: u32) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get(&messenger_adapter_key(messenger_id))
+}
+
+pub fn set_messenger_adapter(env: &Env, messenger_id: u32, adapter: &Address) {
+    env.storage()
+        .instance()
+        .set(&messenger_adapter_key(messenger_id), adapter);
+    add_messenger_id(env, messenger_id);
+}
+
+// Adapter Source Chains (empty = adapter may deliver from any chain)
+pub fn get_adapter_chains(env: &Env, messenger_id: u32) -> Vec<u64> {
+    env.storage()
+        .instance()
+        .get(&adapter_chains_key(messenger_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_adapter_chains(env: &Env, messenger_id: u32, chains: &Vec<u64>) {
+    if chains.is_empty() {
+        env.storage().instance().remove(&adapter_chains_key(messenger_id));
+    } else {
+        env.storage()
+            .instance()
+            .set(&adapter_chains_key(messenger_id), chains);
+    }
+}
+
+// Messenger Fallbacks (by notification route, i.e. the intent's source chain)
+pub fn get_messenger_fallbacks(env: &Env, chain_id: u64) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&messenger_fallbacks_key(chain_id))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_messenger_fallbacks(env: &Env, chain_id: u64, messenger_ids: &Vec<u32>) {
+    if messenger_ids.is_empty() {
+        env.storage().instance().remove(&messenger_fallbacks_key(chain_id));
+    } else {
+        env.storage()
+            .instance()
+            .set(&messenger_fallbacks_key(chain_id), messenger_ids);
+    }
+}
+
+// Notify Retry Limits (0 = unlimited)
+pub fn get_global_max_retries(env: &Env) -> u32 {
+    env.storage().instance().get(&global_max_retries_key()).unwrap_or(0)
+}
+
+pub fn set_global_max_retries(env: &Env, max: u32) {
+    env.storage().instance().set(&global_max_retries_key(), &max);
+}
+
+pub fn get_max_fill_retries(env: &Env) -> u32 {
+    env.storage().instance().get(&max_fill_retries_key()).unwrap_or(0)
+}
+
+pub fn set_max_fill_retries(env: &Env, max: u32) {
+    env.storage().instance().set(&max_fill_retries_key(), &max);
+}
+
+// Notify Retry Counts (destination chain - per intent ID and per fill hash)
+pub fn get_intent_retry_count(env: &Env, intent_id: &BytesN<32>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&intent_retries_key(intent_id))
+        .unwrap_or(0)
+}
+
+pub fn get_fill_retry_count(env: &Env, fill_hash: &BytesN<32>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&fill_retries_key(fill_hash))
+        .unwrap_or(0)
+}
+
+pub fn increment_retry_counts(env: &Env, intent_id: &BytesN<32>, fill_hash: &BytesN<32>) {
+    let intent_count = get_intent_retry_count(env, intent_id);
+    env.storage()
+        .persistent()
+        .set(&intent_retries_key(intent_id), &(intent_count + 1));
+    let fill_count = get_fill_retry_count(env, fill_hash);
+    env.storage()
+        .persistent()
+        .set(&fill_retries_key(fill_hash), &(fill_count + 1));
+}
+
+// Registered Messenger IDs (ascending; the order retry_notify_all escalates through)
+pub fn get_messenger_ids(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&messenger_ids_key())
+        .unwrap_or(Vec::new(env))
+}
+
+fn add_messenger_id(env: &Env, messenger_id: u32) {
+    let mut ids = get_messenger_ids(env);
+    if let Err(index) = ids.binary_search(messenger_id) {
+        ids.insert(index, messenger_id);
+        env.storage().instance().set(&messenger_ids_key(), &ids);
+    }
+}
+
+// Messenger Sends (disabled while an adapter cutover drains)
+pub fn is_messenger_disabled(env: &Env, messenger_id: u32) -> bool {
+    env.storage()
+        .instance()
+        .get(&messenger_disabled_key(messenger_id))
+        .unwrap_or(false)
+}
+
+pub fn set_messenger_disabled(env: &Env, messenger_id: u32, disabled: bool) {
+    if disabled {
+        env.storage()
+            .instance()
+            .set(&messenger_disabled_key(messenger_id), &true);
+    } else {
+        env.storage()
+            .instance()
+            .remove(&messenger_disabled_key(messenger_id));
+    }
+}
+
+// Fill Messengers (source chain - which messenger delivered the completion)
+pub fn get_fill_messenger(env: &Env, fill_hash: &BytesN<32>) -> Option<u32> {
+    env.storage().persistent().get(&fill_messenger_key(fill_hash))
+}
+
+pub fn set_fill_messenger(env: &Env, fill_hash: &BytesN<32>, messenger_id: u32) {
+    env.storage()
+        .persistent()
+        .set(&fill_messenger_key(fill_hash), &messenger_id);
+}
+
+// Fill Records (destination chain - for double-fill prevention)
+pub fn has_fill_record(env: &Env, fill_hash: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&fill_record_key(fill_hash))
+}
+
+pub fn get_fill_record(env: &Env, fill_hash: &BytesN<32>) -> Option<FillRecord> {
+    env.storage().persistent().get(&fill_record_key(fill_hash))
+}
+
+pub fn set_fill_record(env: &Env, fill_hash: &BytesN<32>, record: &FillRecord) {
+    env.storage()
+        .persistent()
+        .set(&fill_record_key(fill_hash), record);
+}
+
+// Relayer Fill Index (destination chain - fill hashes per relayer)
+pub fn get_relayer_fill_hashes(env: &Env, relayer: &Address) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&relayer_fill_index_key(relayer))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Append a fill hash, pruning the oldest entries beyond the configured limit
+/// A limit of 0 keeps as much history as MAX_BATCH_SIZE allows.
+pub fn push_relayer_fill_hash(env: &Env, relayer: &Address, fill_hash: &BytesN<32>) {
+    let mut hashes = get_relayer_fill_hashes(env, relayer);
+    hashes.push_back(fill_hash.clone());
+
+    let limit = match get_fill_index_limit(env) {
+        0 => MAX_BATCH_SIZE,
+        limit => limit,
+    };
+    while hashes.len() > limit {
+        hashes.pop_front();
+    }
+
+    env.storage()
+        .persistent()
+        .set(&relayer_fill_index_key(relayer), &hashes);
+}
+
+pub fn get_fill_index_limit(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&fill_index_limit_key())
+        .unwrap_or(0)
+}
+
+pub fn set_fill_index_limit(env: &Env, limit: u32) {
+    env.storage().instance().set(&fill_index_limit_key(), &limit);
+}
+
+// Settlement Delay (seconds between notify and relayer payout, 0 = immediate)
+pub fn get_settlement_delay(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&settlement_delay_key())
+        .unwrap_or(0)
+}
+
+pub fn set_settlement_delay(env: &Env, delay: u64) {
+    env.storage().instance().set(&settlement_delay_key(), &delay);
+}
+
+// Pending Settlements (source chain - keyed by fill hash)
+pub fn get_pending_settlement(env: &Env, fill_hash: &BytesN<32>) -> Option<PendingSettlement> {
+    env.storage().persistent().get(&settlement_key(fill_hash))
+}
+
+pub fn set_pending_settlement(env: &Env, fill_hash: &BytesN<32>, settlement: &PendingSettlement) {
+    env.storage()
+        .persistent()
+        .set(&settlement_key(fill_hash), settlement);
+}
+
+pub fn remove_pending_settlement(env: &Env, fill_hash: &BytesN<32>) {
+    env.storage().persistent().remove(&settlement_key(fill_hash));
+}
+
+// Unfillable Reports (latest report per intent)
+pub fn get_unfillable_report(env: &Env, intent_id: &BytesN<32>) -> Option<UnfillableReport> {
+    env.storage().persistent().get(&unfillable_key(intent_id))
+}
+
+pub fn set_unfillable_report(env: &Env, intent_id: &BytesN<32>, report: &UnfillableReport) {
+    env.storage()
+        .persistent()
+        .set(&unfillable_key(intent_id), report);
+}
+
+// Cross-chain Asset Equivalence (local token -> remote token per chain)
+pub fn get_asset_equivalence(env: &Env, local_token: &Address, chain_id: u64) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&asset_equiv_key(local_token, chain_id))
+}
+
+pub fn set_asset_equivalence(env: &Env, local_token: &Address, chain_id: u64, remote_token: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .set(&asset_equiv_key(local_token, chain_id), remote_token);
+}
+
+pub fn is_strict_asset_match(env: &Env) -> bool {
+    env.storage().instance().get(&strict_asset_key()).unwrap_or(false)
+}
+
+pub fn set_strict_asset_match(env: &Env, strict: bool) {
+    env.storage().instance().set(&strict_asset_key(), &strict);
+}
+
+// Destination Token Allow-list (per destination chain)
+pub fn is_dest_token_allowed(env: &Env, chain_id: u64, token: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&dest_token_key(chain_id, token))
+}
+
+pub fn set_dest_token_allowed(env: &Env, chain_id: u64, token: &BytesN<32>, allowed: bool) {
+    if allowed {
+        env.storage()
+            .persistent()
+            .set(&dest_token_key(chain_id, token), &true);
+    } else {
+        env.storage()
+            .persistent()
+            .remove(&dest_token_key(chain_id, token));
+    }
+}
+
+// Source Token Allow-list (empty = any token may be escrowed)
+pub fn is_token_listed(env: &Env, token: &Address) -> bool {
+    env.storage().persistent().has(&allowed_token_key(token))
+}
+
+pub fn get_allowed_token_count(env: &Env) -> u32 {
+    env.storage().instance().get(&allowed_token_count_key()).unwrap_or(0)
+}
+
+pub fn set_token_listed(env: &Env, token: &Address, listed: bool) {
+    if listed == is_token_listed(env, token) {
+        return;
+    }
+    let count = get_allowed_token_count(env);
+    if listed {
+        env.storage().persistent().set(&allowed_token_key(token), &true);
+        env.storage().instance().set(&allowed_token_count_key(), &(count + 1));
+    } else {
+        env.storage().persistent().remove(&allowed_token_key(token));
+        env.storage().instance().set(&allowed_token_count_key(), &(count - 1));
+    }
+}
+
+pub fn is_restrict_dest_tokens(env: &Env) -> bool {
+    env.storage().instance().get(&restrict_dest_key()).unwrap_or(false)
+}
+
+pub fn set_restrict_dest_tokens(env: &Env, restrict: bool) {
+    env.storage().instance().set(&restrict_dest_key(), &restrict);
+}
+
+// Receiver Blacklist (destination chain - compliance blocks)
+pub fn is_receiver_blocked(env: &Env, receiver: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&blocked_receiver_key(receiver))
+}
+
+pub fn set_receiver_blocked(env: &Env, receiver: &BytesN<32>, blocked: bool) {
+    if blocked {
+        env.storage()
+            .persistent()
+            .set(&blocked_receiver_key(receiver), &true);
+    } else {
+        env.storage()
+            .persistent()
+            .remove(&blocked_receiver_key(receiver));
+    }
+}
+
+// Frozen Intents (admin hold on a single intent)
+pub fn is_intent_frozen(env: &Env, intent_id: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&frozen_key(intent_id))
+}
+
+pub fn set_intent_frozen(env: &Env, intent_id: &BytesN<32>, frozen: bool) {
+    if frozen {
+        env.storage().persistent().set(&frozen_key(intent_id), &true);
+    } else {
+        env.storage().persistent().remove(&frozen_key(intent_id));
+    }
+}
+
+// Trusted Contracts
+pub fn get_trusted_contract(env: &Env, chain_name: &String) -> Result<String, Error> {
+    env.storage()
+        .instance()
+        .get(&trusted_key(chain_name))
+        .ok_or(Error::UntrustedSource)
+}
+
+pub fn set_trusted_contract_storage(env: &Env, chain_name: &String, contract_address: &String) {
+    env.storage()
+        .instance()
+        .set(&trusted_key(chain_name), contract_address);
+}
+
+// Chain Names (chain_id -> chain_name mapping)
+pub fn get_chain_name(env: &Env, chain_id: u64) -> Result<String, Error> {
+    env.storage()
+        .instance()
+        .get(&chain_name_key(chain_id))
+        .ok_or(Error::ChainNotFound)
+}
+
+pub fn set_chain_name(env: &Env, chain_id: u64, chain_name: &String) {
+    env.storage()
+        .instance()
+        .set(&chain_name_key(chain_id), chain_name);
+}
+
+// Chain Types (chain_id -> ChainType; unset chains are Evm)
+pub fn get_chain_type(env: &Env, chain_id: u64) -> ChainType {
+    env.storage()
+        .instance()
+        .get(&chain_type_key(chain_id))
+        .unwrap_or(ChainType::Evm)
+}
+
+pub fn set_chain_type(env: &Env, chain_id: u64, chain_type: &ChainType) {
+    env.storage()
+        .instance()
+        .set(&chain_type_key(chain_id), chain_type);
+}
+
+// Outbound notify payload version (v1 unless the owner opts into v2)
+pub fn is_notify_payload_v2(env: &Env) -> bool {
+    env.storage().instance().get(&notify_v2_key()).unwrap_or(false)
+}
+
+pub fn set_notify_payload_v2(env: &Env, enabled: bool) {
+    env.storage().instance().set(&notify_v2_key(), &enabled);
+}
+
+// Accumulated Fees
+pub fn get_accumulated_fees(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
         .get(&fees_key(token))
         .unwrap_or(0)
 }
 
 pub fn set_accumulated_fees(env: &Env, token: &Address, amount: i128) {
     env.storage().persistent().set(&fees_key(token), &amount);
+    if amount > 0 {
+        add_fee_token(env, token);
+    }
+}
+
+// Fee Tokens (every token that has ever accrued fees)
+pub fn get_fee_tokens(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&fee_tokens_key())
+        .unwrap_or(Vec::new(env))
+}
+
+fn add_fee_token(env: &Env, token: &Address) {
+    let mut tokens = get_fee_tokens(env);
+    if !tokens.contains(token) {
+        tokens.push_back(token.clone());
+        env.storage().instance().set(&fee_tokens_key(), &tokens);
+    }
+}
+
+// Escrowed Intent Collateral (source tokens held for unsettled intents)
+pub fn get_escrowed(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&escrow_key(token))
+        .unwrap_or(0)
+}
+
+pub fn add_escrowed(env: &Env, token: &Address, delta: i128) {
+    let amount = get_escrowed(env, token) + delta;
+    env.storage().persistent().set(&escrow_key(token), &amount);
+}
+
+// Pending Settlement Totals (payouts owed to relayers but not yet released)
+pub fn get_settlement_owed(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&settlement_owed_key(token))
+        .unwrap_or(0)
+}
+
+pub fn add_settlement_owed(env: &Env, token: &Address, delta: i128) {
+    let amount = get_settlement_owed(env, token) + delta;
+    env.storage()
+        .persistent()
+        .set(&settlement_owed_key(token), &amount);
+}
+
+// Relayer Payouts (pull model; totals also count toward settlement owed)
+pub fn is_pull_payouts(env: &Env) -> bool {
+    env.storage().instance().get(&pull_payouts_key()).unwrap_or(false)
+}
+
+pub fn set_pull_payouts(env: &Env, enabled: bool) {
+    env.storage().instance().set(&pull_payouts_key(), &enabled);
+}
+
+pub fn get_payout_owed(env: &Env, payee: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&payout_owed_key(payee, token))
+        .unwrap_or(0)
+}
+
+pub fn set_payout_owed(env: &Env, payee: &Address, token: &Address, amount: i128) {
+    if amount == 0 {
+        env.storage()
+            .persistent()
+            .remove(&payout_owed_key(payee, token));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&payout_owed_key(payee, token), &amount);
+    }
+}
+
+/// Part of a payee's owed payout reserved out of accumulated fees
+pub fn get_payout_reserved(env: &Env, payee: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&payout_reserved_key(payee, token))
+        .unwrap_or(0)
+}
+
+pub fn set_payout_reserved(env: &Env, payee: &Address, token: &Address, amount: i128) {
+    if amount == 0 {
+        env.storage()
+            .persistent()
+            .remove(&payout_reserved_key(payee, token));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&payout_reserved_key(payee, token), &amount);
+    }
+}
+
+// Reserved Fees (rebates and overpay reimbursements in held payouts)
+// Stay counted in accumulated fees until the payout is released or claimed.
+pub fn get_reserved_fees(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&reserved_fees_key(token))
+        .unwrap_or(0)
+}
+
+pub fn add_reserved_fees(env: &Env, token: &Address, delta: i128) {
+    let amount = get_reserved_fees(env, token) + delta;
+    env.storage()
+        .persistent()
+        .set(&reserved_fees_key(token), &amount);
+}
+
+// Relayer Bonds (refundable collateral; totals count toward obligations)
+pub fn get_min_bond(env: &Env) -> Option<(Address, i128)> {
+    env.storage().instance().get(&min_bond_key())
+}
+
+pub fn set_min_bond(env: &Env, token: &Address, amount: i128) {
+    env.storage()
+        .instance()
+        .set(&min_bond_key(), &(token.clone(), amount));
+}
+
+pub fn remove_min_bond(env: &Env) {
+    env.storage().instance().remove(&min_bond_key());
+}
+
+pub fn get_bond(env: &Env, relayer: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&bond_key(relayer, token))
+        .unwrap_or(0)
+}
+
+/// Set a relayer's bond and keep the token's bonded total in step
+pub fn set_bond(env: &Env, relayer: &Address, token: &Address, amount: i128) {
+    let delta = amount - get_bond(env, relayer, token);
+    if amount == 0 {
+        env.storage().persistent().remove(&bond_key(relayer, token));
+    } else {
+        env.storage()
+            .persistent()
+            .set(&bond_key(relayer, token), &amount);
+    }
+    let total = get_total_bonded(env, token) + delta;
+    env.storage()
+        .persistent()
+        .set(&total_bonded_key(token), &total);
+}
+
+pub fn get_total_bonded(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&total_bonded_key(token))
+        .unwrap_or(0)
+}
+
+// Fee Tiers (ascending by min_volume) and cumulative filled volume per token
+pub fn get_fee_tiers(env: &Env) -> Vec<FeeTier> {
+    env.storage()
+        .instance()
+        .get(&fee_tiers_key())
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_fee_tiers(env: &Env, tiers: &Vec<FeeTier>) {
+    env.storage().instance().set(&fee_tiers_key(), tiers);
+}
+
+pub fn get_total_volume(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&total_volume_key(token))
+        .unwrap_or(0)
+}
+
+pub fn add_total_volume(env: &Env, token: &Address, amount: i128) {
+    let total = get_total_volume(env, token) + amount;
+    env.storage()
+        .persistent()
+        .set(&total_volume_key(token), &total);
+}
+
+// Lifetime relayer payouts (scheduled settlements and pull credits included)
+pub fn get_total_relayer_payouts(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&total_payouts_key(token))
+        .unwrap_or(0)
+}
+
+pub fn add_total_relayer_payouts(env: &Env, token: &Address, amount: i128) {
+    let total = get_total_relayer_payouts(env, token) + amount;
+    env.storage()
+        .persistent()
+        .set(&total_payouts_key(token), &total);
+}
+
+// Fee Withdrawal History (lifetime revenue = accumulated + withdrawn)
+pub fn get_total_withdrawn(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&total_withdrawn_key(token))
+        .unwrap_or(0)
+}
+
+pub fn add_total_withdrawn(env: &Env, token: &Address, amount: i128) {
+    let total = get_total_withdrawn(env, token) + amount;
+    env.storage()
+        .persistent()
+        .set(&total_withdrawn_key(token), &total);
 }
 
 // Outbound Messages (for testing)
@@ -249,6 +1769,7 @@ pub fn store_outbound_message(
         destination_chain: destination_chain.clone(),
         destination_address: destination_address.clone(),
         payload: payload.clone(),
+        created_at: env.ledger().timestamp(),
     };
 
     let mut messages: Vec<OutboundMessage> = env
@@ -267,3 +1788,19 @@ pub fn get_outbound_messages(env: &Env) -> Vec<OutboundMessage> {
         .get(&outbound_key())
         .unwrap_or(Vec::new(env))
 }
+
+/// Drop outbound messages stored before `cutoff`; returns how many were removed
+pub fn purge_outbound_messages(env: &Env, cutoff: u64) -> u32 {
+    let messages = get_outbound_messages(env);
+    let mut kept: Vec<OutboundMessage> = Vec::new(env);
+    for message in messages.iter() {
+        if message.created_at >= cutoff {
+            kept.push_back(message);
+        }
+    }
+    let removed = messages.len() - kept.len();
+    if removed > 0 {
+        env.storage().temporary().set(&outbound_key(), &kept);
+    }
+    removed
+}
@@ -1,15 +1,130 @@
 #![cfg(test)]
 
 use super::*;
-use crate::types::{CreateIntentParams, IntentStatus, RelayerType};
+use crate::types::{CreateIntentParams, HashAlgo, IntentStatus, RelayerType};
 use soroban_sdk::{
-    testutils::{Address as _, Ledger, LedgerInfo},
+    testutils::{Address as _, Events, Ledger, LedgerInfo},
     token::{Client as TokenClient, StellarAssetClient},
-    Address, BytesN, Env, String,
+    contract, contractimpl, contracttype, symbol_short, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Val,
 };
 
+/// Intent layout written by contract versions before schema_version existed
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct IntentV0 {
+    pub intent_id: BytesN<32>,
+    pub sender: Address,
+    pub refund_address: Address,
+    pub source_token: Address,
+    pub source_amount: i128,
+    pub destination_chain_id: u64,
+    pub destination_token: BytesN<32>,
+    pub receiver: BytesN<32>,
+    pub receiver_is_account: bool,
+    pub destination_amount: i128,
+    pub deadline: u64,
+    pub created_at: u64,
+    pub status: IntentStatus,
+    pub relayer: BytesN<32>,
+}
+
+/// Messenger adapter stub that accepts every outbound message
+#[contract]
+pub struct MockAdapter;
+
+#[contractimpl]
+impl MockAdapter {
+    pub fn send_msg(_env: Env, _destination_chain_id: u64, _payload: Bytes) {}
+}
+
+/// Messenger adapter stub whose bridge is down; every send traps
+/// Lives in its own module: contractimpl exports collide with MockAdapter's send_msg.
+mod failing_adapter {
+    use soroban_sdk::{contract, contractimpl, Bytes, Env};
+
+    #[contract]
+    pub struct FailingAdapter;
+
+    #[contractimpl]
+    impl FailingAdapter {
+        pub fn send_msg(_env: Env, destination_chain_id: u64, _payload: Bytes) {
+            // Chain 0 is the registration probe, which conforming adapters accept
+            if destination_chain_id != 0 {
+                panic!("bridge unavailable");
+            }
+        }
+    }
+}
+use failing_adapter::FailingAdapter;
+
+/// Token that only tracks credited balances, so account (G...) receivers
+/// can be paid without a trustline
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn transfer(env: Env, _from: Address, to: Address, amount: i128) {
+        let balance: i128 = env.storage().persistent().get(&to).unwrap_or(0);
+        env.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&id).unwrap_or(0)
+    }
+}
+
+/// Token that burns 1% of every transfer
+mod fee_on_transfer_token {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct FeeOnTransferToken;
+
+    #[contractimpl]
+    impl FeeOnTransferToken {
+        pub fn mint(env: Env, to: Address, amount: i128) {
+            let balance = Self::balance(env.clone(), to.clone());
+            env.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+            let from_balance = Self::balance(env.clone(), from.clone());
+            env.storage().persistent().set(&from, &(from_balance - amount));
+            Self::mint(env, to, amount - amount / 100);
+        }
+
+        pub fn balance(env: Env, id: Address) -> i128 {
+            env.storage().persistent().get(&id).unwrap_or(0)
+        }
+    }
+}
+use fee_on_transfer_token::{FeeOnTransferToken, FeeOnTransferTokenClient};
+
+/// Token whose transfer calls back into the intents contract's refund
+mod reentrant_token {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, IntoVal, Symbol, Val};
+
+    #[contract]
+    pub struct ReentrantToken;
+
+    #[contractimpl]
+    impl ReentrantToken {
+        pub fn set_target(env: Env, target: Address) {
+            env.storage().instance().set(&symbol_short!("TARGET"), &target);
+        }
+
+        pub fn transfer(env: Env, from: Address, _to: Address, _amount: i128) {
+            let target: Address = env.storage().instance().get(&symbol_short!("TARGET")).unwrap();
+            let args = (from, BytesN::from_array(&env, &[0u8; 32])).into_val(&env);
+            env.invoke_contract::<Val>(&target, &Symbol::new(&env, "refund"), args);
+        }
+    }
+}
+use reentrant_token::{ReentrantToken, ReentrantTokenClient};
+
 fn create_token_contract<'a>(env: &Env, admin: &Address) -> (Address, TokenClient<'a>) {
-    let contract_address = env.register_stellar_asset_contract(admin.clone());
+    let contract_address = env.register_stellar_asset_contract_v2(admin.clone()).address();
     (
         contract_address.clone(),
         TokenClient::new(env, &contract_address),
@@ -53,9 +168,10 @@ fn setup_env() -> (
 
     // Initialize with chain_id (Stellar = 1500)
     let client = RozoIntentsContractClient::new(&env, &contract);
-    client.initialize(&owner, &owner, &1500u64);
+    client.initialize(&owner, &owner, &1500u64, &HashAlgo::Sha256);
 
     // Configure
+    client.arm_emergency(&owner);
     client.set_protocol_fee(&owner, &3); // 3 bps
     client.add_relayer(&owner, &relayer, &RelayerType::External);
     client.set_trusted_contract(
@@ -63,8 +179,8 @@ fn setup_env() -> (
         &String::from_str(&env, "base"),
         &String::from_str(&env, "0x123..."),
     );
-    client.set_chain_id_to_name(&owner, &8453u64, &String::from_str(&env, "base"));
-    client.set_chain_id_to_name(&owner, &1500u64, &String::from_str(&env, "stellar"));
+    client.set_chain_id_to_name(&owner, &8453u64, &String::from_str(&env, "base"), &false);
+    client.set_chain_id_to_name(&owner, &1500u64, &String::from_str(&env, "stellar"), &false);
 
     (
         env,
@@ -87,6 +203,138 @@ fn address_to_bytes32(env: &Env, _addr: &Address) -> BytesN<32> {
     BytesN::from_array(env, &[2u8; 32])
 }
 
+fn set_timestamp(env: &Env, timestamp: u64) {
+    env.ledger().set(LedgerInfo {
+        timestamp,
+        ..env.ledger().get()
+    });
+}
+
+/// Register a MockAdapter as messenger 1
+fn register_mock_adapter(env: &Env, contract: &Address, owner: &Address) -> Address {
+    let adapter = env.register_contract(None, MockAdapter);
+    RozoIntentsContractClient::new(env, contract).set_msger_adapter(owner, &1u32, &adapter);
+    adapter
+}
+
+/// IntentData for an intent created on Base (8453) to be filled on this chain (1500)
+fn incoming_intent_data(
+    env: &Env,
+    intent_id: &BytesN<32>,
+    receiver: &Address,
+    token: &Address,
+) -> IntentData {
+    IntentData {
+        intent_id: intent_id.clone(),
+        sender: BytesN::from_array(env, &[4u8; 32]),
+        refund_address: BytesN::from_array(env, &[4u8; 32]),
+        source_token: BytesN::from_array(env, &[6u8; 32]),
+        source_amount: 1_000_000_000i128,
+        source_chain_id: 8453u64,
+        destination_chain_id: 1500u64,
+        destination_token: crate::address_to_bytes32(env, token),
+        receiver: crate::address_to_bytes32(env, receiver),
+        destination_amount: 990_000_000i128,
+        deadline: 2000u64,
+        created_at: 900u64,
+        relayer: zero_bytes32(env),
+        receiver_is_account: false,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
+    }
+}
+
+/// Topics and data of the most recent event the contract published under `name`
+fn last_event(env: &Env, contract: &Address, name: &str) -> (soroban_sdk::Vec<Val>, Val) {
+    let name = Symbol::new(env, name);
+    let (_, topics, data) = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(emitter, topics, _)| {
+            *emitter == *contract && Symbol::try_from_val(env, &topics.get(0).unwrap()).ok() == Some(name.clone())
+        })
+        .last()
+        .unwrap();
+    (topics, data)
+}
+
+/// Compute the fill hash the contract expects for a stored intent
+fn expected_fill_hash(env: &Env, contract: &Address, intent_id: &BytesN<32>) -> BytesN<32> {
+    env.as_contract(contract, || {
+        let intent = storage::get_intent(env, intent_id).unwrap();
+        compute_fill_hash(env, &intent_data_from_intent(env, &intent))
+    })
+}
+
+/// Deliver a matching notify payload for a stored intent via messenger 1
+/// Pays out to `payee` (a contract address) and returns the fill hash used.
+fn notify_matching_fill(
+    env: &Env,
+    contract: &Address,
+    adapter: &Address,
+    intent_id: &BytesN<32>,
+    payee: &Address,
+) -> BytesN<32> {
+    let client = RozoIntentsContractClient::new(env, contract);
+    let fill_hash = expected_fill_hash(env, contract, intent_id);
+    let intent = client.get_intent(intent_id);
+    let payload = encode_notify_payload(
+        env,
+        intent_id,
+        &fill_hash,
+        &crate::address_to_bytes32(env, payee),
+        &BytesN::from_array(env, &[3u8; 32]),
+        intent.destination_amount,
+        false,
+    )
+    .unwrap();
+    client.notify(adapter, &1u32, &8453u64, &payload);
+    fill_hash
+}
+
+/// Complete one standard intent via notify so the contract accrues 300_000 in fees
+fn accrue_fees(
+    env: &Env,
+    contract: &Address,
+    owner: &Address,
+    sender: &Address,
+    receiver: &Address,
+    token: &Address,
+) {
+    let adapter = register_mock_adapter(env, contract, owner);
+    let client = RozoIntentsContractClient::new(env, contract);
+    let intent_id = BytesN::from_array(env, &[77u8; 32]);
+    client.create_intent(sender, &default_params(env, &intent_id, sender, receiver, token));
+    notify_matching_fill(env, contract, &adapter, &intent_id, &Address::generate(env));
+}
+
+/// Standard open-relayer intent: 100 USDC -> 99 USDC on Base, deadline 2000
+fn default_params(
+    env: &Env,
+    intent_id: &BytesN<32>,
+    sender: &Address,
+    receiver: &Address,
+    token: &Address,
+) -> CreateIntentParams {
+    CreateIntentParams {
+        intent_id: intent_id.clone(),
+        source_token: token.clone(),
+        source_amount: 1_000_000_000i128,
+        destination_chain_id: 8453u64,
+        destination_token: address_to_bytes32(env, token),
+        receiver: address_to_bytes32(env, receiver),
+        receiver_is_account: false,
+        destination_amount: 990_000_000i128,
+        deadline: 2000u64,
+        refund_address: sender.clone(),
+        relayer: zero_bytes32(env),
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
+    }
+}
+
 #[test]
 fn test_initialize() {
     let env = Env::default();
@@ -96,12 +344,14 @@ fn test_initialize() {
 
     let contract = env.register_contract(None, RozoIntentsContract);
     let client = RozoIntentsContractClient::new(&env, &contract);
+    assert_eq!(client.try_get_owner(), Err(Ok(Error::NotInitialized)));
 
     // Initialize with chain_id
-    client.initialize(&owner, &owner, &1500u64);
+    client.initialize(&owner, &owner, &1500u64, &HashAlgo::Sha256);
+    assert_eq!(client.get_owner(), owner);
 
     // Should not be able to initialize again
-    let result = client.try_initialize(&owner, &owner, &1500u64);
+    let result = client.try_initialize(&owner, &owner, &1500u64, &HashAlgo::Sha256);
     assert!(result.is_err());
 }
 
@@ -134,6 +384,9 @@ fn test_create_intent() {
         deadline: 2000u64,
         refund_address: sender.clone(),
         relayer: zero_relayer, // open to any relayer
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
     };
     client.create_intent(&sender, &params);
 
@@ -173,6 +426,9 @@ fn test_create_intent_with_assigned_relayer() {
         deadline: 2000u64,
         refund_address: sender.clone(),
         relayer: relayer_bytes.clone(), // assigned relayer
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
     };
     client.create_intent(&sender, &params);
 
@@ -213,6 +469,9 @@ fn test_refund_after_deadline() {
         deadline: 2000u64,
         refund_address: sender.clone(),
         relayer: zero_relayer,
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
     };
     client.create_intent(&sender, &params);
 
@@ -318,6 +577,9 @@ fn test_admin_refund() {
         deadline: 2000u64,
         refund_address: sender.clone(),
         relayer: zero_relayer,
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
     };
     client.create_intent(&sender, &params);
 
@@ -358,6 +620,9 @@ fn test_admin_set_intent_status() {
         deadline: 2000u64,
         refund_address: sender.clone(),
         relayer: zero_relayer,
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
     };
     client.create_intent(&sender, &params);
 
@@ -366,6 +631,14 @@ fn test_admin_set_intent_status() {
 
     let intent = client.get_intent(&intent_id);
     assert_eq!(intent.status, IntentStatus::Failed);
+    // Failed intents keep their escrow until cleanup_failed
+    assert_eq!(client.get_obligations(&token), 1_000_000_000i128);
+
+    // Resolving off-chain releases the escrow; reopening counts it again
+    client.set_intent_status(&owner, &intent_id, &IntentStatus::Filled);
+    assert_eq!(client.get_obligations(&token), 0);
+    client.set_intent_status(&owner, &intent_id, &IntentStatus::Pending);
+    assert_eq!(client.get_obligations(&token), 1_000_000_000i128);
 }
 
 #[test]
@@ -397,6 +670,9 @@ fn test_admin_set_intent_relayer() {
         deadline: 2000u64,
         refund_address: sender.clone(),
         relayer: zero_relayer,
+        referrer: None,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
     };
     client.create_intent(&sender, &params);
 
@@ -406,3 +682,3652 @@ fn test_admin_set_intent_relayer() {
     let intent = client.get_intent(&intent_id);
     assert_eq!(intent.relayer, new_relayer);
 }
+
+#[test]
+fn test_create_intent_idempotent_fresh() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+
+    let params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    assert!(client.create_intent_idempotent(&sender, &params));
+
+    let intent = client.get_intent(&intent_id);
+    assert_eq!(intent.status, IntentStatus::Pending);
+}
+
+#[test]
+fn test_create_intent_idempotent_retry_is_noop() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+
+    let params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    client.create_intent(&sender, &params);
+    let balance_after_create = token_client.balance(&sender);
+
+    // Identical retry succeeds without pulling tokens again
+    assert!(!client.create_intent_idempotent(&sender, &params));
+    assert_eq!(token_client.balance(&sender), balance_after_create);
+}
+
+#[test]
+fn test_create_intent_idempotent_conflict() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+
+    let params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    client.create_intent(&sender, &params);
+
+    // Same ID, different amount
+    let mut conflicting = params.clone();
+    conflicting.destination_amount = 980_000_000i128;
+    let result = client.try_create_intent_idempotent(&sender, &conflicting);
+    assert_eq!(result, Err(Ok(Error::IntentAlreadyExists)));
+}
+
+#[test]
+fn test_create_intent_idempotent_retry_fee_on_transfer_token() {
+    let (env, contract, _owner, sender, receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let token = env.register_contract(None, FeeOnTransferToken);
+    let token_client = FeeOnTransferTokenClient::new(&env, &token);
+    token_client.mint(&sender, &10_000_000_000i128);
+
+    let intent_id = generate_intent_id(&env);
+    let params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    assert!(client.create_intent_idempotent(&sender, &params));
+    let balance_after_create = token_client.balance(&sender);
+
+    // The stored source_amount is net of the transfer fee, but the retry still matches
+    assert_eq!(client.get_intent(&intent_id).source_amount, 990_000_000i128);
+    assert!(!client.create_intent_idempotent(&sender, &params));
+    assert_eq!(token_client.balance(&sender), balance_after_create);
+}
+
+#[test]
+fn test_create_intent_idempotent_retry_after_extend_deadline() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+
+    let params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    client.create_intent(&sender, &params);
+    client.extend_deadline(&sender, &intent_id, &(params.deadline + 1000));
+
+    assert!(!client.create_intent_idempotent(&sender, &params));
+
+    // A retry carrying the extended deadline isn't the original request
+    let mut extended = params.clone();
+    extended.deadline += 1000;
+    let result = client.try_create_intent_idempotent(&sender, &extended);
+    assert_eq!(result, Err(Ok(Error::IntentAlreadyExists)));
+}
+
+#[test]
+fn test_pause_reason() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    assert_eq!(client.get_pause_reason(), None);
+
+    let reason = String::from_str(&env, "investigating messenger outage");
+    client.pause(&owner, &reason);
+    assert!(client.is_paused());
+    assert_eq!(client.get_pause_reason(), Some(reason));
+
+    // New intents are rejected while paused
+    set_timestamp(&env, 1000);
+    let params = default_params(&env, &generate_intent_id(&env), &sender, &receiver, &token);
+    let result = client.try_create_intent(&sender, &params);
+    assert_eq!(result, Err(Ok(Error::Paused)));
+
+    client.unpause(&owner);
+    assert!(!client.is_paused());
+    assert_eq!(client.get_pause_reason(), None);
+}
+
+#[test]
+fn test_preview_notify_would_fill() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let fill_hash = expected_fill_hash(&env, &contract, &intent_id);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &fill_hash,
+        &repayment,
+        &repayment,
+        990_000_000i128,
+        true,
+    )
+    .unwrap();
+
+    let contract_balance = token_client.balance(&contract);
+    assert_eq!(client.preview_notify(&payload), IntentStatus::Filled);
+
+    // Nothing changed
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+    assert_eq!(token_client.balance(&contract), contract_balance);
+}
+
+#[test]
+fn test_preview_notify_would_fail_on_mismatch() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let wrong_hash = BytesN::from_array(&env, &[9u8; 32]);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &wrong_hash,
+        &repayment,
+        &repayment,
+        990_000_000i128,
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(client.preview_notify(&payload), IntentStatus::Failed);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+}
+
+#[test]
+fn test_preview_notify_frozen_matches_notify() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    let intent_id = generate_intent_id(&env);
+    set_timestamp(&env, 1000);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    client.freeze_intent(&owner, &intent_id);
+
+    let fill_hash = expected_fill_hash(&env, &contract, &intent_id);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &fill_hash,
+        &repayment,
+        &repayment,
+        990_000_000i128,
+        true,
+    )
+    .unwrap();
+
+    // Settlement refuses a frozen intent, so the preview does too
+    assert_eq!(client.try_preview_notify(&payload), Err(Ok(Error::IntentFrozen)));
+    assert_eq!(client.try_notify(&adapter, &1u32, &8453u64, &payload), Err(Ok(Error::IntentFrozen)));
+
+    client.unfreeze_intent(&owner, &intent_id);
+    assert_eq!(client.preview_notify(&payload), IntentStatus::Filled);
+}
+
+#[test]
+fn test_preview_notify_checks_relayer_type() {
+    let (env, contract, owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    client.set_chain_type(&owner, &1600u64, &ChainType::Stellar);
+
+    let intent_id = generate_intent_id(&env);
+    let mut params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    params.destination_chain_id = 1600u64;
+    params.min_relayer_type = RelayerType::Rozo;
+    client.create_intent(&sender, &params);
+
+    // Correct hash and amount, but reported by an External relayer
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &expected_fill_hash(&env, &contract, &intent_id),
+        &repayment,
+        &crate::address_to_bytes32(&env, &relayer),
+        params.destination_amount,
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(client.preview_notify(&payload), IntentStatus::Failed);
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Failed);
+}
+
+#[test]
+fn test_auto_assign_picks_live_relayer_with_most_fills() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let stale = Address::generate(&env);
+    let steady = Address::generate(&env);
+    let busy = Address::generate(&env);
+    for r in [&stale, &steady, &busy] {
+        client.add_relayer(&owner, r, &RelayerType::External);
+    }
+
+    // Stale relayer checks in long before the others
+    set_timestamp(&env, 100);
+    client.heartbeat(&stale);
+    set_timestamp(&env, 1000);
+    client.heartbeat(&steady);
+    client.heartbeat(&busy);
+
+    let stale_bytes = crate::address_to_bytes32(&env, &stale);
+    let steady_bytes = crate::address_to_bytes32(&env, &steady);
+    let busy_bytes = crate::address_to_bytes32(&env, &busy);
+    env.as_contract(&contract, || {
+        storage::set_relayer_fill_count(&env, &stale_bytes, 9);
+        storage::set_relayer_fill_count(&env, &steady_bytes, 2);
+        storage::set_relayer_fill_count(&env, &busy_bytes, 5);
+    });
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let assigned = client.auto_assign(&owner, &intent_id);
+    assert_eq!(assigned, busy_bytes);
+    assert_eq!(client.get_intent(&intent_id).relayer, busy_bytes);
+
+    // Removed relayers are no longer candidates
+    client.remove_relayer(&owner, &busy);
+    let next_id = BytesN::from_array(&env, &[116u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &next_id, &sender, &receiver, &token));
+    assert_eq!(client.auto_assign(&owner, &next_id), steady_bytes);
+}
+
+#[test]
+fn test_heartbeat_prunes_stale_live_relayers() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 100);
+    for _ in 0..MAX_BATCH_SIZE {
+        let r = Address::generate(&env);
+        client.add_relayer(&owner, &r, &RelayerType::External);
+        client.heartbeat(&r);
+    }
+
+    // The list is full of live relayers
+    let late = Address::generate(&env);
+    client.add_relayer(&owner, &late, &RelayerType::External);
+    assert_eq!(client.try_heartbeat(&late), Err(Ok(Error::BatchTooLarge)));
+
+    // Once they go quiet their slots free up
+    set_timestamp(&env, 100 + client.get_liveness_window() + 1);
+    client.heartbeat(&late);
+}
+
+#[test]
+fn test_encode_notify_payload_rejects_negative_amount() {
+    let env = Env::default();
+    let id = BytesN::from_array(&env, &[1u8; 32]);
+
+    // A negative i128 would be zero-padded into a huge positive amount on decode
+    let result = encode_notify_payload(&env, &id, &id, &id, &id, -1i128, false);
+    assert_eq!(result, Err(Error::InvalidAmount));
+
+    let payload = encode_notify_payload(&env, &id, &id, &id, &id, 0i128, false).unwrap();
+    assert_eq!(payload.len(), NOTIFY_PAYLOAD_LEN);
+}
+
+#[test]
+fn test_get_fills_by_relayer() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let first = incoming_intent_data(&env, &BytesN::from_array(&env, &[11u8; 32]), &receiver, &token);
+    let second = incoming_intent_data(&env, &BytesN::from_array(&env, &[12u8; 32]), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    client.fill_and_notify(&relayer, &first, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    client.fill_and_notify(&relayer, &second, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    let fills = client.get_fills_by_relayer(&relayer);
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills.get(0).unwrap(), sha256_fill_hash(&env, &first));
+    assert_eq!(fills.get(1).unwrap(), sha256_fill_hash(&env, &second));
+
+    // With a limit of 1 only the newest fill is kept
+    client.set_fill_index_limit(&owner, &1u32);
+    let third = incoming_intent_data(&env, &BytesN::from_array(&env, &[13u8; 32]), &receiver, &token);
+    client.fill_and_notify(&relayer, &third, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    let fills = client.get_fills_by_relayer(&relayer);
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills.get(0).unwrap(), sha256_fill_hash(&env, &third));
+}
+
+#[test]
+fn test_settlement_released_after_delay() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_settlement_delay(&owner, &600u64);
+
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let payee = Address::generate(&env);
+    let fill_hash = notify_matching_fill(&env, &contract, &adapter, &intent_id, &payee);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Filled);
+    assert_eq!(token_client.balance(&payee), 0);
+
+    set_timestamp(&env, 1600);
+    client.release_settlement(&fill_hash);
+
+    // 100 USDC minus 3 bps protocol fee
+    assert_eq!(token_client.balance(&payee), 999_700_000i128);
+    assert!(client.get_pending_settlement(&fill_hash).is_none());
+}
+
+#[test]
+fn test_settlement_blocked_before_delay() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_settlement_delay(&owner, &600u64);
+
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let payee = Address::generate(&env);
+    let fill_hash = notify_matching_fill(&env, &contract, &adapter, &intent_id, &payee);
+
+    set_timestamp(&env, 1599);
+    let result = client.try_release_settlement(&fill_hash);
+    assert_eq!(result, Err(Ok(Error::SettlementLocked)));
+    assert_eq!(token_client.balance(&payee), 0);
+}
+
+#[test]
+fn test_fill_to_contract_self_address_rejected() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    // Receiver bytes32 resolves to the contract's own address
+    let mut intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    intent_data.receiver = crate::address_to_bytes32(&env, &contract);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAddress)));
+}
+
+#[test]
+fn test_notify_payout_to_contract_self_address_rejected() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &expected_fill_hash(&env, &contract, &intent_id),
+        &crate::address_to_bytes32(&env, &contract),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        990_000_000i128,
+        false,
+    )
+    .unwrap();
+    let result = client.try_notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(result, Err(Ok(Error::InvalidAddress)));
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+}
+
+#[test]
+fn test_is_rozo_fallback_active() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_rozo_relayer(&owner, &Address::generate(&env));
+    client.set_rozo_threshold(&owner, &300u64);
+
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    // Threshold not yet elapsed
+    set_timestamp(&env, 1299);
+    assert!(!client.is_rozo_fallback_active(&intent_id));
+
+    set_timestamp(&env, 1300);
+    assert!(client.is_rozo_fallback_active(&intent_id));
+
+    // Unknown intents are never eligible
+    assert!(!client.is_rozo_fallback_active(&BytesN::from_array(&env, &[42u8; 32])));
+}
+
+#[test]
+fn test_max_pending_intents() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_max_pending(&owner, &2u32);
+    set_timestamp(&env, 1000);
+
+    let first = BytesN::from_array(&env, &[21u8; 32]);
+    let second = BytesN::from_array(&env, &[22u8; 32]);
+    let third = BytesN::from_array(&env, &[23u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &first, &sender, &receiver, &token));
+    client.create_intent(&sender, &default_params(&env, &second, &sender, &receiver, &token));
+    assert_eq!(client.get_pending_count(), 2);
+
+    let third_params = default_params(&env, &third, &sender, &receiver, &token);
+    let result = client.try_create_intent(&sender, &third_params);
+    assert_eq!(result, Err(Err(PolicyError::TooManyPendingIntents.into())));
+
+    // Refunding frees a slot
+    set_timestamp(&env, 2000);
+    client.refund(&sender, &first);
+    assert_eq!(client.get_pending_count(), 1);
+
+    let mut third_params = third_params;
+    third_params.deadline = 3000u64;
+    client.create_intent(&sender, &third_params);
+    assert_eq!(client.get_pending_count(), 2);
+}
+
+#[test]
+fn test_migrate_legacy_intent() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let intent_id = generate_intent_id(&env);
+    let legacy = IntentV0 {
+        intent_id: intent_id.clone(),
+        sender: sender.clone(),
+        refund_address: sender.clone(),
+        source_token: token.clone(),
+        source_amount: 1_000_000_000i128,
+        destination_chain_id: 8453u64,
+        destination_token: address_to_bytes32(&env, &token),
+        receiver: address_to_bytes32(&env, &receiver),
+        receiver_is_account: false,
+        destination_amount: 990_000_000i128,
+        deadline: 2000u64,
+        created_at: 1000u64,
+        status: IntentStatus::Pending,
+        relayer: zero_bytes32(&env),
+    };
+    env.as_contract(&contract, || {
+        storage::set_raw_intent(&env, &intent_id, &legacy);
+    });
+
+    // Old layout cannot be read as the current Intent
+    assert!(client.try_get_intent(&intent_id).is_err());
+
+    let ids = soroban_sdk::vec![&env, intent_id.clone()];
+    assert_eq!(client.batch_migrate(&owner, &ids), 1);
+
+    let intent = client.get_intent(&intent_id);
+    assert_eq!(intent.schema_version, INTENT_SCHEMA_VERSION);
+    assert_eq!(intent.referrer, None);
+    assert_eq!(intent.sender, sender);
+    assert_eq!(intent.destination_amount, 990_000_000i128);
+
+    // Already-current intents are left alone
+    assert!(!client.migrate_intent_schema(&owner, &intent_id));
+}
+
+#[test]
+fn test_blacklisted_receiver_cannot_be_filled() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    client.blacklist_receiver(&owner, &intent_data.receiver);
+    assert!(client.is_receiver_blocked(&intent_data.receiver));
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Err(PolicyError::ReceiverBlocked.into())));
+    assert_eq!(token_client.balance(&receiver), 0);
+}
+
+#[test]
+fn test_unblacklisted_receiver_can_be_filled() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    client.blacklist_receiver(&owner, &intent_data.receiver);
+    client.unblacklist_receiver(&owner, &intent_data.receiver);
+    assert!(!client.is_receiver_blocked(&intent_data.receiver));
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&receiver), 990_000_000i128);
+}
+
+#[test]
+fn test_get_intent_data_matches_fill_hash() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let intent_data = client.get_intent_data(&intent_id);
+    assert_eq!(intent_data.source_chain_id, 1500u64);
+    assert_eq!(intent_data.sender, crate::address_to_bytes32(&env, &sender));
+    assert_eq!(
+        sha256_fill_hash(&env, &intent_data),
+        expected_fill_hash(&env, &contract, &intent_id)
+    );
+}
+
+#[test]
+fn test_admin_action_event_for_set_protocol_fee() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_protocol_fee(&owner, &7u32);
+
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> = (
+        Symbol::new(&env, "admin_action"),
+        Symbol::new(&env, "set_protocol_fee"),
+    )
+        .into_val(&env);
+    let audit = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(emitter, topics, _)| *emitter == contract && *topics == expected_topics)
+        .last()
+        .expect("admin_action event not emitted");
+
+    let (admin, context): (Address, u32) = audit.2.into_val(&env);
+    assert_eq!(admin, owner);
+    assert_eq!(context, 7u32);
+}
+
+#[test]
+fn test_fill_overpay_recorded() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    client.fill_and_notify(&relayer, &intent_data, &990_100_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    assert_eq!(token_client.balance(&receiver), 990_100_000i128);
+    let record = client
+        .get_fill_record(&sha256_fill_hash(&env, &intent_data))
+        .unwrap();
+    assert_eq!(record.overpay, 100_000i128);
+
+    // Underpaying is never allowed
+    let short = incoming_intent_data(&env, &BytesN::from_array(&env, &[31u8; 32]), &receiver, &token);
+    let result = client.try_fill_and_notify(&relayer, &short, &989_999_999i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_overpay_reimbursed_from_fees() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_overpay_reimburse(&owner, &true);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    // Relayer reports paying 0.01 USDC above the committed amount
+    let payee = Address::generate(&env);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &expected_fill_hash(&env, &contract, &intent_id),
+        &crate::address_to_bytes32(&env, &payee),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        990_100_000i128,
+        false,
+    )
+    .unwrap();
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+
+    // 300_000 fee collected, 100_000 of it reimbursed to the relayer
+    assert_eq!(token_client.balance(&payee), 999_800_000i128);
+    assert_eq!(client.get_accum_fees(&token), 200_000i128);
+}
+
+#[test]
+fn test_is_solvent() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    assert_eq!(client.get_obligations(&token), 1_000_000_000i128);
+    assert!(client.is_solvent(&token));
+
+    // Simulate an external drain of escrowed collateral
+    token_client.transfer(&contract, &Address::generate(&env), &1i128);
+    assert!(!client.is_solvent(&token));
+}
+
+#[test]
+fn test_touch_expired() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    // Not before the deadline
+    let result = client.try_touch_expired(&intent_id);
+    assert_eq!(result, Err(Ok(Error::IntentNotExpired)));
+
+    set_timestamp(&env, 2000);
+    client.touch_expired(&intent_id);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Expired);
+
+    // Funds stay escrowed
+    assert_eq!(client.get_obligations(&token), 1_000_000_000i128);
+}
+
+#[test]
+fn test_refund_from_expired() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let initial_balance = token_client.balance(&sender);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    set_timestamp(&env, 2500);
+    client.touch_expired(&intent_id);
+    client.refund(&sender, &intent_id);
+
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Refunded);
+    assert_eq!(token_client.balance(&sender), initial_balance);
+    assert_eq!(client.get_pending_count(), 0);
+}
+
+#[test]
+fn test_batch_migrate_rejects_oversized_batch() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for i in 0..=MAX_BATCH_SIZE {
+        ids.push_back(BytesN::from_array(&env, &[i as u8; 32]));
+    }
+
+    let result = client.try_batch_migrate(&owner, &ids);
+    assert_eq!(result, Err(Ok(Error::BatchTooLarge)));
+
+    let result = client.try_set_fill_index_limit(&owner, &(MAX_BATCH_SIZE + 1));
+    assert_eq!(result, Err(Ok(Error::BatchTooLarge)));
+}
+
+#[test]
+fn test_relayer_volume_cap_across_window() {
+    let (env, contract, owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_relayer_volume_cap(&owner, &relayer, &1_500_000_000i128, &3600u64);
+    set_timestamp(&env, 1000);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    // A fill counts nothing until it settles on the source chain
+    let first = incoming_intent_data(&env, &BytesN::from_array(&env, &[40u8; 32]), &receiver, &token);
+    client.fill_and_notify(&relayer, &first, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(client.get_relayer_volume(&relayer), 0);
+
+    let relayer_bytes32 = crate::address_to_bytes32(&env, &relayer);
+    let settled_id = BytesN::from_array(&env, &[43u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &settled_id, &sender, &receiver, &token));
+    let payload = encode_notify_payload(
+        &env,
+        &settled_id,
+        &expected_fill_hash(&env, &contract, &settled_id),
+        &repayment,
+        &relayer_bytes32,
+        990_000_000i128,
+        false,
+    )
+    .unwrap();
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(client.get_relayer_volume(&relayer), 990_000_000i128);
+
+    // A failed settlement doesn't count
+    let failed_id = BytesN::from_array(&env, &[44u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &failed_id, &sender, &receiver, &token));
+    let payload = encode_notify_payload(
+        &env,
+        &failed_id,
+        &BytesN::from_array(&env, &[9u8; 32]),
+        &repayment,
+        &relayer_bytes32,
+        990_000_000i128,
+        false,
+    )
+    .unwrap();
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(client.get_intent(&failed_id).status, IntentStatus::Failed);
+    assert_eq!(client.get_relayer_volume(&relayer), 990_000_000i128);
+
+    // Within the cap
+    let mut small = incoming_intent_data(&env, &BytesN::from_array(&env, &[41u8; 32]), &receiver, &token);
+    small.destination_amount = 500_000_000i128;
+    client.fill_and_notify(&relayer, &small, &500_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    // Beyond the cap
+    let large = incoming_intent_data(&env, &BytesN::from_array(&env, &[42u8; 32]), &receiver, &token);
+    let result = client.try_fill_and_notify(&relayer, &large, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Err(PolicyError::VolumeCapExceeded.into())));
+
+    // Window elapsed: volume resets
+    set_timestamp(&env, 1000 + 3600);
+    let mut late = large.clone();
+    late.deadline = 9000u64;
+    assert_eq!(client.get_relayer_volume(&relayer), 0);
+    client.fill_and_notify(&relayer, &late, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+}
+
+#[test]
+fn test_intent_exists() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    assert!(client.intent_exists(&intent_id));
+    assert!(!client.intent_exists(&BytesN::from_array(&env, &[99u8; 32])));
+}
+
+#[test]
+fn test_circuit_breaker_trips_on_fill_burst() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    client.set_circuit_breaker(&owner, &3u32, &60u64);
+    set_timestamp(&env, 1000);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    for i in 0..3u8 {
+        let data = incoming_intent_data(&env, &BytesN::from_array(&env, &[50 + i; 32]), &receiver, &token);
+        client.fill_and_notify(&relayer, &data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    }
+    assert!(client.is_paused());
+    assert!(client.get_pause_reason().is_some());
+
+    let next = incoming_intent_data(&env, &BytesN::from_array(&env, &[60u8; 32]), &receiver, &token);
+    let result = client.try_fill_and_notify(&relayer, &next, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Ok(Error::Paused)));
+
+    client.reset_circuit_breaker(&owner);
+    assert!(!client.is_paused());
+    client.fill_and_notify(&relayer, &next, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+}
+
+#[test]
+fn test_circuit_breaker_window_resets() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    client.set_circuit_breaker(&owner, &2u32, &60u64);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    set_timestamp(&env, 1000);
+    let first = incoming_intent_data(&env, &BytesN::from_array(&env, &[61u8; 32]), &receiver, &token);
+    client.fill_and_notify(&relayer, &first, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    // Spread out fills never trip the breaker
+    set_timestamp(&env, 1100);
+    let second = incoming_intent_data(&env, &BytesN::from_array(&env, &[62u8; 32]), &receiver, &token);
+    client.fill_and_notify(&relayer, &second, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert!(!client.is_paused());
+}
+
+#[test]
+fn test_relayer_bond() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    client.set_min_bond(&owner, &token, &500_000_000i128);
+    assert_eq!(client.get_min_bond(), Some((token.clone(), 500_000_000i128)));
+    set_timestamp(&env, 1000);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let data = incoming_intent_data(&env, &BytesN::from_array(&env, &[74u8; 32]), &receiver, &token);
+
+    // Unbonded relayers can't fill
+    let result = client.try_fill_and_notify(&relayer, &data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Err(PolicyError::InsufficientBond.into())));
+
+    client.deposit_bond(&relayer, &token, &500_000_000i128);
+    assert_eq!(client.get_bond(&relayer, &token), 500_000_000i128);
+    assert_eq!(client.get_obligations(&token), 500_000_000i128);
+    assert!(client.is_solvent(&token));
+    client.fill_and_notify(&relayer, &data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    // Slashing moves bond into fees and drops the relayer below the minimum
+    client.slash_bond(&owner, &relayer, &token, &200_000_000i128);
+    assert_eq!(client.get_bond(&relayer, &token), 300_000_000i128);
+    assert_eq!(client.get_accum_fees(&token), 200_000_000i128);
+    assert_eq!(client.try_slash_bond(&owner, &relayer, &token, &400_000_000i128), Err(Err(PolicyError::InsufficientBond.into())));
+
+    let next = incoming_intent_data(&env, &BytesN::from_array(&env, &[75u8; 32]), &receiver, &token);
+    let result = client.try_fill_and_notify(&relayer, &next, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Err(PolicyError::InsufficientBond.into())));
+
+    let balance_before = token_client.balance(&relayer);
+    assert_eq!(client.try_withdraw_bond(&relayer, &token, &400_000_000i128), Err(Err(PolicyError::InsufficientBond.into())));
+    client.withdraw_bond(&relayer, &token, &300_000_000i128);
+    assert_eq!(client.get_bond(&relayer, &token), 0);
+    assert_eq!(token_client.balance(&relayer), balance_before + 300_000_000i128);
+
+    let outsider = Address::generate(&env);
+    assert_eq!(client.try_deposit_bond(&outsider, &token, &1i128), Err(Ok(Error::NotRelayer)));
+}
+
+#[test]
+fn test_relayer_rate_limit() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    client.set_relayer_rate_limit(&owner, &2u32, &60u64);
+    set_timestamp(&env, 1000);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    for i in 0..2u8 {
+        let data = incoming_intent_data(&env, &BytesN::from_array(&env, &[70 + i; 32]), &receiver, &token);
+        client.fill_and_notify(&relayer, &data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    }
+
+    let next = incoming_intent_data(&env, &BytesN::from_array(&env, &[72u8; 32]), &receiver, &token);
+    let result = client.try_fill_and_notify(&relayer, &next, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Err(PolicyError::RateLimited.into())));
+
+    // Another relayer has its own window
+    let other = Address::generate(&env);
+    client.add_relayer(&owner, &other, &RelayerType::External);
+    StellarAssetClient::new(&env, &token).mint(&other, &990_000_000i128);
+    let other_data = incoming_intent_data(&env, &BytesN::from_array(&env, &[73u8; 32]), &receiver, &token);
+    client.fill_and_notify(&other, &other_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    set_timestamp(&env, 1060);
+    client.fill_and_notify(&relayer, &next, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    let invalid = client.try_set_relayer_rate_limit(&owner, &1u32, &0u64);
+    assert_eq!(invalid, Err(Ok(Error::InvalidPayload)));
+}
+
+#[test]
+fn test_withdraw_fees_to_destination() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+    assert_eq!(client.get_accum_fees(&token), 300_000i128);
+
+    let bridge = Address::generate(&env);
+    client.withdraw_fees_to(&owner, &token, &bridge, &100_000i128);
+
+    assert_eq!(token_client.balance(&bridge), 100_000i128);
+    assert_eq!(client.get_accum_fees(&token), 200_000i128);
+}
+
+#[test]
+fn test_withdraw_fees_to_sweeps_without_changing_recipient() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+
+    let one_time = Address::generate(&env);
+    client.withdraw_fees_to(&owner, &token, &one_time, &client.get_accum_fees(&token));
+
+    assert_eq!(token_client.balance(&one_time), 300_000i128);
+    assert_eq!(client.get_accum_fees(&token), 0);
+    assert_eq!(client.get_fee_rcpt(), owner);
+
+    // The event names the one-time recipient actually paid
+    let (_, data) = last_event(&env, &contract, "fees_withdrawn");
+    let (_, recipient, amount): (Address, Address, i128) = data.into_val(&env);
+    assert_eq!(recipient, one_time);
+    assert_eq!(amount, 300_000i128);
+}
+
+#[test]
+fn test_withdraw_fees_to_rejects_over_amount() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+
+    let bridge = Address::generate(&env);
+    let result = client.try_withdraw_fees_to(&owner, &token, &bridge, &300_001i128);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+    assert_eq!(client.get_accum_fees(&token), 300_000i128);
+}
+
+#[test]
+fn test_referred_intent_pays_referrer() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_referral_share(&owner, &5000u32);
+    set_timestamp(&env, 1000);
+
+    let referrer = Address::generate(&env);
+    let intent_id = generate_intent_id(&env);
+    let mut params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    params.referrer = Some(referrer.clone());
+    client.create_intent(&sender, &params);
+    assert_eq!(client.get_intent(&intent_id).referrer, Some(referrer.clone()));
+
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+
+    // Half of the 300_000 protocol fee
+    assert_eq!(token_client.balance(&referrer), 150_000i128);
+    assert_eq!(client.get_accum_fees(&token), 150_000i128);
+}
+
+#[test]
+fn test_unreferred_intent_pays_no_referral() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_referral_share(&owner, &5000u32);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+
+    assert_eq!(client.get_accum_fees(&token), 300_000i128);
+
+    // Share can never exceed the fee itself
+    let result = client.try_set_referral_share(&owner, &10_001u32);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+}
+
+#[test]
+fn test_cancel_all_skips_filled_intent() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let initial_balance = token_client.balance(&sender);
+    set_timestamp(&env, 1000);
+
+    let first = BytesN::from_array(&env, &[81u8; 32]);
+    let second = BytesN::from_array(&env, &[82u8; 32]);
+    let filled = BytesN::from_array(&env, &[83u8; 32]);
+    for id in [&first, &second, &filled] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+
+    // A relayer already filled the third intent on this chain
+    let fill_hash = expected_fill_hash(&env, &contract, &filled);
+    env.as_contract(&contract, || {
+        let record = FillRecord {
+            relayer: Address::generate(&env),
+            repayment_address: BytesN::from_array(&env, &[7u8; 32]),
+            repayment_is_account: true,
+            overpay: 0,
+            delivery_proof: zero_bytes32(&env),
+        };
+        storage::set_fill_record(&env, &fill_hash, &record);
+    });
+
+    // Before the deadline
+    let refunded = client.cancel_all(&sender);
+    assert_eq!(refunded, soroban_sdk::vec![&env, first.clone(), second.clone()]);
+
+    assert_eq!(client.get_intent(&first).status, IntentStatus::Refunded);
+    assert_eq!(client.get_intent(&second).status, IntentStatus::Refunded);
+    assert_eq!(client.get_intent(&filled).status, IntentStatus::Pending);
+    assert_eq!(token_client.balance(&sender), initial_balance - 1_000_000_000i128);
+}
+
+#[test]
+fn test_format_amount() {
+    let (env, contract, _owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+    let client = RozoIntentsContractClient::new(&env, &contract);
+
+    // Whole amounts
+    assert_eq!(client.format_amount(&1_000_000_000i128, &7), String::from_str(&env, "100.0000000"));
+    assert_eq!(client.format_amount(&42i128, &0), String::from_str(&env, "42"));
+
+    // Fractional amounts
+    assert_eq!(client.format_amount(&1_234_567i128, &6), String::from_str(&env, "1.234567"));
+    assert_eq!(client.format_amount(&5i128, &3), String::from_str(&env, "0.005"));
+    assert_eq!(client.format_amount(&-15i128, &1), String::from_str(&env, "-1.5"));
+
+    // Zero
+    assert_eq!(client.format_amount(&0i128, &7), String::from_str(&env, "0.0000000"));
+    assert_eq!(client.format_amount(&0i128, &0), String::from_str(&env, "0"));
+
+    assert_eq!(client.try_format_amount(&1i128, &39), Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_report_unfillable_recorded_and_emitted() {
+    let (env, contract, _owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let reason = Symbol::new(&env, "bad_recv_flag");
+    client.report_unfillable(&relayer, &intent_id, &reason);
+
+    let report = client.get_unfillable_report(&intent_id).unwrap();
+    assert_eq!(report.relayer, relayer);
+    assert_eq!(report.reason, reason);
+    assert_eq!(report.reported_at, 1000);
+
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "unfillable_reported"), intent_id.clone()).into_val(&env);
+    let event = env
+        .events()
+        .all()
+        .iter()
+        .find(|(emitter, topics, _)| *emitter == contract && *topics == expected_topics)
+        .expect("unfillable_reported event not emitted");
+    let (reporter, emitted_reason): (Address, Symbol) = event.2.into_val(&env);
+    assert_eq!(reporter, relayer);
+    assert_eq!(emitted_reason, reason);
+
+    // Funds untouched
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+
+    // Non-relayers can't report
+    let stranger = Address::generate(&env);
+    let result = client.try_report_unfillable(&stranger, &intent_id, &reason);
+    assert_eq!(result, Err(Ok(Error::NotRelayer)));
+}
+
+#[test]
+fn test_strict_asset_match() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let base_usdc = BytesN::from_array(&env, &[0xBAu8; 32]);
+    client.set_asset_equivalence(&owner, &token, &8453u64, &base_usdc);
+    client.set_strict_asset_match(&owner, &true);
+    assert_eq!(client.get_asset_equivalence(&token, &8453u64), Some(base_usdc.clone()));
+
+    // Matched equivalence
+    let matched_id = BytesN::from_array(&env, &[91u8; 32]);
+    let mut matched = default_params(&env, &matched_id, &sender, &receiver, &token);
+    matched.destination_token = base_usdc.clone();
+    client.create_intent(&sender, &matched);
+    assert_eq!(client.get_intent(&matched_id).status, IntentStatus::Pending);
+
+    // Mismatched destination token
+    let mismatched_id = BytesN::from_array(&env, &[92u8; 32]);
+    let mut mismatched = default_params(&env, &mismatched_id, &sender, &receiver, &token);
+    mismatched.destination_token = BytesN::from_array(&env, &[0xEEu8; 32]);
+    let result = client.try_create_intent(&sender, &mismatched);
+    assert_eq!(result, Err(Ok(Error::AssetMismatch)));
+
+    // No equivalence configured for this chain
+    let unmapped_id = BytesN::from_array(&env, &[93u8; 32]);
+    let mut unmapped = default_params(&env, &unmapped_id, &sender, &receiver, &token);
+    unmapped.destination_chain_id = 10;
+    unmapped.destination_token = base_usdc;
+    let result = client.try_create_intent(&sender, &unmapped);
+    assert_eq!(result, Err(Ok(Error::AssetMismatch)));
+
+    // Lenient mode accepts any destination token
+    client.set_strict_asset_match(&owner, &false);
+    client.create_intent(&sender, &mismatched);
+}
+
+#[test]
+fn test_total_withdrawn_accumulates() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+    assert_eq!(client.get_total_withdrawn(&token), 0);
+
+    client.withdraw_fees_to(&owner, &token, &Address::generate(&env), &100_000i128);
+    assert_eq!(client.get_total_withdrawn(&token), 100_000i128);
+
+    client.withdraw_fees(&owner, &token);
+    assert_eq!(client.get_total_withdrawn(&token), 300_000i128);
+    assert_eq!(client.get_accum_fees(&token), 0);
+}
+
+#[test]
+fn test_lapsed_claim_counts_relayer_failure() {
+    let (env, contract, owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let expires_at = client.claim_intent(&relayer, &intent_id);
+    assert_eq!(expires_at, 1000 + client.get_claim_window());
+
+    // Another relayer can't take over an active claim
+    let rival = Address::generate(&env);
+    client.add_relayer(&owner, &rival, &RelayerType::External);
+    assert_eq!(client.try_claim_intent(&rival, &intent_id), Err(Ok(Error::IntentClaimed)));
+    assert_eq!(client.try_expire_claim(&intent_id), Err(Ok(Error::IntentClaimed)));
+
+    // Claim lapses without a fill
+    set_timestamp(&env, expires_at);
+    client.expire_claim(&intent_id);
+
+    let relayer_bytes32 = env.as_contract(&contract, || crate::address_to_bytes32(&env, &relayer));
+    assert_eq!(client.get_relayer_failures(&relayer_bytes32), 1);
+    assert_eq!(client.get_intent_claim(&intent_id), None);
+
+    // The griefing relayer is on cooldown; others may claim
+    assert_eq!(client.try_claim_intent(&relayer, &intent_id), Err(Ok(Error::ClaimCooldown)));
+    client.claim_intent(&rival, &intent_id);
+    assert_eq!(client.get_intent_claim(&intent_id).unwrap().relayer, rival);
+}
+
+#[test]
+fn test_event_topic_forms() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    set_timestamp(&env, 2001);
+    client.refund(&sender, &intent_id);
+    client.pause(&owner, &String::from_str(&env, "maintenance"));
+
+    let mut first_topics: soroban_sdk::Vec<Symbol> = soroban_sdk::Vec::new(&env);
+    for (emitter, topics, _) in env.events().all().iter() {
+        if emitter == contract {
+            first_topics.push_back(Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap());
+        }
+    }
+
+    // Critical event names exceed 9 characters and keep the long form
+    assert!(first_topics.contains(Symbol::new(&env, "intent_created")));
+    assert!(first_topics.contains(Symbol::new(&env, "intent_refunded")));
+    // Names that fit use the short form
+    assert!(first_topics.contains(symbol_short!("paused")));
+}
+
+#[test]
+fn test_fill_uses_preferred_messenger() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    // Messenger 1 is registered by the helper; the sender prefers messenger 2
+    register_mock_adapter(&env, &contract, &owner);
+    let preferred = env.register_contract(None, MockAdapter);
+    client.set_msger_adapter(&owner, &2u32, &preferred);
+
+    let mut intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    intent_data.preferred_messenger = Some(2u32);
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &None);
+
+    let expected_topics: soroban_sdk::Vec<soroban_sdk::Val> =
+        (Symbol::new(&env, "fill_and_notify_sent"), intent_data.intent_id.clone()).into_val(&env);
+    let sent = env
+        .events()
+        .all()
+        .iter()
+        .find(|(emitter, topics, _)| *emitter == contract && *topics == expected_topics)
+        .expect("fill_and_notify_sent not emitted");
+    let (_, _, messenger_id): (Address, BytesN<32>, u32) = sent.2.into_val(&env);
+    assert_eq!(messenger_id, 2u32);
+
+    // No override and no preference
+    let mut other = incoming_intent_data(&env, &BytesN::from_array(&env, &[94u8; 32]), &receiver, &token);
+    other.preferred_messenger = None;
+    let result = client.try_fill_and_notify(&relayer, &other, &990_000_000i128, &repayment, &true, &proof, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidMessenger)));
+}
+
+#[test]
+fn test_fill_profitability_matches_fill() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let (cost, payout) = client.fill_profitability(&intent_id, &None);
+    assert_eq!(cost, 990_000_000i128);
+    assert_eq!(payout, 999_700_000i128);
+
+    let payee = Address::generate(&env);
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &payee);
+    assert_eq!(token_client.balance(&payee), payout);
+}
+
+#[test]
+fn test_pull_payout_credit_and_claim() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_pull_payouts(&owner, &true);
+    set_timestamp(&env, 1000);
+
+    let payee = Address::generate(&env);
+    let first = BytesN::from_array(&env, &[95u8; 32]);
+    let second = BytesN::from_array(&env, &[96u8; 32]);
+    for id in [&first, &second] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+        notify_matching_fill(&env, &contract, &adapter, id, &payee);
+    }
+
+    // Credited on completion, nothing transferred yet
+    assert_eq!(token_client.balance(&payee), 0);
+    assert_eq!(client.get_payout_owed(&payee, &token), 1_999_400_000i128);
+    assert!(client.is_solvent(&token));
+
+    // Drained on claim
+    assert_eq!(client.claim_payout(&payee, &token), 1_999_400_000i128);
+    assert_eq!(token_client.balance(&payee), 1_999_400_000i128);
+    assert_eq!(client.get_payout_owed(&payee, &token), 0);
+    assert_eq!(client.try_claim_payout(&payee, &token), Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_notify_payload_length_errors() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+
+    let short = Bytes::from_array(&env, &[0u8; 100]);
+    let result = client.try_notify(&adapter, &1u32, &8453u64, &short);
+    assert_eq!(result, Err(Ok(Error::PayloadTooShort)));
+
+    let long = Bytes::from_array(&env, &[0u8; 288]);
+    let result = client.try_notify(&adapter, &1u32, &8453u64, &long);
+    assert_eq!(result, Err(Ok(Error::PayloadTooLong)));
+}
+
+#[test]
+fn test_intent_timeline_create_then_refund() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    assert_eq!(client.get_intent_timeline(&intent_id), (1000, 0, 0));
+
+    set_timestamp(&env, 2500);
+    client.refund(&sender, &intent_id);
+    assert_eq!(client.get_intent_timeline(&intent_id), (1000, 0, 2500));
+}
+
+#[test]
+fn test_intent_timeline_create_then_fill() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    assert_eq!(client.get_intent(&intent_id).filled_at, 0);
+
+    set_timestamp(&env, 1450);
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+    assert_eq!(client.get_intent(&intent_id).filled_at, 1450);
+    assert_eq!(client.get_intent_timeline(&intent_id), (1000, 1450, 0));
+
+    // The intent_filled event carries the fill timestamp last
+    let (_, data) = last_event(&env, &contract, "intent_filled");
+    let (_, _, _, _, filled_at): (BytesN<32>, BytesN<32>, i128, i128, u64) = data.into_val(&env);
+    assert_eq!(filled_at, 1450);
+}
+
+#[test]
+fn test_min_relayer_type_rozo_only() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let mut intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    intent_data.min_relayer_type = RelayerType::Rozo;
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    // External relayer is rejected
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorizedRelayer)));
+
+    // Rozo relayer succeeds
+    let rozo = Address::generate(&env);
+    client.add_relayer(&owner, &rozo, &RelayerType::Rozo);
+    StellarAssetClient::new(&env, &token).mint(&rozo, &990_000_000i128);
+    client.fill_and_notify(&rozo, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&receiver), 990_000_000i128);
+}
+
+#[test]
+fn test_min_relayer_type_enforced_on_settlement() {
+    let (env, contract, owner, sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let rozo = Address::generate(&env);
+    client.add_relayer(&owner, &rozo, &RelayerType::Rozo);
+    let payee = Address::generate(&env);
+    client.set_chain_type(&owner, &1600u64, &ChainType::Stellar);
+
+    // An External relayer filled with min_relayer_type None in its intent data; the
+    // fill hash doesn't cover the field, so only the stored requirement catches it
+    let mut results = Vec::new(&env);
+    for (id, filler) in [(131u8, &relayer), (132u8, &rozo)] {
+        let intent_id = BytesN::from_array(&env, &[id; 32]);
+        let mut params = default_params(&env, &intent_id, &sender, &receiver, &token);
+        params.destination_chain_id = 1600u64;
+        params.min_relayer_type = RelayerType::Rozo;
+        client.create_intent(&sender, &params);
+
+        let payload = encode_notify_payload(
+            &env,
+            &intent_id,
+            &expected_fill_hash(&env, &contract, &intent_id),
+            &crate::address_to_bytes32(&env, &payee),
+            &crate::address_to_bytes32(&env, filler),
+            params.destination_amount,
+            false,
+        )
+        .unwrap();
+        client.notify(&adapter, &1u32, &8453u64, &payload);
+        results.push_back(client.get_intent(&intent_id).status);
+    }
+
+    assert_eq!(results.get(0), Some(IntentStatus::Failed));
+    assert_eq!(results.get(1), Some(IntentStatus::Filled));
+    // Only the Rozo relayer's fill was paid out
+    assert_eq!(token_client.balance(&payee), 1_000_000_000i128 - 300_000);
+}
+
+#[test]
+fn test_min_relayer_type_requires_stellar_destination() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let balance = token_client.balance(&sender);
+
+    // EVM fills never check the relayer type, so the intent could only end up Failed
+    let mut params = default_params(&env, &BytesN::from_array(&env, &[135u8; 32]), &sender, &receiver, &token);
+    params.min_relayer_type = RelayerType::Rozo;
+    assert_eq!(client.try_create_intent(&sender, &params), Err(Ok(Error::WrongChain)));
+    assert_eq!(token_client.balance(&sender), balance);
+
+    // Stellar destinations enforce it
+    client.set_chain_type(&owner, &1600u64, &ChainType::Stellar);
+    params.destination_chain_id = 1600u64;
+    client.create_intent(&sender, &params);
+    assert_eq!(client.get_intent(&params.intent_id).min_relayer_type, RelayerType::Rozo);
+}
+
+#[test]
+fn test_replace_adapter_with_drain_blocks_fills() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let new_adapter = env.register_contract(None, MockAdapter);
+    client.replace_messenger_adapter(&owner, &1u32, &new_adapter, &true);
+    assert_eq!(client.get_msger_adapter(&1u32), Some(new_adapter));
+    assert!(client.is_messenger_disabled(&1u32));
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Err(PolicyError::MessengerDisabled.into())));
+
+    client.enable_messenger(&owner, &1u32);
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&receiver), 990_000_000i128);
+}
+
+#[test]
+fn test_cleanup_failed_with_and_without_refund() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let refunded = BytesN::from_array(&env, &[97u8; 32]);
+    let dropped = BytesN::from_array(&env, &[98u8; 32]);
+    let pending = BytesN::from_array(&env, &[99u8; 32]);
+    for id in [&refunded, &dropped, &pending] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+    client.set_intent_status(&owner, &refunded, &IntentStatus::Failed);
+    client.set_intent_status(&owner, &dropped, &IntentStatus::Failed);
+
+    // Only Failed intents may be cleaned
+    let result = client.try_cleanup_failed(&owner, &soroban_sdk::vec![&env, pending.clone()], &true);
+    assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+
+    // With refund: sender gets the escrow back
+    assert_eq!(client.cleanup_failed(&owner, &soroban_sdk::vec![&env, refunded.clone()], &true), 1);
+    assert!(!client.intent_exists(&refunded));
+    assert_eq!(token_client.balance(&sender), initial_balance - 2_000_000_000i128);
+
+    // Without refund: entry removed, funds stay in the contract
+    assert_eq!(client.cleanup_failed(&owner, &soroban_sdk::vec![&env, dropped.clone()], &false), 1);
+    assert!(!client.intent_exists(&dropped));
+    assert_eq!(token_client.balance(&sender), initial_balance - 2_000_000_000i128);
+    assert_eq!(client.get_obligations(&token), 1_000_000_000i128);
+
+    // cancel_all skips removed entries
+    assert_eq!(client.cancel_all(&sender), soroban_sdk::vec![&env, pending]);
+}
+
+#[test]
+fn test_fee_tier_applies_after_volume_threshold() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    client.arm_emergency(&owner);
+
+    // 1 bps once 100 USDC has been filled
+    let tiers = soroban_sdk::vec![&env, FeeTier { min_volume: 1_000_000_000i128, fee_bps: 1 }];
+    client.set_fee_tiers(&owner, &tiers);
+    assert_eq!(client.get_effective_fee(&token), 3);
+
+    // First fill pays the base 3 bps
+    let first = BytesN::from_array(&env, &[101u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &first, &sender, &receiver, &token));
+    notify_matching_fill(&env, &contract, &adapter, &first, &Address::generate(&env));
+    assert_eq!(client.get_accum_fees(&token), 300_000i128);
+
+    // Volume threshold crossed
+    assert_eq!(client.get_total_volume(&token), 1_000_000_000i128);
+    assert_eq!(client.get_effective_fee(&token), 1);
+
+    let second = BytesN::from_array(&env, &[102u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &second, &sender, &receiver, &token));
+    notify_matching_fill(&env, &contract, &adapter, &second, &Address::generate(&env));
+    assert_eq!(client.get_accum_fees(&token), 400_000i128);
+}
+
+#[test]
+fn test_set_msger_adapter_rejects_non_adapter() {
+    let (env, contract, owner, _sender, _receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+
+    let adapter = env.register_contract(None, MockAdapter);
+    client.set_msger_adapter(&owner, &1u32, &adapter);
+    assert_eq!(client.get_msger_adapter(&1u32), Some(adapter.clone()));
+
+    // A token contract has no send_msg
+    let result = client.try_set_msger_adapter(&owner, &2u32, &token);
+    assert_eq!(result, Err(Ok(Error::InvalidMessenger)));
+    assert_eq!(client.get_msger_adapter(&2u32), None);
+    let result = client.try_replace_messenger_adapter(&owner, &1u32, &token, &false);
+    assert_eq!(result, Err(Ok(Error::InvalidMessenger)));
+    assert_eq!(client.get_msger_adapter(&1u32), Some(adapter));
+}
+
+#[test]
+fn test_set_chain_id_to_name_guard() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let arbitrum = String::from_str(&env, "arbitrum");
+
+    // Fresh mapping
+    client.set_chain_id_to_name(&owner, &42161u64, &arbitrum, &false);
+
+    // Identical re-set is fine
+    client.set_chain_id_to_name(&owner, &42161u64, &arbitrum, &false);
+
+    // Conflicting overwrite needs force
+    let other = String::from_str(&env, "optimism");
+    let result = client.try_set_chain_id_to_name(&owner, &42161u64, &other, &false);
+    assert_eq!(result, Err(Ok(Error::ChainAlreadyMapped)));
+    client.set_chain_id_to_name(&owner, &42161u64, &other, &true);
+}
+
+#[test]
+fn test_is_initialized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+
+    let contract = env.register_contract(None, RozoIntentsContract);
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    assert!(!client.is_initialized());
+
+    client.initialize(&owner, &owner, &1500u64, &HashAlgo::Sha256);
+    assert!(client.is_initialized());
+}
+
+#[test]
+fn test_withdraw_fees_token_recipient_routing() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+
+    // Fallback: global recipient
+    let global = Address::generate(&env);
+    client.set_fee_rcpt(&owner, &global);
+    assert_eq!(client.get_token_fee_recipient(&token), None);
+    client.withdraw_fees(&owner, &token);
+    assert_eq!(token_client.balance(&global), 300_000i128);
+
+    // Token-specific treasury
+    let treasury = Address::generate(&env);
+    client.set_token_fee_recipient(&owner, &token, &treasury);
+    assert_eq!(client.get_token_fee_recipient(&token), Some(treasury.clone()));
+
+    let adapter = client.get_msger_adapter(&1u32).unwrap();
+    let intent_id = BytesN::from_array(&env, &[103u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+
+    client.withdraw_fees(&owner, &token);
+    assert_eq!(token_client.balance(&treasury), 300_000i128);
+    assert_eq!(token_client.balance(&global), 300_000i128);
+}
+
+#[test]
+fn test_pending_fees_for_recipient() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+
+    // A second token accrues fees under its own treasury
+    let (other_token, _other_client) = create_token_contract(&env, &owner);
+    StellarAssetClient::new(&env, &other_token).mint(&sender, &10_000_000_000i128);
+    let adapter = client.get_msger_adapter(&1u32).unwrap();
+    let intent_id = BytesN::from_array(&env, &[104u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &other_token));
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+
+    let treasury = Address::generate(&env);
+    client.set_token_fee_recipient(&owner, &other_token, &treasury);
+
+    // The global recipient (owner) is owed only the token without an override
+    assert_eq!(client.get_pending_fees_for_recipient(&owner), soroban_sdk::vec![&env, (token.clone(), 300_000i128)]);
+    assert_eq!(
+        client.get_pending_fees_for_recipient(&treasury),
+        soroban_sdk::vec![&env, (other_token.clone(), 300_000i128)]
+    );
+    assert_eq!(client.get_pending_fees_for_recipient(&Address::generate(&env)).len(), 0);
+
+    // Withdrawn tokens drop off the list
+    client.withdraw_fees(&owner, &token);
+    assert_eq!(client.get_pending_fees_for_recipient(&owner).len(), 0);
+}
+
+#[test]
+fn test_account_repayment_pays_g_address() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    // Hold the payout so the resolved address can be inspected without an account trustline
+    client.set_settlement_delay(&owner, &600u64);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let account = Address::from_string(&String::from_str(
+        &env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    let fill_hash = expected_fill_hash(&env, &contract, &intent_id);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &fill_hash,
+        &crate::address_to_bytes32(&env, &account),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        990_000_000i128,
+        true,
+    )
+    .unwrap();
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+
+    let settlement = client.get_pending_settlement(&fill_hash).unwrap();
+    assert_eq!(settlement.payout_address, account);
+}
+
+#[test]
+fn test_creation_fee_collected_on_fill() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_creation_fee(&owner, &50_000i128);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    assert_eq!(token_client.balance(&sender), initial_balance - 1_000_050_000i128);
+    assert_eq!(client.get_intent(&intent_id).creation_fee, 50_000i128);
+
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+
+    // 3 bps protocol fee plus the creation fee
+    assert_eq!(client.get_accum_fees(&token), 350_000i128);
+    assert!(client.is_solvent(&token));
+}
+
+#[test]
+fn test_creation_fee_refunded_when_unfilled() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_creation_fee(&owner, &50_000i128);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    set_timestamp(&env, 2001);
+    client.refund(&sender, &intent_id);
+
+    assert_eq!(token_client.balance(&sender), initial_balance);
+    assert_eq!(client.get_accum_fees(&token), 0);
+    assert_eq!(client.get_obligations(&token), 0);
+}
+
+#[test]
+fn test_is_intent_data_filled() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    assert!(!client.is_intent_data_filled(&intent_data));
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert!(client.is_intent_data_filled(&intent_data));
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    assert_eq!(client.get_owner(), owner);
+
+    let new_owner = Address::generate(&env);
+    client.propose_owner(&owner, &new_owner);
+    assert_eq!(client.get_pending_owner(), Some(new_owner.clone()));
+
+    // Proposal alone doesn't transfer control
+    assert_eq!(client.get_owner(), owner);
+    assert_eq!(client.try_set_protocol_fee(&new_owner, &5u32), Err(Ok(Error::NotOwner)));
+
+    // Only the proposed owner may accept
+    let stranger = Address::generate(&env);
+    assert_eq!(client.try_accept_owner(&stranger), Err(Ok(Error::NotAuthorized)));
+
+    client.accept_owner(&new_owner);
+    assert_eq!(client.get_owner(), new_owner);
+    assert_eq!(client.get_pending_owner(), None);
+
+    client.set_protocol_fee(&new_owner, &5u32);
+    assert_eq!(client.try_set_protocol_fee(&owner, &5u32), Err(Ok(Error::NotOwner)));
+}
+
+#[test]
+fn test_destination_token_allow_list() {
+    let (env, contract, owner, _sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    client.set_restrict_dest_tokens(&owner, &true);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    // Disallowed destination token
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Err(PolicyError::DestinationTokenNotAllowed.into())));
+
+    // Allowed destination token
+    client.set_dest_token_allowed(&owner, &1500u64, &intent_data.destination_token, &true);
+    assert!(client.is_dest_token_allowed(&1500u64, &intent_data.destination_token));
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&receiver), 990_000_000i128);
+}
+
+#[test]
+fn test_refunds_allowed_while_paused() {
+    let (env, contract, owner, sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let expired = BytesN::from_array(&env, &[104u8; 32]);
+    let stuck = BytesN::from_array(&env, &[105u8; 32]);
+    for id in [&expired, &stuck] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+
+    client.pause(&owner, &String::from_str(&env, "incident"));
+
+    // Fills are blocked
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Ok(Error::Paused)));
+
+    // Users and the admin can still recover funds
+    client.admin_refund(&owner, &stuck);
+    set_timestamp(&env, 2001);
+    client.refund(&sender, &expired);
+
+    assert_eq!(token_client.balance(&sender), initial_balance);
+}
+
+#[test]
+fn test_normalize_amount_exact() {
+    let (env, contract, _owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+    let client = RozoIntentsContractClient::new(&env, &contract);
+
+    // 1 USDC: 6 decimals <-> 7 decimals
+    assert_eq!(client.normalize_amount(&1_000_000i128, &6, &7, &true), (10_000_000i128, 0));
+    assert_eq!(client.normalize_amount(&10_000_000i128, &7, &6, &true), (1_000_000i128, 0));
+}
+
+#[test]
+fn test_normalize_amount_lossy() {
+    let (env, contract, _owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+    let client = RozoIntentsContractClient::new(&env, &contract);
+
+    // The last 7-decimal digit can't be represented with 6 decimals
+    assert_eq!(client.normalize_amount(&10_000_005i128, &7, &6, &false), (1_000_000i128, 5));
+
+    let result = client.try_normalize_amount(&10_000_005i128, &7, &6, &true);
+    assert_eq!(result, Err(Ok(Error::PrecisionLoss)));
+}
+
+#[test]
+fn test_fill_messenger_recorded() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let fill_hash = expected_fill_hash(&env, &contract, &intent_id);
+    assert_eq!(client.get_fill_messenger(&fill_hash), None);
+
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+    assert_eq!(client.get_fill_messenger(&fill_hash), Some(1u32));
+}
+
+#[test]
+fn test_frozen_intent_rejects_fill_and_refund() {
+    let (env, contract, owner, sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    // Source side: refund blocked while frozen
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    client.freeze_intent(&owner, &intent_id);
+    assert!(client.is_intent_frozen(&intent_id));
+
+    set_timestamp(&env, 2001);
+    let result = client.try_refund(&sender, &intent_id);
+    assert_eq!(result, Err(Ok(Error::IntentFrozen)));
+
+    // Destination side: fill blocked while frozen
+    set_timestamp(&env, 1000);
+    let fill_id = BytesN::from_array(&env, &[106u8; 32]);
+    let intent_data = incoming_intent_data(&env, &fill_id, &receiver, &token);
+    client.freeze_intent(&owner, &fill_id);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Ok(Error::IntentFrozen)));
+
+    // Both resume after unfreezing
+    client.unfreeze_intent(&owner, &fill_id);
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&receiver), 990_000_000i128);
+
+    client.unfreeze_intent(&owner, &intent_id);
+    assert!(!client.is_intent_frozen(&intent_id));
+    set_timestamp(&env, 2001);
+    client.refund(&sender, &intent_id);
+    assert_eq!(token_client.balance(&sender), initial_balance);
+}
+
+#[test]
+fn test_frozen_intent_blocks_notify() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    client.freeze_intent(&owner, &intent_id);
+
+    let fill_hash = expected_fill_hash(&env, &contract, &intent_id);
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &fill_hash,
+        &crate::address_to_bytes32(&env, &Address::generate(&env)),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        client.get_intent(&intent_id).destination_amount,
+        false,
+    )
+    .unwrap();
+    let result = client.try_notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(result, Err(Ok(Error::IntentFrozen)));
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+
+    client.unfreeze_intent(&owner, &intent_id);
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Filled);
+}
+
+#[test]
+fn test_extend_deadline() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_rozo_relayer(&owner, &Address::generate(&env));
+    client.set_rozo_threshold(&owner, &300u64);
+
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let deadline = client.get_intent(&intent_id).deadline;
+
+    // Only the sender or refund address may extend
+    let result = client.try_extend_deadline(&Address::generate(&env), &intent_id, &(deadline + 1000));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Must strictly move the deadline forward
+    let result = client.try_extend_deadline(&sender, &intent_id, &deadline);
+    assert_eq!(result, Err(Ok(Error::InvalidDeadline)));
+
+    client.extend_deadline(&sender, &intent_id, &(deadline + 1000));
+    let intent = client.get_intent(&intent_id);
+    assert_eq!(intent.deadline, deadline + 1000);
+    assert_eq!(intent.created_at, 1000);
+
+    // Fallback still measures from creation
+    set_timestamp(&env, 1300);
+    assert!(client.is_rozo_fallback_active(&intent_id));
+
+    // Not refundable under the old deadline
+    set_timestamp(&env, deadline + 1);
+    let result = client.try_refund(&sender, &intent_id);
+    assert_eq!(result, Err(Ok(Error::IntentNotExpired)));
+
+    // No extension once expired
+    set_timestamp(&env, deadline + 1000);
+    let result = client.try_extend_deadline(&sender, &intent_id, &(deadline + 5000));
+    assert_eq!(result, Err(Ok(Error::IntentExpired)));
+}
+
+#[test]
+fn test_create_intents_rejects_duplicate_in_batch() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let first = BytesN::from_array(&env, &[107u8; 32]);
+    let second = BytesN::from_array(&env, &[108u8; 32]);
+    let batch = soroban_sdk::vec![
+        &env,
+        default_params(&env, &first, &sender, &receiver, &token),
+        default_params(&env, &second, &sender, &receiver, &token),
+        default_params(&env, &first, &sender, &receiver, &token),
+    ];
+
+    let result = client.try_create_intents(&sender, &batch);
+    assert_eq!(result, Err(Ok(Error::IntentAlreadyExists)));
+
+    // Nothing was applied
+    assert!(!client.intent_exists(&first));
+    assert!(!client.intent_exists(&second));
+    assert_eq!(client.get_pending_count(), 0);
+    assert_eq!(token_client.balance(&sender), initial_balance);
+
+    // Without the duplicate the batch goes through
+    let batch = soroban_sdk::vec![
+        &env,
+        default_params(&env, &first, &sender, &receiver, &token),
+        default_params(&env, &second, &sender, &receiver, &token),
+    ];
+    client.create_intents(&sender, &batch);
+    assert!(client.intent_exists(&first));
+    assert!(client.intent_exists(&second));
+    assert_eq!(client.get_pending_count(), 2);
+}
+
+#[test]
+fn test_min_fill_amount_threshold() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let short = BytesN::from_array(&env, &[109u8; 32]);
+    let exact = BytesN::from_array(&env, &[110u8; 32]);
+    for id in [&short, &exact] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+    let min = client.min_fill_amount(&exact);
+    assert_eq!(min, client.get_intent(&exact).destination_amount);
+
+    let payee = crate::address_to_bytes32(&env, &Address::generate(&env));
+    let relayer_bytes = BytesN::from_array(&env, &[3u8; 32]);
+    for (id, amount) in [(&short, min - 1), (&exact, min)] {
+        let fill_hash = expected_fill_hash(&env, &contract, id);
+        let payload = encode_notify_payload(&env, id, &fill_hash, &payee, &relayer_bytes, amount, false).unwrap();
+        client.notify(&adapter, &1u32, &8453u64, &payload);
+    }
+
+    assert_eq!(client.get_intent(&short).status, IntentStatus::Failed);
+    assert_eq!(client.get_intent(&exact).status, IntentStatus::Filled);
+
+    let result = client.try_min_fill_amount(&BytesN::from_array(&env, &[42u8; 32]));
+    assert_eq!(result, Err(Ok(Error::IntentNotFound)));
+}
+
+#[test]
+fn test_emergency_evacuate_requires_pause() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let safe = Address::generate(&env);
+    let result = client.try_emergency_evacuate(&owner, &token, &safe);
+    assert_eq!(result, Err(Ok(Error::NotPaused)));
+    assert_eq!(token_client.balance(&safe), 0);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+}
+
+#[test]
+fn test_emergency_evacuate_marks_intents() {
+    let (env, contract, owner, sender, receiver, relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let open = BytesN::from_array(&env, &[111u8; 32]);
+    let refunded = BytesN::from_array(&env, &[112u8; 32]);
+    for id in [&open, &refunded] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+    client.admin_refund(&owner, &refunded);
+    let held = token_client.balance(&contract);
+
+    // Bonds are owed back to relayers and stay behind
+    client.deposit_bond(&relayer, &token, &50_000_000i128);
+
+    client.pause(&owner, &String::from_str(&env, "exploit"));
+    client.arm_emergency(&owner);
+    let safe = Address::generate(&env);
+    assert_eq!(client.emergency_evacuate(&owner, &token, &safe), 1);
+
+    assert_eq!(token_client.balance(&safe), held);
+    assert_eq!(token_client.balance(&contract), 50_000_000i128);
+    assert_eq!(client.get_intent(&open).status, IntentStatus::Unrecoverable);
+    assert_eq!(client.get_intent(&refunded).status, IntentStatus::Refunded);
+    assert_eq!(client.get_pending_count(), 0);
+    assert_eq!(client.get_obligations(&token), 50_000_000i128);
+    assert!(client.is_solvent(&token));
+
+    // Unrecoverable intents can no longer be refunded
+    client.unpause(&owner);
+    set_timestamp(&env, 2001);
+    let result = client.try_refund(&sender, &open);
+    assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+    let result = client.try_admin_refund(&owner, &open);
+    assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+}
+
+#[test]
+fn test_emergency_evacuate_then_cleanup_failed() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let open = BytesN::from_array(&env, &[133u8; 32]);
+    let failed = BytesN::from_array(&env, &[134u8; 32]);
+    for id in [&open, &failed] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+    client.set_intent_status(&owner, &failed, &IntentStatus::Failed);
+
+    // Failed intents aren't open: their escrow stays behind for cleanup_failed
+    client.pause(&owner, &String::from_str(&env, "exploit"));
+    client.arm_emergency(&owner);
+    let safe = Address::generate(&env);
+    assert_eq!(client.emergency_evacuate(&owner, &token, &safe), 1);
+    assert_eq!(token_client.balance(&safe), 1_000_000_000i128);
+    assert_eq!(client.get_obligations(&token), 1_000_000_000i128);
+    assert!(client.is_solvent(&token));
+
+    client.unpause(&owner);
+    assert_eq!(client.cleanup_failed(&owner, &soroban_sdk::vec![&env, failed], &true), 1);
+    assert_eq!(token_client.balance(&sender), initial_balance - 1_000_000_000i128);
+    assert_eq!(token_client.balance(&contract), 0);
+    assert_eq!(client.get_obligations(&token), 0);
+}
+
+#[test]
+fn test_get_intents_by_sender_pagination() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let first = BytesN::from_array(&env, &[113u8; 32]);
+    let second = BytesN::from_array(&env, &[114u8; 32]);
+    let third = BytesN::from_array(&env, &[115u8; 32]);
+    for id in [&first, &second, &third] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+
+    assert_eq!(
+        client.get_intents_by_sender(&sender, &0, &2),
+        soroban_sdk::vec![&env, first.clone(), second.clone()]
+    );
+    assert_eq!(client.get_intents_by_sender(&sender, &2, &2), soroban_sdk::vec![&env, third.clone()]);
+    assert_eq!(client.get_intents_by_sender(&sender, &3, &2).len(), 0);
+    assert_eq!(client.get_intents_by_sender(&sender, &0, &0).len(), 0);
+
+    // Settled intents stay listed; status tells them apart
+    client.admin_refund(&owner, &second);
+    assert_eq!(client.get_intents_by_sender(&sender, &0, &10).len(), 3);
+    assert_eq!(client.get_intent(&second).status, IntentStatus::Refunded);
+
+    assert_eq!(client.get_intents_by_sender(&Address::generate(&env), &0, &10).len(), 0);
+}
+
+#[test]
+fn test_expiry_buckets() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let ids = [
+        (BytesN::from_array(&env, &[116u8; 32]), 2000u64),
+        (BytesN::from_array(&env, &[117u8; 32]), 5000u64),
+        (BytesN::from_array(&env, &[118u8; 32]), 5500u64),
+        (BytesN::from_array(&env, &[119u8; 32]), 9000u64),
+    ];
+    for (id, deadline) in ids.iter() {
+        let mut params = default_params(&env, id, &sender, &receiver, &token);
+        params.deadline = *deadline;
+        client.create_intent(&sender, &params);
+    }
+    let (a, b, c, d) = (ids[0].0.clone(), ids[1].0.clone(), ids[2].0.clone(), ids[3].0.clone());
+
+    // One-hour buckets: 2000 -> 0, 5000 and 5500 -> 1, 9000 -> 2
+    assert_eq!(client.get_expiry_bucket(&0), soroban_sdk::vec![&env, a.clone()]);
+    assert_eq!(client.get_expiry_bucket(&1), soroban_sdk::vec![&env, b.clone(), c.clone()]);
+    assert_eq!(client.get_expiry_bucket(&2), soroban_sdk::vec![&env, d.clone()]);
+
+    assert_eq!(
+        client.get_expiring_intents(&0, &7200, &10),
+        soroban_sdk::vec![&env, a.clone(), b.clone(), c.clone()]
+    );
+    // Deadlines outside the range are dropped even when their bucket is read
+    assert_eq!(client.get_expiring_intents(&5000, &5500, &10), soroban_sdk::vec![&env, b.clone()]);
+    assert_eq!(client.get_expiring_intents(&0, &7200, &1), soroban_sdk::vec![&env, a.clone()]);
+    assert_eq!(client.get_expiring_intents(&7200, &7200, &10).len(), 0);
+
+    // Terminal intents leave the index; extended deadlines move buckets
+    client.admin_refund(&owner, &b);
+    assert_eq!(client.get_expiry_bucket(&1), soroban_sdk::vec![&env, c.clone()]);
+    client.extend_deadline(&sender, &a, &9500u64);
+    assert_eq!(client.get_expiry_bucket(&0).len(), 0);
+    assert_eq!(client.get_expiry_bucket(&2), soroban_sdk::vec![&env, d.clone(), a.clone()]);
+
+    let result = client.try_get_expiring_intents(&0, &(3600 * 200), &10);
+    assert_eq!(result, Err(Ok(Error::BatchTooLarge)));
+}
+
+#[test]
+fn test_keccak256_fill_hash_matches_evm() {
+    let env = Env::default();
+
+    // Reference: keccak256(abi.encode(intentData)) for the IntentData below in Solidity
+    let intent_data = IntentData {
+        intent_id: BytesN::from_array(&env, &[1u8; 32]),
+        sender: BytesN::from_array(&env, &[2u8; 32]),
+        refund_address: BytesN::from_array(&env, &[3u8; 32]),
+        source_token: BytesN::from_array(&env, &[4u8; 32]),
+        source_amount: 1_000_000_000i128,
+        source_chain_id: 1500u64,
+        destination_chain_id: 8453u64,
+        destination_token: BytesN::from_array(&env, &[5u8; 32]),
+        receiver: BytesN::from_array(&env, &[6u8; 32]),
+        destination_amount: 990_000_000i128,
+        deadline: 2000u64,
+        created_at: 900u64,
+        relayer: zero_bytes32(&env),
+        receiver_is_account: true,
+        preferred_messenger: None,
+        min_relayer_type: RelayerType::None,
+    };
+    let expected: [u8; 32] = [
+        0x1b, 0x9a, 0x9f, 0x7e, 0x34, 0xd1, 0x65, 0x42, 0x18, 0xf4, 0x62, 0x3f, 0x51, 0xe8, 0x54, 0xf5,
+        0x7c, 0x63, 0xd3, 0x47, 0x08, 0xef, 0xde, 0x5d, 0xee, 0x14, 0x4c, 0x89, 0xce, 0x18, 0x84, 0xae,
+    ];
+    assert_eq!(keccak256_fill_hash(&env, &intent_data), BytesN::from_array(&env, &expected));
+}
+
+#[test]
+fn test_keccak256_mode_selected_at_init() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let contract = env.register_contract(None, RozoIntentsContract);
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.initialize(&owner, &owner, &1500u64, &HashAlgo::Keccak256);
+    assert_eq!(client.get_hash_algo(), HashAlgo::Keccak256);
+
+    let (token, _token_client) = create_token_contract(&env, &owner);
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &Address::generate(&env), &token);
+    let hash = env.as_contract(&contract, || compute_fill_hash(&env, &intent_data));
+    assert_eq!(hash, keccak256_fill_hash(&env, &intent_data));
+    assert_ne!(hash, sha256_fill_hash(&env, &intent_data));
+}
+
+#[test]
+fn test_fill_hash_algorithms_are_stable_and_distinct() {
+    let env = Env::default();
+    let (token, _token_client) = create_token_contract(&env, &Address::generate(&env));
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &Address::generate(&env), &token);
+
+    assert_eq!(sha256_fill_hash(&env, &intent_data), sha256_fill_hash(&env, &intent_data.clone()));
+    assert_eq!(keccak256_fill_hash(&env, &intent_data), keccak256_fill_hash(&env, &intent_data.clone()));
+    assert_ne!(sha256_fill_hash(&env, &intent_data), keccak256_fill_hash(&env, &intent_data));
+}
+
+#[test]
+fn test_keccak256_mode_completes_fills_end_to_end() {
+    let (env, contract, owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    env.as_contract(&contract, || storage::set_hash_algo(&env, &HashAlgo::Keccak256));
+    set_timestamp(&env, 1000);
+
+    // Source side: notify must carry the keccak256 fill hash
+    let stale = BytesN::from_array(&env, &[51u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &stale, &sender, &receiver, &token));
+    let intent = client.get_intent(&stale);
+    let sha_hash = env.as_contract(&contract, || sha256_fill_hash(&env, &intent_data_from_intent(&env, &intent)));
+    let payload = encode_notify_payload(
+        &env,
+        &stale,
+        &sha_hash,
+        &crate::address_to_bytes32(&env, &Address::generate(&env)),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        intent.destination_amount,
+        false,
+    )
+    .unwrap();
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(client.get_intent(&stale).status, IntentStatus::Failed);
+
+    let intent_id = BytesN::from_array(&env, &[52u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let fill_hash = notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Filled);
+    let intent = client.get_intent(&intent_id);
+    let keccak_hash = env.as_contract(&contract, || keccak256_fill_hash(&env, &intent_data_from_intent(&env, &intent)));
+    assert_eq!(fill_hash, keccak_hash);
+
+    // Destination side: the fill record is keyed by the keccak256 hash
+    let intent_data = incoming_intent_data(&env, &BytesN::from_array(&env, &[53u8; 32]), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert!(client.get_fill_record(&keccak256_fill_hash(&env, &intent_data)).is_some());
+    assert!(client.get_fill_record(&sha256_fill_hash(&env, &intent_data)).is_none());
+}
+
+#[test]
+fn test_auction_bids_and_window() {
+    let (env, contract, owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let min = client.get_intent(&intent_id).destination_amount;
+
+    // Auctions are off until a window is set
+    let result = client.try_bid(&relayer, &intent_id, &min);
+    assert_eq!(result, Err(Ok(Error::AuctionClosed)));
+    client.set_auction_window(&owner, &60u64);
+
+    // Unregistered relayers and underbids are rejected
+    let result = client.try_bid(&Address::generate(&env), &intent_id, &min);
+    assert_eq!(result, Err(Ok(Error::NotRelayer)));
+    let result = client.try_bid(&relayer, &intent_id, &(min - 1));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    client.bid(&relayer, &intent_id, &min);
+    client.bid(&relayer, &intent_id, &(min + 10));
+    let bids = client.get_bids(&intent_id);
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids.get(0).unwrap().destination_amount, min + 10);
+
+    // No finalizing while open, no bidding once closed
+    let result = client.try_finalize_auction(&intent_id);
+    assert_eq!(result, Err(Ok(Error::AuctionNotClosed)));
+    set_timestamp(&env, 1060);
+    let result = client.try_bid(&relayer, &intent_id, &(min + 20));
+    assert_eq!(result, Err(Ok(Error::AuctionClosed)));
+}
+
+#[test]
+fn test_auction_best_bid_wins() {
+    let (env, contract, owner, sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_auction_window(&owner, &60u64);
+    let rival = Address::generate(&env);
+    client.add_relayer(&owner, &rival, &RelayerType::External);
+
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let min = client.get_intent(&intent_id).destination_amount;
+
+    client.bid(&relayer, &intent_id, &(min + 5));
+    client.bid(&rival, &intent_id, &(min + 7));
+
+    set_timestamp(&env, 1060);
+    assert_eq!(client.finalize_auction(&intent_id), rival);
+
+    let intent = client.get_intent(&intent_id);
+    assert_eq!(intent.relayer, crate::address_to_bytes32(&env, &rival));
+    assert_eq!(intent.destination_amount, min + 7);
+    assert_eq!(client.get_bids(&intent_id).len(), 0);
+
+    // Assigned intents are no longer auctioned
+    let result = client.try_finalize_auction(&intent_id);
+    assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+}
+
+#[test]
+fn test_auction_without_bids() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_auction_window(&owner, &60u64);
+    set_timestamp(&env, 1000);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    set_timestamp(&env, 1060);
+    let result = client.try_finalize_auction(&intent_id);
+    assert_eq!(result, Err(Ok(Error::NoBids)));
+}
+
+#[test]
+fn test_total_relayer_payouts_accumulate() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    assert_eq!(client.get_total_relayer_payouts(&token), 0);
+
+    let payee = Address::generate(&env);
+    let first = BytesN::from_array(&env, &[116u8; 32]);
+    let second = BytesN::from_array(&env, &[117u8; 32]);
+    for id in [&first, &second] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+        notify_matching_fill(&env, &contract, &adapter, id, &payee);
+    }
+
+    // 1000 USDC each, less the 3 bps protocol fee
+    assert_eq!(client.get_total_relayer_payouts(&token), 1_999_400_000i128);
+    assert_eq!(client.get_total_relayer_payouts(&token), token_client.balance(&payee));
+    assert_eq!(
+        client.get_total_volume(&token),
+        client.get_total_relayer_payouts(&token) + client.get_accum_fees(&token)
+    );
+}
+
+#[test]
+fn test_fill_pays_typed_receiver() {
+    let (env, contract, owner, _sender, _receiver, relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let token = env.register_contract(None, MockToken);
+    let token_client = MockTokenClient::new(&env, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    // Account receiver: same 32 bytes must resolve to the G... address
+    let account = Address::from_string(&String::from_str(
+        &env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    let mut intent_data = incoming_intent_data(&env, &BytesN::from_array(&env, &[118u8; 32]), &account, &token);
+    intent_data.receiver_is_account = true;
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&account), 990_000_000i128);
+
+    // Contract receiver
+    let contract_receiver = Address::generate(&env);
+    let intent_data = incoming_intent_data(&env, &BytesN::from_array(&env, &[119u8; 32]), &contract_receiver, &token);
+    assert!(!intent_data.receiver_is_account);
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(token_client.balance(&contract_receiver), 990_000_000i128);
+}
+
+#[test]
+fn test_refund_fee() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    // A fee that would leave nothing is rejected
+    let result = client.try_set_refund_fee(&owner, &10000u32);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+
+    let unfeed = BytesN::from_array(&env, &[120u8; 32]);
+    let feed = BytesN::from_array(&env, &[121u8; 32]);
+    let admin_refunded = BytesN::from_array(&env, &[122u8; 32]);
+    for id in [&unfeed, &feed, &admin_refunded] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+
+    // Without a fee the full amount comes back
+    set_timestamp(&env, 2001);
+    client.refund(&sender, &unfeed);
+    assert_eq!(token_client.balance(&sender), initial_balance - 2_000_000_000i128);
+    assert_eq!(client.get_accum_fees(&token), 0);
+
+    // 50 bps kept as protocol fees
+    client.arm_emergency(&owner);
+    client.set_refund_fee(&owner, &50u32);
+    assert_eq!(client.get_refund_fee(), 50u32);
+    client.refund(&sender, &feed);
+    assert_eq!(token_client.balance(&sender), initial_balance - 1_005_000_000i128);
+    assert_eq!(client.get_accum_fees(&token), 5_000_000i128);
+
+    // Admin refunds are never charged
+    client.admin_refund(&owner, &admin_refunded);
+    assert_eq!(token_client.balance(&sender), initial_balance - 5_000_000i128);
+    assert_eq!(client.get_accum_fees(&token), 5_000_000i128);
+    assert!(client.is_solvent(&token));
+}
+
+#[test]
+fn test_reentrant_token_cannot_call_back() {
+    let (env, contract, owner, _sender, receiver, relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let token = env.register_contract(None, ReentrantToken);
+    ReentrantTokenClient::new(&env, &token).set_target(&contract);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert!(result.is_err());
+    assert!(client.get_fill_record(&sha256_fill_hash(&env, &intent_data)).is_none());
+}
+
+#[test]
+fn test_reentrancy_lock_blocks_guarded_calls() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    // Simulate a call already in progress
+    env.as_contract(&contract, || storage::acquire_lock(&env).unwrap());
+    set_timestamp(&env, 2001);
+    let result = client.try_refund(&sender, &intent_id);
+    assert_eq!(result, Err(Ok(Error::Reentrancy)));
+    let result = client.try_admin_refund(&owner, &intent_id);
+    assert_eq!(result, Err(Ok(Error::Reentrancy)));
+
+    // Once released, the lock is taken and cleared around each call
+    env.as_contract(&contract, || storage::release_lock(&env));
+    client.refund(&sender, &intent_id);
+    assert_eq!(token_client.balance(&sender), initial_balance);
+    // Left unlocked after a successful call
+    env.as_contract(&contract, || assert!(storage::acquire_lock(&env).is_ok()));
+}
+
+#[test]
+fn test_min_intent_duration() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    assert_eq!(client.get_min_duration(), 0);
+
+    // Unset: a deadline one second out is still accepted
+    let mut params = default_params(&env, &BytesN::from_array(&env, &[123u8; 32]), &sender, &receiver, &token);
+    params.deadline = 1001;
+    client.create_intent(&sender, &params);
+
+    client.set_min_duration(&owner, &600u64);
+
+    let mut params = default_params(&env, &BytesN::from_array(&env, &[124u8; 32]), &sender, &receiver, &token);
+    params.deadline = 1599;
+    let result = client.try_create_intent(&sender, &params);
+    assert_eq!(result, Err(Err(PolicyError::DeadlineTooSoon.into())));
+
+    params.deadline = 1600;
+    client.create_intent(&sender, &params);
+    assert!(client.intent_exists(&params.intent_id));
+}
+
+#[test]
+fn test_get_intents_batch() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let first = BytesN::from_array(&env, &[125u8; 32]);
+    let missing = BytesN::from_array(&env, &[126u8; 32]);
+    let second = BytesN::from_array(&env, &[127u8; 32]);
+    for id in [&first, &second] {
+        client.create_intent(&sender, &default_params(&env, id, &sender, &receiver, &token));
+    }
+
+    let intents = client.get_intents(&soroban_sdk::vec![&env, first.clone(), missing.clone(), second.clone()]);
+    assert_eq!(intents.len(), 3);
+    assert_eq!(intents.get(0).unwrap().unwrap().intent_id, first);
+    assert!(intents.get(1).unwrap().is_none());
+    assert_eq!(intents.get(2).unwrap().unwrap().intent_id, second);
+
+    let mut too_many = soroban_sdk::Vec::new(&env);
+    for _ in 0..51 {
+        too_many.push_back(missing.clone());
+    }
+    assert_eq!(client.try_get_intents(&too_many), Err(Ok(Error::BatchTooLarge)));
+}
+
+#[test]
+fn test_relayer_rebate_reconciles() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    client.arm_emergency(&owner);
+
+    let result = client.try_set_relayer_rebate_bps(&owner, &10001u32);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+    client.set_relayer_rebate_bps(&owner, &5000u32);
+    assert_eq!(client.get_relayer_rebate_bps(), 5000u32);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let (_, quoted) = client.fill_profitability(&intent_id, &None);
+    let payee = Address::generate(&env);
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &payee);
+
+    // 3 bps fee = 300_000; half of it goes back to the relayer
+    let fees = client.get_accum_fees(&token);
+    let payout = token_client.balance(&payee);
+    assert_eq!(payout, quoted);
+    assert_eq!(fees, 150_000i128);
+    assert_eq!(payout, 1_000_000_000i128 - 300_000 + 150_000);
+    assert_eq!(fees + payout, 1_000_000_000i128);
+}
+
+/// Smallest wasm module the host accepts as a contract: just the
+/// contractenvmetav0 section declaring interface version 21
+fn minimal_contract_wasm(env: &Env) -> Bytes {
+    let mut wasm = Bytes::from_array(env, &[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00]);
+    wasm.append(&Bytes::from_array(env, &[0x00, 0x1e, 0x11]));
+    wasm.append(&Bytes::from_slice(env, b"contractenvmetav0"));
+    wasm.append(&Bytes::from_array(env, &[0, 0, 0, 0, 0, 0, 0, 21, 0, 0, 0, 0]));
+    wasm
+}
+
+#[test]
+fn test_upgrade() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    assert_eq!(client.get_version(), 1);
+
+    let wasm_hash = env.deployer().upload_contract_wasm(minimal_contract_wasm(&env));
+
+    let result = client.try_upgrade(&Address::generate(&env), &wasm_hash);
+    assert_eq!(result, Err(Ok(Error::NotOwner)));
+    assert_eq!(client.get_version(), 1);
+
+    client.upgrade(&owner, &wasm_hash);
+
+    let mut upgraded = false;
+    for (emitter, topics, _) in env.events().all().iter() {
+        if emitter == contract && Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap()
+            == Symbol::new(&env, "contract_upgraded")
+        {
+            upgraded = true;
+        }
+    }
+    assert!(upgraded);
+
+    // The new code has no functions, so read the bumped version from storage
+    assert_eq!(env.as_contract(&contract, || storage::get_contract_version(&env)), 2);
+}
+
+#[test]
+fn test_compact_notify_payload_round_trip() {
+    let (env, contract, owner, _sender, _receiver, _relayer, _token, _token_client) = setup_env();
+
+    let intent_id = BytesN::from_array(&env, &[128u8; 32]);
+    let fill_hash = BytesN::from_array(&env, &[129u8; 32]);
+    let repayment = BytesN::from_array(&env, &[130u8; 32]);
+    let relayer = BytesN::from_array(&env, &[131u8; 32]);
+
+    let payload =
+        encode_compact_notify_payload(&env, &intent_id, &fill_hash, &repayment, &relayer, 990_000_000i128, true)
+            .unwrap();
+    assert_eq!(payload.len(), COMPACT_NOTIFY_PAYLOAD_LEN);
+    assert_eq!(
+        decode_notify_payload(&env, &payload).unwrap(),
+        (fill_hash.clone(), intent_id.clone(), repayment.clone(), relayer.clone(), 990_000_000i128, true)
+    );
+
+    // Compact only for this chain and chains the owner typed as Stellar; the chain name
+    // plays no part. Everything else gets the padded layout.
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_chain_id_to_name(&owner, &1600u64, &String::from_str(&env, "xlm-testnet"), &false);
+    client.set_chain_type(&owner, &1600u64, &ChainType::Stellar);
+    client.set_chain_id_to_name(&owner, &1700u64, &String::from_str(&env, "stellar-evm"), &false);
+    assert_eq!(
+        client.try_set_chain_type(&Address::generate(&env), &1700u64, &ChainType::Stellar),
+        Err(Ok(Error::NotOwner))
+    );
+    let build = |chain_id: u64| {
+        env.as_contract(&contract, || {
+            build_notify_payload(&env, chain_id, &intent_id, &fill_hash, &repayment, &relayer, 1, false).unwrap()
+        })
+    };
+
+    // v1 until the owner opts into v2
+    assert!(!client.is_notify_payload_v2());
+    assert_eq!(build(1500u64).len(), COMPACT_NOTIFY_PAYLOAD_LEN);
+    assert_eq!(build(1600u64).len(), COMPACT_NOTIFY_PAYLOAD_LEN);
+    assert_eq!(build(8453u64).len(), NOTIFY_PAYLOAD_LEN);
+    assert_eq!(notify_payload_source_chain_id(&build(8453u64)), None);
+    assert_eq!(
+        client.try_set_notify_payload_v2(&Address::generate(&env), &true),
+        Err(Ok(Error::NotOwner))
+    );
+
+    client.set_notify_payload_v2(&owner, &true);
+    let stellar = build(1500u64);
+    assert_eq!(stellar.len(), COMPACT_NOTIFY_PAYLOAD_V2_LEN);
+    assert_eq!(notify_payload_source_chain_id(&stellar), Some(1500u64));
+    let other_stellar = build(1600u64);
+    assert_eq!(other_stellar.len(), COMPACT_NOTIFY_PAYLOAD_V2_LEN);
+    assert_eq!(notify_payload_source_chain_id(&other_stellar), Some(1600u64));
+    let base = build(8453u64);
+    assert_eq!(base.len(), NOTIFY_PAYLOAD_V2_LEN);
+    assert_eq!(notify_payload_source_chain_id(&base), Some(8453u64));
+    assert_eq!(build(1700u64).len(), NOTIFY_PAYLOAD_V2_LEN);
+    assert_eq!(build(42161u64).len(), NOTIFY_PAYLOAD_V2_LEN);
+}
+
+#[test]
+fn test_notify_payload_versions() {
+    let env = Env::default();
+
+    let intent_id = BytesN::from_array(&env, &[132u8; 32]);
+    let fill_hash = BytesN::from_array(&env, &[133u8; 32]);
+    let repayment = BytesN::from_array(&env, &[134u8; 32]);
+    let relayer = BytesN::from_array(&env, &[135u8; 32]);
+    let expected = (fill_hash.clone(), intent_id.clone(), repayment.clone(), relayer.clone(), 42i128, false);
+
+    let v1 = encode_compact_notify_payload(&env, &intent_id, &fill_hash, &repayment, &relayer, 42i128, false).unwrap();
+    assert_eq!(v1.get(0), Some(NOTIFY_PAYLOAD_V1));
+    assert_eq!(decode_notify_payload(&env, &v1).unwrap(), expected.clone());
+
+    // v2 carries source_chain_id between the tag and the v1 body
+    let mut v2 = Bytes::new(&env);
+    v2.push_back(NOTIFY_PAYLOAD_V2);
+    v2.append(&Bytes::from_array(&env, &1500u64.to_be_bytes()));
+    v2.append(&v1.slice(1..));
+    assert_eq!(v2.len(), COMPACT_NOTIFY_PAYLOAD_V2_LEN);
+    assert_eq!(decode_notify_payload(&env, &v2).unwrap(), expected);
+
+    // A known tag at the wrong length is a length error
+    let mut truncated_v2 = Bytes::new(&env);
+    truncated_v2.push_back(NOTIFY_PAYLOAD_V2);
+    truncated_v2.append(&v1.slice(1..));
+    assert_eq!(decode_notify_payload(&env, &truncated_v2), Err(Error::PayloadTooShort));
+
+    let mut unknown = v1.clone();
+    unknown.set(0, 9);
+    assert_eq!(decode_notify_payload(&env, &unknown), Err(Error::InvalidPayload));
+
+    // The EVM layout carries its tag in a leading word
+    let evm_v1 = encode_notify_payload(&env, &intent_id, &fill_hash, &repayment, &relayer, 42i128, false).unwrap();
+    assert_eq!(evm_v1.get(31), Some(NOTIFY_PAYLOAD_V1));
+    assert_eq!(decode_notify_payload(&env, &evm_v1).unwrap(), expected.clone());
+    assert_eq!(notify_payload_source_chain_id(&evm_v1), None);
+
+    let mut evm_v2 = Bytes::from_array(&env, &crate::abi_word_u64(NOTIFY_PAYLOAD_V2 as u64));
+    evm_v2.append(&Bytes::from_array(&env, &crate::abi_word_u64(1500u64)));
+    evm_v2.append(&evm_v1.slice(32..));
+    assert_eq!(decode_notify_payload(&env, &evm_v2).unwrap(), expected);
+    assert_eq!(notify_payload_source_chain_id(&evm_v2), Some(1500u64));
+
+    // The untagged pre-versioning layout is no longer accepted
+    assert_eq!(decode_notify_payload(&env, &evm_v1.slice(32..)), Err(Error::InvalidPayload));
+    let mut unknown_evm = evm_v1.clone();
+    unknown_evm.set(31, 9);
+    assert_eq!(decode_notify_payload(&env, &unknown_evm), Err(Error::InvalidPayload));
+    let mut high_bits = evm_v1.clone();
+    high_bits.set(0, 1);
+    assert_eq!(decode_notify_payload(&env, &high_bits), Err(Error::InvalidPayload));
+}
+
+/// Notify payload as RozoIntents.sol's _notify encodes it with abi.encode:
+/// eight 32-byte words, version 2 with the source chain ID in the second word
+const EVM_NOTIFY_PAYLOAD_V2: [u8; 256] = [
+        // version = 2
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+        // sourceChainId = 1500
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0xdc,
+        // intentId
+        0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+        0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+        // fillHash
+        0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+        0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+        // repaymentAddress (EVM address, left-padded)
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5e, 0x5e, 0x5e, 0x5e,
+        0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e, 0x5e,
+        // relayer (EVM address, left-padded)
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xab, 0xab, 0xab, 0xab,
+        0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab,
+        // amount = 990_000_000
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0x02, 0x33, 0x80,
+        // flags: repaymentIsAccount
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+
+#[test]
+fn test_decode_evm_v2_payload_vector() {
+    let env = Env::default();
+    let payload = Bytes::from_array(&env, &EVM_NOTIFY_PAYLOAD_V2);
+    assert_eq!(payload.len(), NOTIFY_PAYLOAD_V2_LEN);
+
+    let mut repayment = [0u8; 32];
+    repayment[12..].copy_from_slice(&[0x5e; 20]);
+    let mut relayer = [0u8; 32];
+    relayer[12..].copy_from_slice(&[0xab; 20]);
+    assert_eq!(
+        decode_notify_payload(&env, &payload).unwrap(),
+        (
+            BytesN::from_array(&env, &[0x22; 32]),
+            BytesN::from_array(&env, &[0x11; 32]),
+            BytesN::from_array(&env, &repayment),
+            BytesN::from_array(&env, &relayer),
+            990_000_000i128,
+            true,
+        )
+    );
+    assert_eq!(notify_payload_source_chain_id(&payload), Some(1500u64));
+}
+
+#[test]
+fn test_notify_rejects_v2_payload_for_other_source_chain() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let fill_hash = expected_fill_hash(&env, &contract, &intent_id);
+    let payee = crate::address_to_bytes32(&env, &Address::generate(&env));
+    let relayer = BytesN::from_array(&env, &[3u8; 32]);
+
+    // Built for an intent created on Base, not this chain
+    client.set_notify_payload_v2(&owner, &true);
+    let misrouted = env.as_contract(&contract, || {
+        build_notify_payload(&env, 8453u64, &intent_id, &fill_hash, &payee, &relayer, 990_000_000i128, false).unwrap()
+    });
+    let result = client.try_notify(&adapter, &1u32, &8453u64, &misrouted);
+    assert_eq!(result, Err(Ok(Error::WrongChain)));
+    assert_eq!(client.try_preview_notify(&misrouted), Err(Ok(Error::WrongChain)));
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+
+    let mut routed = Bytes::from_array(&env, &crate::abi_word_u64(NOTIFY_PAYLOAD_V2 as u64));
+    routed.append(&Bytes::from_array(&env, &crate::abi_word_u64(1500u64)));
+    routed.append(&misrouted.slice(64..));
+    client.notify(&adapter, &1u32, &8453u64, &routed);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Filled);
+}
+
+#[test]
+fn test_notify_accepts_compact_payload() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let payee = Address::generate(&env);
+    let payload = encode_compact_notify_payload(
+        &env,
+        &intent_id,
+        &expected_fill_hash(&env, &contract, &intent_id),
+        &crate::address_to_bytes32(&env, &payee),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        client.get_intent(&intent_id).destination_amount,
+        false,
+    )
+    .unwrap();
+    client.notify(&adapter, &1u32, &1500u64, &payload);
+
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Filled);
+    assert_eq!(token_client.balance(&payee), 999_700_000i128);
+}
+
+#[test]
+fn test_withdrawable_fees_net_of_reserved() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    accrue_fees(&env, &contract, &owner, &sender, &receiver, &token);
+    assert_eq!(client.get_withdrawable_fees(&token), 300_000i128);
+
+    // An owed settlement the balance doesn't cover eats into withdrawable fees
+    env.as_contract(&contract, || storage::add_settlement_owed(&env, &token, 100_000i128));
+    assert_eq!(client.get_accum_fees(&token), 300_000i128);
+    assert_eq!(client.get_withdrawable_fees(&token), 200_000i128);
+
+    // Relayer bonds are held back the same way
+    let relayer = Address::generate(&env);
+    env.as_contract(&contract, || storage::set_bond(&env, &relayer, &token, 50_000i128));
+    assert_eq!(client.get_withdrawable_fees(&token), 150_000i128);
+
+    // Withdrawals can't dip into what's reserved
+    let destination = Address::generate(&env);
+    let result = client.try_withdraw_fees_to(&owner, &token, &destination, &200_000i128);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+    client.withdraw_fees(&owner, &token);
+    assert_eq!(client.get_total_withdrawn(&token), 150_000i128);
+    assert_eq!(client.get_accum_fees(&token), 150_000i128);
+    assert_eq!(client.get_withdrawable_fees(&token), 0);
+
+    env.as_contract(&contract, || storage::add_settlement_owed(&env, &token, 500_000i128));
+    assert_eq!(client.get_withdrawable_fees(&token), 0);
+}
+
+#[test]
+fn test_withdrawable_fees_exclude_reserved_rebate() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    client.set_relayer_rebate_bps(&owner, &5000u32);
+    client.set_pull_payouts(&owner, &true);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let payee = Address::generate(&env);
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &payee);
+
+    // Half of the 300_000 fee is rebated through a payout that hasn't been claimed yet
+    assert_eq!(client.get_accum_fees(&token), 300_000i128);
+    assert_eq!(client.get_reserved_fees(&token), 150_000i128);
+    assert_eq!(client.get_withdrawable_fees(&token), 150_000i128);
+    assert_eq!(client.get_obligations(&token), token_client.balance(&contract));
+    let result = client.try_withdraw_fees_to(&owner, &token, &Address::generate(&env), &300_000i128);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    // Claiming pays the rebate and settles the reservation
+    assert_eq!(client.claim_payout(&payee, &token), 1_000_000_000i128 - 150_000);
+    assert_eq!(client.get_accum_fees(&token), 150_000i128);
+    assert_eq!(client.get_reserved_fees(&token), 0);
+    assert_eq!(client.get_withdrawable_fees(&token), 150_000i128);
+    assert!(client.is_solvent(&token));
+}
+
+#[test]
+fn test_fee_on_transfer_token_escrows_net_amount() {
+    let (env, contract, _owner, sender, receiver, _relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let token = env.register_contract(None, FeeOnTransferToken);
+    let token_client = FeeOnTransferTokenClient::new(&env, &token);
+    token_client.mint(&sender, &10_000_000_000i128);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    // 1% of the 100 USDC never reached the contract
+    assert_eq!(client.get_intent(&intent_id).source_amount, 990_000_000i128);
+    assert_eq!(token_client.balance(&contract), 990_000_000i128);
+    assert_eq!(client.get_obligations(&token), 990_000_000i128);
+    assert!(client.is_solvent(&token));
+}
+
+#[test]
+fn test_cancel_intent_before_fill() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    let initial_balance = token_client.balance(&sender);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let result = client.try_cancel_intent(&Address::generate(&env), &intent_id);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Before the deadline, unlike refund
+    client.cancel_intent(&sender, &intent_id);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Refunded);
+    assert_eq!(token_client.balance(&sender), initial_balance);
+
+    let mut cancelled = false;
+    for (emitter, topics, _) in env.events().all().iter() {
+        if emitter == contract
+            && Symbol::try_from_val(&env, &topics.get(0).unwrap()).unwrap() == Symbol::new(&env, "intent_cancelled")
+        {
+            cancelled = true;
+        }
+    }
+    assert!(cancelled);
+
+    // A late notify can no longer settle it
+    let result = client.try_cancel_intent(&sender, &intent_id);
+    assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &expected_fill_hash(&env, &contract, &intent_id),
+        &crate::address_to_bytes32(&env, &Address::generate(&env)),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        990_000_000i128,
+        false,
+    )
+    .unwrap();
+    let result = client.try_notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(result, Err(Ok(Error::InvalidStatus)));
+}
+
+#[test]
+fn test_relayer_metadata() {
+    let (env, contract, owner, _sender, _receiver, relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    assert_eq!(client.get_relayer_metadata(&relayer), None);
+
+    let endpoint = String::from_str(&env, "https://relayer.example.com");
+    let name = String::from_str(&env, "Example Relayer");
+    client.set_relayer_metadata(&relayer, &endpoint, &name);
+    assert_eq!(client.get_relayer_metadata(&relayer), Some((endpoint.clone(), name.clone())));
+
+    let outsider = Address::generate(&env);
+    let result = client.try_set_relayer_metadata(&outsider, &endpoint, &name);
+    assert_eq!(result, Err(Ok(Error::NotRelayer)));
+    assert_eq!(client.get_relayer_metadata(&outsider), None);
+
+    // Removing the relayer drops its listing
+    client.remove_relayer(&owner, &relayer);
+    assert_eq!(client.get_relayer_metadata(&relayer), None);
+}
+
+#[test]
+fn test_relayer_type_counts() {
+    let (env, contract, owner, _sender, _receiver, relayer, _token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    // setup_env registers one External relayer
+    assert_eq!(client.get_relayer_type_counts(), (0u32, 1u32, 0u32));
+
+    let rozo_a = Address::generate(&env);
+    let rozo_b = Address::generate(&env);
+    let external = Address::generate(&env);
+    client.add_relayer(&owner, &rozo_a, &RelayerType::Rozo);
+    client.add_relayer(&owner, &rozo_b, &RelayerType::Rozo);
+    client.add_relayer(&owner, &external, &RelayerType::External);
+    assert_eq!(client.get_relayer_type_counts(), (2u32, 2u32, 0u32));
+
+    // Re-adding with the same type does not double count
+    client.add_relayer(&owner, &rozo_a, &RelayerType::Rozo);
+    assert_eq!(client.get_relayer_type_counts(), (2u32, 2u32, 0u32));
+
+    // Promoting moves the relayer between buckets
+    client.add_relayer(&owner, &relayer, &RelayerType::Rozo);
+    assert_eq!(client.get_relayer_type_counts(), (3u32, 1u32, 0u32));
+
+    client.remove_relayer(&owner, &rozo_b);
+    client.remove_relayer(&owner, &rozo_b);
+    assert_eq!(client.get_relayer_type_counts(), (2u32, 1u32, 0u32));
+}
+
+#[test]
+fn test_retry_notify_all_escalates_past_failing_messenger() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    // Messenger 1's bridge goes down; messenger 2 is healthy
+    let failing = env.register_contract(None, FailingAdapter);
+    client.set_msger_adapter(&owner, &1u32, &failing);
+    let healthy = env.register_contract(None, MockAdapter);
+    client.set_msger_adapter(&owner, &2u32, &healthy);
+
+    assert_eq!(client.retry_notify_all(&relayer, &intent_data), 2u32);
+
+    // Only the original filler may escalate
+    let stranger = Address::generate(&env);
+    let result = client.try_retry_notify_all(&stranger, &intent_data);
+    assert_eq!(result, Err(Ok(Error::NotAssignedRelayer)));
+
+    // With every messenger down the retry fails
+    client.set_msger_adapter(&owner, &2u32, &failing);
+    let result = client.try_retry_notify_all(&relayer, &intent_data);
+    assert_eq!(result, Err(Ok(Error::NoMessengerAvailable)));
+}
+
+#[test]
+fn test_notify_rejects_chain_outside_adapter_set() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    assert_eq!(client.get_adapter_chains(&1u32).len(), 0);
+
+    // Messenger 1 may only deliver fills from Base
+    client.set_adapter_chains(&owner, &1u32, &soroban_sdk::vec![&env, 8453u64]);
+    assert_eq!(client.get_adapter_chains(&1u32), soroban_sdk::vec![&env, 8453u64]);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    let payload = encode_notify_payload(
+        &env,
+        &intent_id,
+        &expected_fill_hash(&env, &contract, &intent_id),
+        &crate::address_to_bytes32(&env, &Address::generate(&env)),
+        &BytesN::from_array(&env, &[3u8; 32]),
+        990_000_000i128,
+        false,
+    )
+    .unwrap();
+    let result = client.try_notify(&adapter, &1u32, &1u64, &payload);
+    assert_eq!(result, Err(Ok(Error::WrongChain)));
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+
+    // The allowed chain still completes the fill
+    client.notify(&adapter, &1u32, &8453u64, &payload);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Filled);
+}
+
+#[test]
+fn test_intent_created_topic_names_destination_chain() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+
+    // Base (8453) is named "base" in setup_env
+    let intent_id = BytesN::from_array(&env, &[31u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    let (topics, _) = last_event(&env, &contract, "intent_created");
+    let chain = String::try_from_val(&env, &topics.get(2).unwrap()).unwrap();
+    assert_eq!(chain, String::from_str(&env, "base"));
+
+    // Unnamed corridors fall back to the numeric chain ID
+    let intent_id = BytesN::from_array(&env, &[32u8; 32]);
+    let mut params = default_params(&env, &intent_id, &sender, &receiver, &token);
+    params.destination_chain_id = 10u64;
+    client.create_intent(&sender, &params);
+    let (topics, _) = last_event(&env, &contract, "intent_created");
+    assert_eq!(u64::try_from_val(&env, &topics.get(2).unwrap()).unwrap(), 10u64);
+}
+
+#[test]
+fn test_sender_intent_count_survives_refunds() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    assert_eq!(client.get_sender_intent_count(&sender), 0);
+
+    let first = BytesN::from_array(&env, &[41u8; 32]);
+    let second = BytesN::from_array(&env, &[42u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &first, &sender, &receiver, &token));
+    client.create_intent(&sender, &default_params(&env, &second, &sender, &receiver, &token));
+    assert_eq!(client.get_sender_intent_count(&sender), 2);
+
+    let open_count = |client: &RozoIntentsContractClient| {
+        client
+            .get_intents_by_sender(&sender, &0u32, &MAX_BATCH_SIZE)
+            .iter()
+            .filter(|id| client.get_intent(id).status.is_open())
+            .count()
+    };
+    assert_eq!(open_count(&client), 2);
+
+    // Refunds close intents but never lower the lifetime count
+    set_timestamp(&env, 2001);
+    client.refund(&sender, &first);
+    assert_eq!(open_count(&client), 1);
+    assert_eq!(client.get_sender_intent_count(&sender), 2);
+
+    let third = BytesN::from_array(&env, &[43u8; 32]);
+    let mut params = default_params(&env, &third, &sender, &receiver, &token);
+    params.deadline = 5000;
+    client.create_intent(&sender, &params);
+    client.cancel_all(&sender);
+    assert_eq!(open_count(&client), 0);
+    assert_eq!(client.get_sender_intent_count(&sender), 3);
+}
+
+#[test]
+fn test_fill_and_notify_falls_back_when_primary_adapter_fails() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    // Messenger 1 traps on every send; messenger 2 works
+    let failing = env.register_contract(None, FailingAdapter);
+    client.set_msger_adapter(&owner, &1u32, &failing);
+    let healthy = env.register_contract(None, MockAdapter);
+    client.set_msger_adapter(&owner, &2u32, &healthy);
+
+    // Without a fallback route the failing primary reverts the fill
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let result = client.try_fill_and_notify(
+        &relayer,
+        &intent_data,
+        &990_000_000i128,
+        &repayment,
+        &true,
+        &proof,
+        &Some(1u32),
+    );
+    assert_eq!(result, Err(Ok(Error::AllMessengersFailed)));
+    assert!(!client.is_intent_data_filled(&intent_data));
+
+    client.set_messenger_fallbacks(&owner, &8453u64, &soroban_sdk::vec![&env, 1u32, 2u32]);
+    assert_eq!(client.get_messenger_fallbacks(&8453u64), soroban_sdk::vec![&env, 1u32, 2u32]);
+
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert!(client.is_intent_data_filled(&intent_data));
+
+    // The event names the messenger that actually sent
+    let (_, data) = last_event(&env, &contract, "fill_and_notify_sent");
+    let (_, _, messenger_id): (Address, BytesN<32>, u32) = data.into_val(&env);
+    assert_eq!(messenger_id, 2u32);
+
+    // Once every adapter on the route fails, the fill reverts
+    client.set_msger_adapter(&owner, &2u32, &failing);
+    let other = incoming_intent_data(&env, &BytesN::from_array(&env, &[95u8; 32]), &receiver, &token);
+    let result = client.try_fill_and_notify(&relayer, &other, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    assert_eq!(result, Err(Ok(Error::AllMessengersFailed)));
+}
+
+#[test]
+fn test_guarded_admin_functions_require_arming() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    // setup_env's arming has lapsed
+    assert_eq!(client.try_set_protocol_fee(&owner, &5u32), Err(Ok(Error::NotArmed)));
+    assert_eq!(client.get_protocol_fee(), 3);
+
+    // Armed: callable within the window
+    assert_eq!(client.arm_emergency(&owner), 1000 + ARM_WINDOW_SECS);
+    client.set_protocol_fee(&owner, &5u32);
+    assert_eq!(client.get_protocol_fee(), 5);
+
+    // ...and rejected once it passes
+    set_timestamp(&env, 1001 + ARM_WINDOW_SECS);
+    assert_eq!(client.try_set_protocol_fee(&owner, &7u32), Err(Ok(Error::NotArmed)));
+
+    // Every fee setter is guarded the same way
+    assert_eq!(client.try_set_fee_tiers(&owner, &soroban_sdk::vec![&env]), Err(Ok(Error::NotArmed)));
+    assert_eq!(client.try_set_refund_fee(&owner, &50u32), Err(Ok(Error::NotArmed)));
+    assert_eq!(client.try_set_creation_fee(&owner, &1i128), Err(Ok(Error::NotArmed)));
+    assert_eq!(client.try_set_referral_share(&owner, &100u32), Err(Ok(Error::NotArmed)));
+    assert_eq!(client.try_set_relayer_rebate_bps(&owner, &100u32), Err(Ok(Error::NotArmed)));
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    client.pause(&owner, &String::from_str(&env, "exploit"));
+    let safe = Address::generate(&env);
+    assert_eq!(client.try_emergency_evacuate(&owner, &token, &safe), Err(Ok(Error::NotArmed)));
+    assert_eq!(token_client.balance(&safe), 0);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+
+    // Only the owner may arm
+    let stranger = Address::generate(&env);
+    assert_eq!(client.try_arm_emergency(&stranger), Err(Ok(Error::NotOwner)));
+}
+
+#[test]
+fn test_max_intent_amount() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    assert_eq!(client.get_max_intent_amount(), 0);
+
+    client.set_max_intent_amount(&owner, &1_000_000_000i128);
+    assert_eq!(client.get_max_intent_amount(), 1_000_000_000i128);
+
+    // One unit above the cap is rejected
+    let too_large = BytesN::from_array(&env, &[61u8; 32]);
+    let mut params = default_params(&env, &too_large, &sender, &receiver, &token);
+    params.source_amount = 1_000_000_001i128;
+    assert_eq!(client.try_create_intent(&sender, &params), Err(Err(PolicyError::AmountTooLarge.into())));
+
+    // Exactly at and just below the cap are accepted
+    let at_cap = BytesN::from_array(&env, &[62u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &at_cap, &sender, &receiver, &token));
+    let below = BytesN::from_array(&env, &[63u8; 32]);
+    let mut params = default_params(&env, &below, &sender, &receiver, &token);
+    params.source_amount = 999_999_999i128;
+    client.create_intent(&sender, &params);
+
+    assert_eq!(client.try_set_max_intent_amount(&owner, &-1i128), Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_min_intent_amount_per_token() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    client.set_min_intent_amount(&owner, &token, &1_000_000_000i128);
+    assert_eq!(client.get_min_intent_amount(&token), 1_000_000_000i128);
+
+    let dust = BytesN::from_array(&env, &[64u8; 32]);
+    let mut params = default_params(&env, &dust, &sender, &receiver, &token);
+    params.source_amount = 999_999_999i128;
+    assert_eq!(client.try_create_intent(&sender, &params), Err(Ok(Error::InvalidAmount)));
+
+    let at_min = BytesN::from_array(&env, &[65u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &at_min, &sender, &receiver, &token));
+
+    // Tokens without a minimum are unrestricted
+    let other_token = Address::generate(&env);
+    assert_eq!(client.get_min_intent_amount(&other_token), 0);
+
+    // Clearing the minimum lets the dust intent through
+    client.set_min_intent_amount(&owner, &token, &0i128);
+    client.create_intent(&sender, &params);
+
+    assert_eq!(client.try_set_min_intent_amount(&owner, &token, &-1i128), Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_refund_quote_matches_refund_transfer() {
+    let (env, contract, owner, sender, receiver, _relayer, token, token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+    client.arm_emergency(&owner);
+    client.set_refund_fee(&owner, &50u32);
+    client.set_creation_fee(&owner, &50_000i128);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+
+    // Before the deadline the amount is quoted but the refund wouldn't go through
+    let (refundable, amount) = client.refund_quote(&intent_id);
+    assert!(!refundable);
+    assert_eq!(amount, 1_000_000_000i128 - 5_000_000i128 + 50_000i128);
+
+    set_timestamp(&env, 2001);
+    client.freeze_intent(&owner, &intent_id);
+    assert_eq!(client.refund_quote(&intent_id), (false, amount));
+    client.unfreeze_intent(&owner, &intent_id);
+    assert_eq!(client.refund_quote(&intent_id), (true, amount));
+
+    let before = token_client.balance(&sender);
+    client.refund(&sender, &intent_id);
+    assert_eq!(token_client.balance(&sender) - before, amount);
+
+    assert!(!client.refund_quote(&intent_id).0);
+    assert_eq!(client.try_refund_quote(&BytesN::from_array(&env, &[64u8; 32])), Err(Ok(Error::IntentNotFound)));
+}
+
+#[test]
+fn test_address_to_bytes32_typed_round_trips() {
+    let env = Env::default();
+
+    let account = Address::from_string(&String::from_str(
+        &env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    let (bytes, is_account) = crate::address_to_bytes32_typed(&env, &account);
+    assert!(is_account);
+    assert_eq!(bytes, crate::address_to_bytes32(&env, &account));
+    assert_eq!(bytes32_to_address_typed(&env, &bytes, is_account), account);
+
+    let contract = env.register_contract(None, MockAdapter);
+    let (bytes, is_account) = crate::address_to_bytes32_typed(&env, &contract);
+    assert!(!is_account);
+    assert_eq!(bytes32_to_address_typed(&env, &bytes, is_account), contract);
+
+    // The untyped form can't tell the two apart: the wrong flag yields a different Address
+    assert_ne!(bytes32_to_address_typed(&env, &bytes, true), contract);
+}
+
+#[test]
+fn test_fill_record_keeps_delivery_proof() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+
+    let intent_data = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let tx_hash = BytesN::from_array(&env, &[0xabu8; 32]);
+    client.fill_and_notify(&relayer, &intent_data, &990_000_000i128, &repayment, &true, &tx_hash, &Some(1u32));
+
+    let record = client.get_fill_record(&sha256_fill_hash(&env, &intent_data)).unwrap();
+    assert_eq!(record.delivery_proof, tx_hash);
+    assert_eq!(record.relayer, relayer);
+}
+
+#[test]
+fn test_source_token_allow_list() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let (other_token, _other_client) = create_token_contract(&env, &owner);
+    StellarAssetClient::new(&env, &other_token).mint(&sender, &10_000_000_000i128);
+
+    // Empty allow-list: any token
+    assert!(client.is_token_allowed(&token));
+    assert!(client.is_token_allowed(&other_token));
+
+    client.add_allowed_token(&owner, &token);
+    assert!(client.is_token_allowed(&token));
+    assert!(!client.is_token_allowed(&other_token));
+
+    let rejected = BytesN::from_array(&env, &[71u8; 32]);
+    let result = client.try_create_intent(&sender, &default_params(&env, &rejected, &sender, &receiver, &other_token));
+    assert_eq!(result, Err(Err(PolicyError::TokenNotAllowed.into())));
+    let listed = BytesN::from_array(&env, &[72u8; 32]);
+    client.create_intent(&sender, &default_params(&env, &listed, &sender, &receiver, &token));
+
+    // Removing the only listed token lifts the restriction
+    client.remove_allowed_token(&owner, &token);
+    assert!(client.is_token_allowed(&other_token));
+    client.create_intent(&sender, &default_params(&env, &rejected, &sender, &receiver, &other_token));
+
+    let stranger = Address::generate(&env);
+    assert_eq!(client.try_add_allowed_token(&stranger, &token), Err(Ok(Error::NotOwner)));
+}
+
+#[test]
+fn test_retry_limits_per_fill_and_per_intent() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    client.set_retry_limits(&owner, &3u32, &2u32);
+    assert_eq!(client.get_retry_limits(), (3u32, 2u32));
+
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+    let first = incoming_intent_data(&env, &generate_intent_id(&env), &receiver, &token);
+    client.fill_and_notify(&relayer, &first, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    // Per fill hash: two retries, then capped
+    client.retry_notify(&relayer, &first, &1u32);
+    client.retry_notify(&relayer, &first, &1u32);
+    assert_eq!(client.try_retry_notify(&relayer, &first, &1u32), Err(Ok(Error::RetryLimitReached)));
+    assert_eq!(client.get_fill_retry_count(&sha256_fill_hash(&env, &first)), 2);
+
+    // A second fill hash for the same intent ID shares the per-intent budget
+    let mut second = first.clone();
+    second.destination_amount = 980_000_000i128;
+    client.fill_and_notify(&relayer, &second, &980_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    client.retry_notify(&relayer, &second, &1u32);
+    assert_eq!(client.try_retry_notify(&relayer, &second, &1u32), Err(Ok(Error::RetryLimitReached)));
+    assert_eq!(client.try_retry_notify_all(&relayer, &second), Err(Ok(Error::RetryLimitReached)));
+
+    assert_eq!(client.get_intent_retry_count(&first.intent_id), 3);
+    assert_eq!(client.get_fill_retry_count(&sha256_fill_hash(&env, &second)), 1);
+}
+
+#[test]
+fn test_fee_accrued_event_per_fill() {
+    let (env, contract, owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    let adapter = register_mock_adapter(&env, &contract, &owner);
+    set_timestamp(&env, 1000);
+    client.arm_emergency(&owner);
+    client.set_relayer_rebate_bps(&owner, &5000u32);
+    client.set_creation_fee(&owner, &50_000i128);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    notify_matching_fill(&env, &contract, &adapter, &intent_id, &Address::generate(&env));
+
+    // 3 bps of 1_000_000_000, half rebated, plus the creation fee; indexed by token
+    let (topics, data) = last_event(&env, &contract, "fee_accrued");
+    assert_eq!(Address::try_from_val(&env, &topics.get(1).unwrap()).unwrap(), token);
+    let (fee_amount, fee_intent): (i128, BytesN<32>) = data.into_val(&env);
+    assert_eq!(fee_amount, 200_000i128);
+    assert_eq!(fee_amount, client.get_accum_fees(&token));
+    assert_eq!(fee_intent, intent_id);
+}
+
+#[test]
+fn test_effective_status_reports_expiry_without_storing_it() {
+    let (env, contract, _owner, sender, receiver, _relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    set_timestamp(&env, 1000);
+
+    let intent_id = generate_intent_id(&env);
+    client.create_intent(&sender, &default_params(&env, &intent_id, &sender, &receiver, &token));
+    assert_eq!(client.get_intent_effective_status(&intent_id), IntentStatus::Pending);
+
+    set_timestamp(&env, 2001);
+    assert_eq!(client.get_intent_effective_status(&intent_id), IntentStatus::Expired);
+    assert_eq!(client.get_intent(&intent_id).status, IntentStatus::Pending);
+
+    // Settled statuses pass through unchanged
+    client.refund(&sender, &intent_id);
+    assert_eq!(client.get_intent_effective_status(&intent_id), IntentStatus::Refunded);
+}
+
+#[test]
+fn test_purge_outbound_removes_old_messages() {
+    let (env, contract, owner, _sender, receiver, relayer, token, _token_client) = setup_env();
+
+    let client = RozoIntentsContractClient::new(&env, &contract);
+    register_mock_adapter(&env, &contract, &owner);
+    let repayment = BytesN::from_array(&env, &[7u8; 32]);
+    let proof = BytesN::from_array(&env, &[8u8; 32]);
+
+    set_timestamp(&env, 1000);
+    let old = incoming_intent_data(&env, &BytesN::from_array(&env, &[81u8; 32]), &receiver, &token);
+    client.fill_and_notify(&relayer, &old, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+    set_timestamp(&env, 1500);
+    let recent = incoming_intent_data(&env, &BytesN::from_array(&env, &[82u8; 32]), &receiver, &token);
+    client.fill_and_notify(&relayer, &recent, &990_000_000i128, &repayment, &true, &proof, &Some(1u32));
+
+    // At 1600, a 300 second cutoff keeps only the message stored at 1500
+    set_timestamp(&env, 1600);
+    assert_eq!(client.purge_outbound(&owner, &300u64), 1);
+    let messages = env.as_contract(&contract, || storage::get_outbound_messages(&env));
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages.get(0).unwrap().created_at, 1500);
+
+    assert_eq!(client.purge_outbound(&owner, &300u64), 0);
+    let stranger = Address::generate(&env);
+    assert_eq!(client.try_purge_outbound(&stranger, &0u64), Err(Ok(Error::NotOwner)));
+}
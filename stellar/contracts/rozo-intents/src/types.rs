@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Bytes, BytesN, String};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, String, Symbol};
 
 /// Input parameters for create_intent function
 /// Bundled to avoid hitting the 10-parameter limit
@@ -16,10 +16,15 @@ pub struct CreateIntentParams {
     pub deadline: u64,
     pub refund_address: Address,
     pub relayer: BytesN<32>,
+    pub referrer: Option<Address>,
+    pub preferred_messenger: Option<u32>,
+    pub min_relayer_type: RelayerType,
 }
 
 /// Intent Status
 /// PENDING -> FILLED (success) or FAILED (mismatch) or REFUNDED (after deadline)
+/// PENDING -> EXPIRED (deadline passed, funds still escrowed) -> FILLED or REFUNDED
+/// PENDING or EXPIRED -> UNRECOVERABLE (emergency evacuation while paused)
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum IntentStatus {
@@ -27,6 +32,20 @@ pub enum IntentStatus {
     Filled,    // Completed (via notify)
     Failed,    // Fill verification failed (fillHash mismatch)
     Refunded,  // Sender refunded after deadline
+    Expired,   // Past deadline and refundable, funds still escrowed
+    Unrecoverable, // Escrow evacuated by emergency_evacuate; settled off-chain
+}
+
+impl IntentStatus {
+    /// Unresolved: can still be completed by notify or refunded
+    pub fn is_open(&self) -> bool {
+        matches!(self, IntentStatus::Pending | IntentStatus::Expired)
+    }
+
+    /// Source funds still counted as escrow: open intents and Failed ones awaiting cleanup
+    pub fn holds_escrow(&self) -> bool {
+        self.is_open() || *self == IntentStatus::Failed
+    }
 }
 
 /// Relayer Type
@@ -39,6 +58,35 @@ pub enum RelayerType {
     External,  // Third-party relayer
 }
 
+impl RelayerType {
+    /// Trust level for min_relayer_type checks: Rozo > External > None
+    pub fn rank(&self) -> u32 {
+        match self {
+            RelayerType::None => 0,
+            RelayerType::External => 1,
+            RelayerType::Rozo => 2,
+        }
+    }
+}
+
+/// Fill hash algorithm, fixed at initialization
+/// Keccak256 hashes the abi.encode(IntentData) layout used by the EVM contracts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum HashAlgo {
+    Sha256,     // Packed Soroban layout (original)
+    Keccak256,  // EVM-compatible layout
+}
+
+/// Chain Type
+/// Set by the owner per chain ID; picks the notify payload layout sent to that chain
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ChainType {
+    Evm,      // ABI word-padded payloads (default for unconfigured chains)
+    Stellar,  // Compact payloads decoded by another Stellar deployment
+}
+
 /// Intent Structure (stored on source chain)
 #[derive(Clone, Debug)]
 #[contracttype]
@@ -57,8 +105,50 @@ pub struct Intent {
     pub created_at: u64,               // Timestamp when intent was created (for Rozo fallback)
     pub status: IntentStatus,
     pub relayer: BytesN<32>,           // Assigned relayer (bytes32 for cross-chain compatibility)
+    pub schema_version: u32,           // Storage layout version (see INTENT_SCHEMA_VERSION)
+    pub referrer: Option<Address>,     // Growth program referrer, paid a share of the protocol fee
+    pub preferred_messenger: Option<u32>, // Messenger fill_and_notify uses when the relayer doesn't pick one
+    pub filled_at: u64,                // Timestamp the fill was confirmed (0 = not filled)
+    pub refunded_at: u64,              // Timestamp escrow was refunded (0 = not refunded)
+    pub min_relayer_type: RelayerType, // Least trusted relayer type allowed to fill (None = any; Stellar destinations only)
+    pub creation_fee: i128,            // Flat fee escrowed at creation; kept on fill, returned on refund
 }
 
+/// Upper bound on items any batch function accepts or any list view returns
+/// Keeps every call well inside Soroban's per-transaction resource limits.
+pub const MAX_BATCH_SIZE: u32 = 50;
+
+/// Highest protocol fee (basis points) the owner may configure
+pub const MAX_PROTOCOL_FEE_BPS: u32 = 30;
+
+/// How long arm_emergency keeps the guarded admin functions callable (seconds)
+pub const ARM_WINDOW_SECS: u64 = 300;
+
+/// Width of the deadline buckets open intents are indexed under (seconds)
+/// Changing it orphans existing entries, so it is fixed rather than admin-set.
+pub const EXPIRY_BUCKET_SECS: u64 = 3600;
+
+/// Most deadline buckets one get_expiring_intents call will scan (one week of hours)
+pub const MAX_EXPIRY_BUCKET_SCAN: u64 = 168;
+
+/// Largest decimals value format_amount accepts (i128 has at most 39 digits)
+pub const MAX_DISPLAY_DECIMALS: u32 = 38;
+
+/// Notify payload sizes: EVM-padded words, and the compact Stellar-to-Stellar layout
+/// Both lead with a version tag (a full word for EVM); v2 adds source_chain_id after it.
+pub const NOTIFY_PAYLOAD_LEN: u32 = 224;
+pub const NOTIFY_PAYLOAD_V2_LEN: u32 = 256;
+pub const COMPACT_NOTIFY_PAYLOAD_LEN: u32 = 146;
+pub const COMPACT_NOTIFY_PAYLOAD_V2_LEN: u32 = 154;
+pub const NOTIFY_PAYLOAD_V1: u8 = 1;
+pub const NOTIFY_PAYLOAD_V2: u8 = 2;
+
+/// Current Intent storage layout version
+/// Bump when adding fields to Intent and give them a default in intent_field_defaults.
+/// v0: original layout, v1: schema_version, v2: referrer, v3: preferred_messenger,
+/// v4: filled_at and refunded_at, v5: min_relayer_type, v6: creation_fee
+pub const INTENT_SCHEMA_VERSION: u32 = 6;
+
 /// Intent Data Structure (passed to fillAndNotify)
 /// Full intent data for cross-chain verification
 #[derive(Clone, Debug)]
@@ -80,6 +170,11 @@ pub struct IntentData {
     // Address type flags for Stellar addresses (true = Account/G..., false = Contract/C...)
     // These are needed because bytes32 cannot encode the address type
     pub receiver_is_account: bool,      // Is receiver a Stellar account (G...) or contract (C...)?
+    // Sender's messenger preference; advisory only and not part of the fill hash
+    pub preferred_messenger: Option<u32>,
+    // Least trusted relayer type allowed to fill (None = any); checked by fill_and_notify on
+    // Stellar destinations only. Not part of the fill hash: the EVM IntentData has no such field.
+    pub min_relayer_type: RelayerType,
 }
 
 /// Fill Record Structure
@@ -90,6 +185,103 @@ pub struct FillRecord {
     pub relayer: Address,              // Who filled on destination chain
     pub repayment_address: BytesN<32>, // Relayer's address on source chain for payout
     pub repayment_is_account: bool,    // Is repayment address an account (G...) or contract (C...)?
+    pub overpay: i128,                 // Amount paid to receiver above destination_amount
+    pub delivery_proof: BytesN<32>,    // Relayer's reference to the destination transfer (e.g. tx hash)
+}
+
+/// Intent Claim
+/// A relayer's temporary lock on an open intent (source chain)
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct IntentClaim {
+    pub relayer: Address,
+    pub expires_at: u64,               // Claim lapses if the intent is still open at this time
+}
+
+/// Auction Bid
+/// A relayer's offer to pay the receiver `destination_amount` (source chain)
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AuctionBid {
+    pub relayer: Address,
+    pub destination_amount: i128,
+}
+
+/// Unfillable Report
+/// A relayer's note that an intent can't be filled as created (e.g. wrong receiver flag)
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct UnfillableReport {
+    pub relayer: Address,
+    pub reason: Symbol,
+    pub reported_at: u64,
+}
+
+/// Pending Settlement Structure
+/// Relayer payout recorded by notify, released after the settlement delay
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct PendingSettlement {
+    pub intent_id: BytesN<32>,
+    pub token: Address,
+    pub payout_address: Address,
+    pub amount: i128,
+    pub reserved: i128,                // Part of amount reserved out of accumulated fees (rebate + reimbursement)
+    pub release_at: u64,               // Earliest timestamp release_settlement may pay out
+}
+
+/// Fee Tier
+/// Fee applied once a token's cumulative filled volume reaches min_volume
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct FeeTier {
+    pub min_volume: i128,
+    pub fee_bps: u32,
+}
+
+/// Relayer Volume Cap
+/// Limits how much a relayer may settle within a rolling window
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct VolumeCap {
+    pub cap: i128,
+    pub window: u64,                   // Window length in seconds
+}
+
+/// Relayer Volume Window
+/// Settled volume since window_start; resets once the window elapses
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct VolumeWindow {
+    pub window_start: u64,
+    pub volume: i128,
+}
+
+/// Circuit Breaker Config
+/// Auto-pause once max_fills fills happen within window seconds (max_fills 0 = disabled)
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct CircuitBreaker {
+    pub max_fills: u32,
+    pub window: u64,
+}
+
+/// Circuit Breaker Window
+/// Fills seen since window_start; resets once the window elapses
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct FillRateWindow {
+    pub window_start: u64,
+    pub count: u32,
+}
+
+/// Relayer Rate Limit
+/// Each relayer may fill at most max_fills times per window seconds (max_fills 0 = unlimited)
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct RelayerRateLimit {
+    pub max_fills: u32,
+    pub window: u64,
 }
 
 /// Outbound message (for testing/debugging)
@@ -99,4 +291,5 @@ pub struct OutboundMessage {
     pub destination_chain: String,
     pub destination_address: String,
     pub payload: Bytes,
+    pub created_at: u64,               // Ledger timestamp the message was stored (for purge_outbound)
 }
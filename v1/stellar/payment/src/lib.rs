@@ -1,11 +1,17 @@
 #![no_std]
 
-use soroban_sdk::{contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, String, Symbol};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, String, Symbol, Vec,
+};
 
 // Storage keys
 const DEST: Symbol = symbol_short!("dest");
 const USDC: Symbol = symbol_short!("usdc");
 const INIT: Symbol = symbol_short!("init");
+const SPLITS: Symbol = symbol_short!("splits");
+
+// Split shares are basis points of each payment
+const TOTAL_BPS: u32 = 10000;
 
 // Mainnet USDC contract
 const USDC_CONTRACT: &str = "CCW67TSZV3SSS2HXMBQ5JFGCKJNXKZM7UQUWUZPUTHXSTZLEO7SJMI75";
@@ -19,6 +25,8 @@ pub enum Error {
     NotInitialized = 2,
     ZeroAmount = 3,
     InsufficientBalance = 4,
+    InvalidSplits = 5,
+    SplitsNotConfigured = 6,
 }
 
 // Event topics
@@ -49,8 +57,9 @@ pub struct Payment;
 #[contractimpl]
 impl Payment {
     /// Initialize the contract with a destination address
+    /// `token` overrides the USDC contract (e.g. testnet); None uses mainnet USDC
     /// Can only be called once
-    pub fn init(env: Env, destination: Address) -> Result<(), Error> {
+    pub fn init(env: Env, destination: Address, token: Option<Address>) -> Result<(), Error> {
         // Check if already initialized
         if env.storage().instance().has(&INIT) {
             return Err(Error::AlreadyInitialized);
@@ -60,8 +69,10 @@ impl Payment {
         env.storage().instance().set(&DEST, &destination);
 
         // Store USDC address
-        let usdc_str = String::from_str(&env, USDC_CONTRACT);
-        let usdc_addr = Address::from_string(&usdc_str);
+        let usdc_addr = match token {
+            Some(token) => token,
+            None => Address::from_string(&String::from_str(&env, USDC_CONTRACT)),
+        };
         env.storage().instance().set(&USDC, &usdc_addr);
 
         // Mark as initialized
@@ -109,6 +120,94 @@ impl Payment {
         Ok(())
     }
 
+    /// Configure recipients that share each pay_split payment
+    /// Shares are basis points and must sum to 10000. Requires the destination's
+    /// authorization and can only be set once.
+    pub fn init_split(env: Env, destinations: Vec<(Address, u32)>) -> Result<(), Error> {
+        // Check initialization
+        if !env.storage().instance().has(&INIT) {
+            return Err(Error::NotInitialized);
+        }
+        if env.storage().instance().has(&SPLITS) {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        // The configured destination decides how its payments are shared
+        let destination: Address = env.storage().instance().get(&DEST).unwrap();
+        destination.require_auth();
+
+        if destinations.is_empty() {
+            return Err(Error::InvalidSplits);
+        }
+        let mut total_bps: u32 = 0;
+        for (_, bps) in destinations.iter() {
+            if bps == 0 || bps > TOTAL_BPS - total_bps {
+                return Err(Error::InvalidSplits);
+            }
+            total_bps += bps;
+        }
+        if total_bps != TOTAL_BPS {
+            return Err(Error::InvalidSplits);
+        }
+
+        env.storage().instance().set(&SPLITS, &destinations);
+
+        Ok(())
+    }
+
+    /// Pay USDC split across the configured recipients
+    /// Each recipient gets amount * bps / 10000; the rounding remainder goes to the first recipient.
+    /// Emits one payment event per recipient.
+    pub fn pay_split(env: Env, from: Address, amount: i128, memo: String) -> Result<(), Error> {
+        // Check initialization
+        if !env.storage().instance().has(&INIT) {
+            return Err(Error::NotInitialized);
+        }
+        let splits: Vec<(Address, u32)> = match env.storage().instance().get(&SPLITS) {
+            Some(splits) => splits,
+            None => return Err(Error::SplitsNotConfigured),
+        };
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        // Require authorization from the payer
+        from.require_auth();
+
+        let usdc_address: Address = env.storage().instance().get(&USDC).unwrap();
+        let token_client = token::Client::new(&env, &usdc_address);
+
+        // Work out each share up front so the remainder can go to the first recipient
+        let mut shares: Vec<i128> = Vec::new(&env);
+        let mut allocated: i128 = 0;
+        for (_, bps) in splits.iter() {
+            let share = amount * bps as i128 / TOTAL_BPS as i128;
+            shares.push_back(share);
+            allocated += share;
+        }
+        shares.set(0, shares.get(0).unwrap() + (amount - allocated));
+
+        for (i, (destination, _)) in splits.iter().enumerate() {
+            let share = shares.get(i as u32).unwrap();
+            token_client.transfer(&from, &destination, &share);
+
+            // Emit payment event
+            env.events().publish(
+                (PAYMENT, from.clone()),
+                PaymentEvent {
+                    from: from.clone(),
+                    destination,
+                    amount: share,
+                    memo: memo.clone(),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
     /// Flush any tokens from this contract to the destination
     /// This is useful for recovering tokens accidentally sent to the contract
     pub fn flush(env: Env, token_contract: Address, amount: i128) -> Result<(), Error> {
@@ -147,6 +246,11 @@ impl Payment {
         env.storage().instance().get(&DEST).unwrap()
     }
 
+    /// Get the configured split recipients (empty if not configured)
+    pub fn get_splits(env: Env) -> Vec<(Address, u32)> {
+        env.storage().instance().get(&SPLITS).unwrap_or(Vec::new(&env))
+    }
+
     /// Get the USDC token address
     pub fn get_usdc(env: Env) -> Address {
         env.storage().instance().get(&USDC).unwrap()